@@ -17,65 +17,105 @@ DROP TABLE IF EXISTS jobs;
 
 CREATE TABLE IF NOT EXISTS jobs (
     id TEXT PRIMARY KEY,
+    ats_job_id TEXT,
     title TEXT NOT NULL,
     description TEXT,
+    description_length INTEGER,
+    description_quality_score REAL,
+    text_description TEXT,
+    description_hash TEXT,
     company TEXT NOT NULL,
     slug TEXT NOT NULL,
     ats TEXT NOT NULL,
     url TEXT NOT NULL,
+    apply_url TEXT,
     company_url TEXT,
+    company_industry TEXT,
+    company_stage TEXT,
+    logo_url TEXT,
     location TEXT,
     city TEXT,
     region TEXT,
     country TEXT,
     country_code TEXT,
+    metro_area TEXT,
+    preferred_timezone TEXT,
     posted TEXT,
-    created_at DATETIME DEFAULT CURRENT_TIMESTAMP
+    seniority_level TEXT,
+    employment_type TEXT,
+    normalized_department TEXT,
+    last_seen TEXT,
+    created_at DATETIME DEFAULT CURRENT_TIMESTAMP,
+    UNIQUE (slug, ats_job_id)
 );
 
 CREATE TABLE IF NOT EXISTS job_departments (
     job_id TEXT NOT NULL,
     name TEXT NOT NULL,
-    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE ON UPDATE CASCADE,
     PRIMARY KEY (job_id, name)
 );
 
 CREATE TABLE IF NOT EXISTS job_offices (
     job_id TEXT NOT NULL,
     name TEXT NOT NULL,
-    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE ON UPDATE CASCADE,
     PRIMARY KEY (job_id, name)
 );
 
 CREATE TABLE IF NOT EXISTS job_tags (
     job_id TEXT NOT NULL,
     name TEXT NOT NULL,
-    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE ON UPDATE CASCADE,
     PRIMARY KEY (job_id, name)
 );
 
 CREATE TABLE IF NOT EXISTS job_degree_levels (
     job_id TEXT NOT NULL,
     name TEXT NOT NULL,
-    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE ON UPDATE CASCADE,
     PRIMARY KEY (job_id, name)
 );
 
 CREATE TABLE IF NOT EXISTS job_subject_areas (
     job_id TEXT NOT NULL,
     name TEXT NOT NULL,
-    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE ON UPDATE CASCADE,
     PRIMARY KEY (job_id, name)
 );
 
+CREATE TABLE IF NOT EXISTS job_benefits (
+    job_id TEXT NOT NULL,
+    name TEXT NOT NULL,
+    FOREIGN KEY (job_id) REFERENCES jobs(id) ON DELETE CASCADE ON UPDATE CASCADE,
+    PRIMARY KEY (job_id, name)
+);
+
+-- Previous values of tracked job fields, kept after the job itself is deleted
+CREATE TABLE IF NOT EXISTS job_history (
+    job_id TEXT NOT NULL,
+    field_name TEXT NOT NULL,
+    old_value TEXT,
+    new_value TEXT,
+    changed_at TEXT NOT NULL
+);
+
 CREATE INDEX IF NOT EXISTS idx_jobs_company ON jobs(company);
 CREATE INDEX IF NOT EXISTS idx_jobs_posted ON jobs(posted);
+CREATE INDEX IF NOT EXISTS idx_jobs_last_seen ON jobs(last_seen);
 CREATE INDEX IF NOT EXISTS idx_jobs_title ON jobs(title);
 CREATE INDEX IF NOT EXISTS idx_jobs_created_at_desc ON jobs(created_at DESC);
+CREATE INDEX IF NOT EXISTS idx_jobs_seniority_level ON jobs(seniority_level);
+CREATE INDEX IF NOT EXISTS idx_jobs_employment_type ON jobs(employment_type);
+CREATE INDEX IF NOT EXISTS idx_jobs_normalized_department ON jobs(normalized_department);
+CREATE INDEX IF NOT EXISTS idx_jobs_company_industry ON jobs(company_industry);
+CREATE INDEX IF NOT EXISTS idx_jobs_company_stage ON jobs(company_stage);
 CREATE INDEX IF NOT EXISTS idx_jobs_city ON jobs(city);
 CREATE INDEX IF NOT EXISTS idx_jobs_region ON jobs(region);
 CREATE INDEX IF NOT EXISTS idx_jobs_country ON jobs(country);
 CREATE INDEX IF NOT EXISTS idx_jobs_country_code ON jobs(country_code);
+CREATE INDEX IF NOT EXISTS idx_jobs_metro_area ON jobs(metro_area);
+CREATE INDEX IF NOT EXISTS idx_jobs_preferred_timezone ON jobs(preferred_timezone);
 
 CREATE INDEX IF NOT EXISTS idx_countries_name ON countries(name);
 
@@ -96,3 +136,8 @@ CREATE INDEX IF NOT EXISTS idx_job_degree_levels_name ON job_degree_levels(name)
 
 CREATE INDEX IF NOT EXISTS idx_job_subject_areas_job_id ON job_subject_areas(job_id);
 CREATE INDEX IF NOT EXISTS idx_job_subject_areas_name ON job_subject_areas(name);
+
+CREATE INDEX IF NOT EXISTS idx_job_benefits_job_id ON job_benefits(job_id);
+CREATE INDEX IF NOT EXISTS idx_job_benefits_name ON job_benefits(name);
+
+CREATE INDEX IF NOT EXISTS idx_job_history_job_id ON job_history(job_id, changed_at);
@@ -0,0 +1,79 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use std::fmt::Write as _;
+use std::fs;
+use std::path::Path;
+use zapply::location::LocationEngine;
+
+const CITIES: &str = "cities15000.txt";
+const ADMIN1: &str = "admin1CodesASCII.txt";
+const COUNTRIES: &str = "countryInfo.txt";
+const MOCK_CITY_COUNT: usize = 10_000;
+
+const INPUTS: &[(&str, &str)] = &[
+    ("city_country", "London, UK"),
+    ("ambiguous_city", "Paris, TX"),
+    ("remote", "Remote"),
+    ("malformed", "Somewhere over the rainbow"),
+    ("multi_part", "New York, New York, United States"),
+];
+
+/// One `cities15000.txt` row; only the columns `parse_city_line` reads are filled in.
+fn city_row(out: &mut String, id: usize, name: &str, country: &str, admin1: &str, population: u32) {
+    writeln!(out, "{id}\t{name}\t{name}\t\t0\t0\tP\tPPL\t{country}\t\t{admin1}\t\t\t\t{population}").unwrap();
+}
+
+/// Writes a GeoNames-shaped dataset of real anchor cities plus synthetic ones.
+fn write_mock_dataset(dir: &Path) {
+    fs::write(dir.join(COUNTRIES), "#ISO\tISO3\tISO-Numeric\tfips\tCountry\n\
+        US\tUSA\t840\tUS\tUnited States\n\
+        GB\tGBR\t826\tUK\tUnited Kingdom\n\
+        FR\tFRA\t250\tFR\tFrance\n\
+        DE\tDEU\t276\tGM\tGermany\n").unwrap();
+    fs::write(dir.join(ADMIN1), "US.TX\tTexas\tTexas\t4736286\n\
+        US.NY\tNew York\tNew York\t5128638\n\
+        GB.ENG\tEngland\tEngland\t6269131\n\
+        FR.11\tÎle-de-France\tIle-de-France\t3012874\n\
+        DE.16\tBerlin\tBerlin\t2950157\n").unwrap();
+
+    let mut cities = String::new();
+    city_row(&mut cities, 1, "London", "GB", "ENG", 8_961_989);
+    city_row(&mut cities, 2, "Paris", "FR", "11", 2_138_551);
+    city_row(&mut cities, 3, "Paris", "US", "TX", 24_782);
+    city_row(&mut cities, 4, "New York", "US", "NY", 8_804_190);
+    city_row(&mut cities, 5, "Berlin", "DE", "16", 3_426_354);
+    let regions = [("US", "TX"), ("US", "NY"), ("GB", "ENG"), ("FR", "11"), ("DE", "16")];
+    for i in 0..MOCK_CITY_COUNT {
+        let (country, admin1) = regions[i % regions.len()];
+        city_row(&mut cities, 100 + i, &format!("Synthville {}", i), country, admin1, 15_000 + (i as u32 * 37) % 500_000);
+    }
+    fs::write(dir.join(CITIES), cities).unwrap();
+}
+
+/// Real GeoNames data when it's in the crate directory, otherwise the mock dataset.
+fn load_engine() -> LocationEngine {
+    let mut engine = LocationEngine::new();
+    if [CITIES, ADMIN1, COUNTRIES].iter().all(|f| Path::new(f).exists()) {
+        engine.load_geonames(CITIES, ADMIN1, COUNTRIES).unwrap();
+        return engine;
+    }
+
+    let dir = std::env::temp_dir().join(format!("zapply_location_bench_{}", std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    write_mock_dataset(&dir);
+    let path = |f: &str| dir.join(f).to_str().unwrap().to_string();
+    engine.load_geonames(&path(CITIES), &path(ADMIN1), &path(COUNTRIES)).unwrap();
+    fs::remove_dir_all(&dir).ok();
+    engine
+}
+
+fn bench_resolve(c: &mut Criterion) {
+    let engine = load_engine();
+    let mut group = c.benchmark_group("resolve");
+    for (name, input) in INPUTS {
+        group.bench_function(*name, |b| b.iter(|| engine.resolve(black_box(input))));
+    }
+    group.finish();
+}
+
+criterion_group!(benches, bench_resolve);
+criterion_main!(benches);
@@ -0,0 +1,103 @@
+use criterion::{BenchmarkId, Criterion, black_box, criterion_group, criterion_main};
+use regex::Regex;
+use zapply::tag::TagEngine;
+
+const TITLE: &str = "Software Engineering Intern (Rust/Python) - Remote";
+
+const DESCRIPTION_SENTENCES: &[&str] = &[
+    "You will join our platform team building backend services in Rust and Python.",
+    "We work with PostgreSQL, Kubernetes and AWS, and deploy several times a day.",
+    "This is a paid internship with a salary of $45 per hour and a relocation stipend.",
+    "The role is hybrid, with two days a week in our London office.",
+    "We welcome applicants from all backgrounds and are proud to be an equal opportunity employer.",
+    "Candidates should be pursuing a Bachelor's or Master's degree in Computer Science or a related field.",
+    "Experience with React, TypeScript or machine learning is a plus but not required.",
+    "Unfortunately we are unable to offer visa sponsorship for this position.",
+    "You will pair with senior engineers, write design docs and ship features to production.",
+    "Our benefits include health insurance, a learning budget and flexible working hours.",
+];
+
+/// Roughly 1000 words of plausible job-description text.
+fn synthetic_description() -> String {
+    let mut text = String::new();
+    while text.split_whitespace().count() < 1000 {
+        for sentence in DESCRIPTION_SENTENCES {
+            text.push_str(sentence);
+            text.push(' ');
+        }
+    }
+    text
+}
+
+/// Every rule's keyword pattern as its own regex, for comparison with the `RegexSet` pre-filter.
+fn sequential_patterns(engine: &TagEngine) -> Vec<Regex> {
+    let rules: toml::Value = toml::from_str(&engine.to_toml().unwrap()).unwrap();
+    rules["rules"].as_array().unwrap().iter()
+        .map(|r| Regex::new(r["pattern"].as_str().unwrap()).unwrap())
+        .collect()
+}
+
+fn bench_detect_tags(c: &mut Criterion) {
+    // Uncached, or every iteration after the first would be a cache hit
+    let engine = TagEngine::new().with_cache_capacity(0);
+    let description = synthetic_description();
+
+    let mut group = c.benchmark_group("detect_tags");
+    group.bench_function("description_1000_words", |b| b.iter(|| engine.detect_tags(black_box(&description))));
+    group.bench_function("title", |b| b.iter(|| engine.detect_tags(black_box(TITLE))));
+    group.bench_function("empty", |b| b.iter(|| engine.detect_tags(black_box(""))));
+    group.finish();
+
+    // Keyword patterns only (no context checks), so this is a lower bound for a sequential scan
+    let patterns = sequential_patterns(&engine);
+    let mut group = c.benchmark_group("sequential_scan");
+    group.bench_function("description_1000_words", |b| {
+        b.iter(|| patterns.iter().filter(|r| r.is_match(black_box(&description))).count())
+    });
+    group.bench_function("title", |b| b.iter(|| patterns.iter().filter(|r| r.is_match(black_box(TITLE))).count()));
+    group.finish();
+}
+
+/// Sequential `detect_tags` against the rayon-backed `detect_tags_batch`.
+fn bench_detect_tags_batch(c: &mut Criterion) {
+    let engine = TagEngine::new().with_cache_capacity(0);
+    let mut group = c.benchmark_group("detect_tags_batch");
+    for size in [100, 1000, 10000] {
+        let owned: Vec<String> = (0..size)
+            .map(|i| format!("{} {}", TITLE, DESCRIPTION_SENTENCES[i % DESCRIPTION_SENTENCES.len()]))
+            .collect();
+        let texts: Vec<&str> = owned.iter().map(String::as_str).collect();
+        group.bench_with_input(BenchmarkId::new("sequential", size), &texts, |b, texts| {
+            b.iter(|| texts.iter().map(|t| engine.detect_tags(black_box(t))).collect::<Vec<_>>())
+        });
+        group.bench_with_input(BenchmarkId::new("parallel", size), &texts, |b, texts| {
+            b.iter(|| engine.detect_tags_batch(black_box(texts)))
+        });
+    }
+    group.finish();
+}
+
+/// 100 jobs sharing one description, as from a company with a boilerplate
+/// template, with and without the result cache.
+fn bench_detect_tags_cached(c: &mut Criterion) {
+    let description = synthetic_description();
+    let mut group = c.benchmark_group("detect_tags_repeated");
+    for (name, capacity) in [("uncached", 0), ("cached", 1000)] {
+        let engine = TagEngine::new().with_cache_capacity(capacity);
+        group.bench_function(name, |b| {
+            b.iter(|| (0..100).map(|_| engine.detect_tags(black_box(&description)).len()).sum::<usize>())
+        });
+        let (hits, misses) = engine.cache_stats();
+        if hits + misses > 0 {
+            println!("{}: {:.1}% hit rate over {} lookups", name, 100.0 * hits as f64 / (hits + misses) as f64, hits + misses);
+        }
+    }
+    group.finish();
+}
+
+fn bench_engine_new(c: &mut Criterion) {
+    c.bench_function("TagEngine::new", |b| b.iter(TagEngine::new));
+}
+
+criterion_group!(benches, bench_detect_tags, bench_detect_tags_batch, bench_detect_tags_cached, bench_engine_new);
+criterion_main!(benches);
@@ -0,0 +1,169 @@
+use crate::models::{AtsType, CompanyEntry};
+
+/// Hosts each ATS serves boards and APIs from (matched on the host or any subdomain).
+const ATS_HOSTS: &[(&str, AtsType)] = &[
+    ("greenhouse.io", AtsType::Greenhouse),
+    ("lever.co", AtsType::Lever),
+    ("smartrecruiters.com", AtsType::SmartRecruiters),
+    ("ashbyhq.com", AtsType::Ashby),
+    ("workable.com", AtsType::Workable),
+    ("recruitee.com", AtsType::Recruitee),
+    ("breezy.hr", AtsType::Breezy),
+];
+
+/// Guesses the ATS for a company listed without a `type`.
+pub struct AtsAutoDetector;
+
+impl AtsAutoDetector {
+    /// Sets the company's `ats_type` and points `api_url` at the detected
+    /// ATS's board API, when the board token can be read off the careers URL.
+    pub async fn detect_company(company: &mut CompanyEntry, client: &reqwest::Client) {
+        let (ats, url) = Self::detect(&company.api_url, client).await;
+        company.ats_type = ats;
+        if let Some(api_url) = Self::api_url(ats, &url) {
+            company.api_url = api_url;
+        }
+    }
+
+    /// Checks the URL first; otherwise follows redirects with a HEAD request
+    /// and checks the final URL and `Server` header. Returns the ATS with the
+    /// URL it was recognized from, the redirect target if there was one.
+    pub async fn detect(careers_url: &str, client: &reqwest::Client) -> (AtsType, String) {
+        let from_url = Self::from_url(careers_url);
+        if from_url != AtsType::Unknown {
+            return (from_url, careers_url.to_string());
+        }
+
+        let Ok(resp) = client.head(careers_url).send().await else {
+            return (AtsType::Unknown, careers_url.to_string());
+        };
+        let final_url = resp.url().to_string();
+        let from_redirect = Self::from_url(&final_url);
+        if from_redirect != AtsType::Unknown {
+            return (from_redirect, final_url);
+        }
+        let from_header = resp.headers()
+            .get(reqwest::header::SERVER)
+            .and_then(|v| v.to_str().ok())
+            .map(Self::from_server_header)
+            .unwrap_or(AtsType::Unknown);
+        (from_header, final_url)
+    }
+
+    /// The board API `board_url` fetches for a careers page on `ats`'s own
+    /// host, e.g. "https://jobs.lever.co/acme" -> "https://api.lever.co/v0/postings/acme?mode=json".
+    /// `None` for URLs that already are the API or that aren't on the ATS's host.
+    pub fn api_url(ats: AtsType, careers_url: &str) -> Option<String> {
+        if ats == AtsType::Unknown || Self::from_url(careers_url) != ats {
+            return None;
+        }
+        let url = reqwest::Url::parse(careers_url).ok()?;
+        let host = url.host_str()?.to_lowercase();
+        let path_token = url.path_segments()
+            .and_then(|mut segments| segments.find(|s| !s.is_empty()))
+            .map(str::to_string);
+        // Recruitee and Breezy boards live on a company subdomain
+        let subdomain = host.split('.').next().filter(|s| !matches!(*s, "www" | "app" | "api")).map(str::to_string);
+        let board = |api_host: &str| path_token.clone().filter(|_| host != api_host);
+
+        match ats {
+            AtsType::Greenhouse => board("boards-api.greenhouse.io")
+                .map(|t| format!("https://boards-api.greenhouse.io/v1/boards/{}/jobs", t)),
+            AtsType::Lever => board("api.lever.co")
+                .map(|t| format!("https://api.lever.co/v0/postings/{}?mode=json", t)),
+            AtsType::SmartRecruiters => board("api.smartrecruiters.com")
+                .map(|t| format!("https://api.smartrecruiters.com/v1/companies/{}/postings", t)),
+            AtsType::Ashby => board("api.ashbyhq.com")
+                .map(|t| format!("https://api.ashbyhq.com/posting-api/job-board/{}?includeCompensation=true", t)),
+            AtsType::Workable => path_token.filter(|t| t != "api")
+                .map(|t| format!("https://apply.workable.com/api/v1/widget/accounts/{}", t)),
+            AtsType::Recruitee => subdomain.filter(|_| path_token.as_deref() != Some("api"))
+                .map(|s| format!("https://{}.recruitee.com/api/offers", s)),
+            AtsType::Breezy => subdomain.filter(|_| path_token.as_deref() != Some("json"))
+                .map(|s| format!("https://{}.breezy.hr/json", s)),
+            _ => None,
+        }
+    }
+
+    pub fn from_url(url: &str) -> AtsType {
+        let Some(host) = reqwest::Url::parse(url).ok().and_then(|u| u.host_str().map(str::to_lowercase)) else {
+            return AtsType::Unknown;
+        };
+        ATS_HOSTS.iter()
+            .find(|(domain, _)| host == *domain || host.ends_with(&format!(".{}", domain)))
+            .map(|(_, ats)| *ats)
+            .unwrap_or(AtsType::Unknown)
+    }
+
+    pub fn from_server_header(server: &str) -> AtsType {
+        let server = server.to_lowercase();
+        ATS_HOSTS.iter()
+            .find(|(domain, _)| server.contains(domain.split('.').next().unwrap_or(domain)))
+            .map(|(_, ats)| *ats)
+            .unwrap_or(AtsType::Unknown)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_detect_from_url() {
+        assert_eq!(AtsAutoDetector::from_url("https://boards-api.greenhouse.io/v1/boards/stripe/jobs"), AtsType::Greenhouse);
+        assert_eq!(AtsAutoDetector::from_url("https://job-boards.greenhouse.io/canva"), AtsType::Greenhouse);
+        assert_eq!(AtsAutoDetector::from_url("https://jobs.lever.co/spotify"), AtsType::Lever);
+        assert_eq!(AtsAutoDetector::from_url("https://api.smartrecruiters.com/v1/companies/visa/postings"), AtsType::SmartRecruiters);
+        assert_eq!(AtsAutoDetector::from_url("https://jobs.ashbyhq.com/notion"), AtsType::Ashby);
+        assert_eq!(AtsAutoDetector::from_url("https://apply.workable.com/huggingface/"), AtsType::Workable);
+        assert_eq!(AtsAutoDetector::from_url("https://bunq.recruitee.com/api/offers"), AtsType::Recruitee);
+        assert_eq!(AtsAutoDetector::from_url("https://acme.breezy.hr/json"), AtsType::Breezy);
+    }
+
+    #[test]
+    fn test_detect_unknown_url() {
+        assert_eq!(AtsAutoDetector::from_url("https://careers.example.com/jobs"), AtsType::Unknown);
+        // Lookalike hosts must not match
+        assert_eq!(AtsAutoDetector::from_url("https://notlever.co/jobs"), AtsType::Unknown);
+        assert_eq!(AtsAutoDetector::from_url("https://example.com/?ref=greenhouse.io"), AtsType::Unknown);
+        assert_eq!(AtsAutoDetector::from_url("not a url"), AtsType::Unknown);
+    }
+
+    #[test]
+    fn test_api_url_for_careers_pages() {
+        let api_url = |ats, url| AtsAutoDetector::api_url(ats, url);
+        assert_eq!(api_url(AtsType::Greenhouse, "https://job-boards.greenhouse.io/canva").as_deref(), Some("https://boards-api.greenhouse.io/v1/boards/canva/jobs"));
+        assert_eq!(api_url(AtsType::Lever, "https://jobs.lever.co/spotify/").as_deref(), Some("https://api.lever.co/v0/postings/spotify?mode=json"));
+        assert_eq!(api_url(AtsType::SmartRecruiters, "https://jobs.smartrecruiters.com/Visa").as_deref(), Some("https://api.smartrecruiters.com/v1/companies/Visa/postings"));
+        assert_eq!(api_url(AtsType::Ashby, "https://jobs.ashbyhq.com/notion").as_deref(), Some("https://api.ashbyhq.com/posting-api/job-board/notion?includeCompensation=true"));
+        assert_eq!(api_url(AtsType::Workable, "https://apply.workable.com/huggingface/").as_deref(), Some("https://apply.workable.com/api/v1/widget/accounts/huggingface"));
+        assert_eq!(api_url(AtsType::Recruitee, "https://bunq.recruitee.com/").as_deref(), Some("https://bunq.recruitee.com/api/offers"));
+        assert_eq!(api_url(AtsType::Breezy, "https://acme.breezy.hr").as_deref(), Some("https://acme.breezy.hr/json"));
+
+        // Already an API, or not on the ATS's host (detected from a header)
+        assert_eq!(api_url(AtsType::Greenhouse, "https://boards-api.greenhouse.io/v1/boards/stripe/jobs"), None);
+        assert_eq!(api_url(AtsType::Recruitee, "https://bunq.recruitee.com/api/offers"), None);
+        assert_eq!(api_url(AtsType::Breezy, "https://acme.breezy.hr/json"), None);
+        assert_eq!(api_url(AtsType::Greenhouse, "https://careers.example.com/jobs"), None);
+        assert_eq!(api_url(AtsType::Lever, "https://jobs.lever.co/"), None);
+    }
+
+    #[tokio::test]
+    async fn test_detect_company_rewrites_api_url() {
+        let mut company = CompanyEntry { api_url: "https://jobs.lever.co/spotify".to_string(), ..Default::default() };
+        AtsAutoDetector::detect_company(&mut company, &reqwest::Client::new()).await;
+        assert_eq!(company.ats_type, AtsType::Lever);
+        assert_eq!(company.api_url, "https://api.lever.co/v0/postings/spotify?mode=json");
+
+        let mut company = CompanyEntry { api_url: "https://boards-api.greenhouse.io/v1/boards/stripe/jobs".to_string(), ..Default::default() };
+        AtsAutoDetector::detect_company(&mut company, &reqwest::Client::new()).await;
+        assert_eq!(company.ats_type, AtsType::Greenhouse);
+        assert_eq!(company.api_url, "https://boards-api.greenhouse.io/v1/boards/stripe/jobs");
+    }
+
+    #[test]
+    fn test_detect_from_server_header() {
+        assert_eq!(AtsAutoDetector::from_server_header("Greenhouse"), AtsType::Greenhouse);
+        assert_eq!(AtsAutoDetector::from_server_header("cloudflare"), AtsType::Unknown);
+    }
+}
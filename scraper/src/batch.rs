@@ -0,0 +1,503 @@
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::time::{Duration, Instant};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use tokio::sync::mpsc;
+use tracing::{debug, warn, info_span, Instrument};
+
+use crate::JobDb;
+use crate::checkpoint::Checkpoint;
+use crate::dedup::Deduplicator;
+use crate::error::ScraperError;
+use crate::last_run::LastRuns;
+use crate::metrics::Metrics;
+use crate::models::Job;
+use crate::utils::job_fingerprint;
+use crate::webhook::{WebhookConfig, fire_webhook};
+
+const INITIAL_BATCH_SIZE: usize = 100;
+const MIN_BATCH_SIZE: usize = 25;
+const MAX_BATCH_SIZE: usize = 500;
+const SLOW_BATCH: Duration = Duration::from_secs(2);
+const FAST_BATCH: Duration = Duration::from_millis(500);
+/// Number of recent inserts averaged before resizing.
+const LATENCY_WINDOW: usize = 3;
+/// Jobs company tasks can queue before they wait on the batch consumer.
+pub const CHANNEL_CAPACITY: usize = 2 * INITIAL_BATCH_SIZE;
+
+/// Batch size that follows DB write latency: halves when inserts are slow,
+/// doubles when they are fast.
+pub struct BatchSizer {
+    size: AtomicUsize,
+    latencies: Mutex<VecDeque<Duration>>,
+}
+
+impl BatchSizer {
+    pub fn new() -> Self {
+        Self {
+            size: AtomicUsize::new(INITIAL_BATCH_SIZE),
+            latencies: Mutex::new(VecDeque::with_capacity(LATENCY_WINDOW)),
+        }
+    }
+
+    pub fn size(&self) -> usize {
+        self.size.load(Ordering::SeqCst)
+    }
+
+    pub fn record(&self, elapsed: Duration) {
+        let mut latencies = self.latencies.lock().unwrap();
+        if latencies.len() == LATENCY_WINDOW {
+            latencies.pop_front();
+        }
+        latencies.push_back(elapsed);
+        let average = latencies.iter().sum::<Duration>() / latencies.len() as u32;
+
+        let current = self.size();
+        let next = if average > SLOW_BATCH {
+            (current / 2).max(MIN_BATCH_SIZE)
+        } else if average < FAST_BATCH {
+            (current * 2).min(MAX_BATCH_SIZE)
+        } else {
+            current
+        };
+
+        if next != current {
+            debug!("Batch size {} -> {} (avg insert {:?})", current, next, average);
+            self.size.store(next, Ordering::SeqCst);
+            // Latencies at the old size say little about the new one
+            latencies.clear();
+        }
+    }
+}
+
+/// Cap on total jobs inserted in one run (`--limit-jobs=N`).
+pub struct JobLimit {
+    limit: usize,
+    reserved: AtomicUsize,
+    reached: AtomicBool,
+}
+
+impl JobLimit {
+    pub fn new(limit: usize) -> Self {
+        Self {
+            limit,
+            reserved: AtomicUsize::new(0),
+            reached: AtomicBool::new(false),
+        }
+    }
+
+    /// Claims up to `wanted` insert slots and returns how many were granted.
+    /// Warns once when the limit is hit.
+    pub fn reserve(&self, wanted: usize) -> usize {
+        let previous = self.reserved
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |r| Some((r + wanted).min(self.limit)))
+            .unwrap_or(self.limit);
+        let granted = (previous + wanted).min(self.limit) - previous;
+        if previous + wanted >= self.limit && !self.reached.swap(true, Ordering::SeqCst) {
+            warn!("Job limit {} reached, stopping insertions", self.limit);
+        }
+        granted
+    }
+}
+
+/// What company tasks send to the batch consumer.
+pub enum BatchItem {
+    Job(Box<Job>),
+    /// A company finished scraping and all of its jobs were sent.
+    CompanyDone(FinishedCompany),
+}
+
+/// Checkpointed and recorded in `last_run.json` once a flush has stored all of
+/// the company's jobs. Failed companies never get here, so a resume or the
+/// next incremental fetch retries them.
+pub struct FinishedCompany {
+    pub slug: String,
+    pub started_at: DateTime<Utc>,
+    /// Fetched without `updated_after`.
+    pub full_fetch: bool,
+}
+
+/// Owns the insert buffer: dedupes incoming jobs and flushes them to the
+/// database whenever the buffer reaches the current batch size.
+pub struct BatchConsumer {
+    pub db: Arc<dyn JobDb>,
+    pub client: reqwest::Client,
+    pub metrics: Arc<Metrics>,
+    pub sizer: BatchSizer,
+    pub limit: Option<JobLimit>,
+    /// From `JobDb::get_existing_ids`; jobs inserted this run are added.
+    pub known_ids: HashSet<String>,
+    /// From `JobDb::get_job_fingerprints`; kept up to date the same way.
+    pub fingerprints: HashMap<String, String>,
+    /// Checkpoint file and its contents, when `--checkpoint`/`--resume` is on.
+    pub checkpoint: Option<(String, Checkpoint)>,
+    /// `last_run.json` and its contents; `None` when nothing is written to a database.
+    pub last_run: Option<(String, Arc<LastRuns>)>,
+    pub webhook: Option<WebhookConfig>,
+}
+
+impl BatchConsumer {
+    /// Runs until every sender is dropped, then flushes what's left. Only the
+    /// final flush fails the run; earlier insert errors are logged and counted.
+    pub async fn run(mut self, mut rx: mpsc::Receiver<BatchItem>) -> Result<()> {
+        let mut buffer = Vec::new();
+        let mut finished = Vec::new();
+        // Companies with jobs in a failed flush; never counted as finished
+        let mut failed_slugs = HashSet::new();
+        // Already-stored jobs found again; flushed as `last_seen` updates
+        let mut seen_again = Vec::new();
+        // Already-stored jobs whose title, location or description changed;
+        // upserted so the old values are archived in `job_history`
+        let mut updated = Vec::new();
+        // Outlives the buffer, so duplicates are caught across flushes
+        let mut dedup = Deduplicator::default();
+
+        while let Some(item) = rx.recv().await {
+            match item {
+                BatchItem::Job(job) => {
+                    let job = *job;
+                    if dedup.is_duplicate(&job) {
+                        debug!("Dropping {} job '{}': Duplicate listing", job.company, job.title);
+                        self.metrics.jobs_deduped.fetch_add(1, Ordering::SeqCst);
+                        continue;
+                    }
+                    let fingerprint = job_fingerprint(&job.title, &job.location, &job.description_hash);
+                    if self.known_ids.insert(job.id.clone()) {
+                        self.fingerprints.insert(job.id.clone(), fingerprint);
+                        buffer.push(job);
+                    } else {
+                        match self.fingerprints.get_mut(&job.id) {
+                            Some(known) if *known != fingerprint => {
+                                *known = fingerprint;
+                                updated.push(job);
+                            }
+                            // Unchanged, or the backend keeps no fingerprints
+                            _ => seen_again.push(job.id),
+                        }
+                    }
+                }
+                BatchItem::CompanyDone(company) => finished.push(company),
+            }
+
+            if buffer.len() >= self.sizer.size() || seen_again.len() + updated.len() >= MAX_BATCH_SIZE {
+                let jobs = std::mem::take(&mut buffer);
+                let changed = std::mem::take(&mut updated);
+                let batch_slugs: HashSet<String> = jobs.iter().chain(&changed).map(|j| j.slug.clone()).collect();
+                let mut done = std::mem::take(&mut finished);
+                done.retain(|c| !failed_slugs.contains(&c.slug));
+                let seen = std::mem::take(&mut seen_again);
+                if let Err(e) = self.flush(jobs, done, seen, changed).await {
+                    let e = ScraperError::DbError { detail: format!("{:#}", e) };
+                    self.metrics.record_failure(&e);
+                    warn!(error_kind = e.kind(), "Failed to insert batch: {}", e);
+                    failed_slugs.extend(batch_slugs);
+                }
+            }
+        }
+
+        finished.retain(|c| !failed_slugs.contains(&c.slug));
+        self.flush(buffer, finished, seen_again, updated).await
+    }
+
+    async fn flush(&mut self, mut jobs: Vec<Job>, finished: Vec<FinishedCompany>, seen: Vec<String>, updated: Vec<Job>) -> Result<()> {
+        self.db.mark_seen(&seen).await?;
+        if !updated.is_empty() {
+            self.db.insert_jobs(&updated).await?;
+        }
+        if let Some(limit) = &self.limit {
+            jobs.truncate(limit.reserve(jobs.len()));
+        }
+        if !jobs.is_empty() {
+            let started = Instant::now();
+            let inserted = self.db.insert_jobs(&jobs)
+                .instrument(info_span!("db.insert", batch.size = jobs.len()))
+                .await;
+            self.sizer.record(started.elapsed());
+            inserted?;
+            self.metrics.jobs_inserted.fetch_add(jobs.len(), Ordering::SeqCst);
+        }
+
+        if let Some((path, last_run)) = &self.last_run {
+            for company in &finished {
+                if let Err(e) = last_run.record(&company.slug, company.started_at, company.full_fetch, path) {
+                    warn!("Failed to save {}: {}", path, e);
+                }
+            }
+        }
+        // Companies whose jobs were all stored already finish here too
+        if let Some((path, checkpoint)) = &mut self.checkpoint
+            && !finished.is_empty()
+        {
+            checkpoint.record_flush(finished.into_iter().map(|c| c.slug).collect());
+            if let Err(e) = checkpoint.save(path) {
+                warn!("Failed to save checkpoint {}: {}", path, e);
+            }
+        }
+        if let Some(webhook) = &self.webhook
+            && !jobs.is_empty()
+        {
+            fire_webhook(&self.client, webhook, &jobs).await;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::DbQuery;
+
+    /// Records inserted job ids, slowly enough that senders back up.
+    #[derive(Default)]
+    struct SlowDb {
+        inserted: Mutex<Vec<String>>,
+        seen: Mutex<Vec<String>>,
+        fail_next_insert: AtomicBool,
+    }
+
+    #[async_trait::async_trait]
+    impl JobDb for SlowDb {
+        async fn execute_batch(&self, _: &[DbQuery]) -> Result<()> {
+            Ok(())
+        }
+        async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+            Ok(HashSet::new())
+        }
+        async fn initialize_geo_tables(&self, _: &HashMap<String, String>, _: &HashMap<String, String>) -> Result<()> {
+            Ok(())
+        }
+        async fn mark_seen(&self, ids: &[String]) -> Result<()> {
+            self.seen.lock().unwrap().extend_from_slice(ids);
+            Ok(())
+        }
+        async fn insert_jobs(&self, jobs: &[Job]) -> Result<()> {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            if self.fail_next_insert.swap(false, Ordering::SeqCst) {
+                anyhow::bail!("database is locked");
+            }
+            self.inserted.lock().unwrap().extend(jobs.iter().map(|j| j.id.clone()));
+            Ok(())
+        }
+    }
+
+    fn done(slug: &str) -> BatchItem {
+        BatchItem::CompanyDone(FinishedCompany { slug: slug.to_string(), started_at: Utc::now(), full_fetch: true })
+    }
+
+    fn job(slug: &str, n: usize) -> Job {
+        Job {
+            title: format!("Intern {}", n),
+            company: slug.to_string(),
+            slug: slug.to_string(),
+            ..Job::fixture(&format!("{}-{}", slug, n))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_consumer_drops_nothing_at_capacity() {
+        let db = Arc::new(SlowDb::default());
+        let metrics = Arc::new(Metrics::default());
+        let (tx, rx) = mpsc::channel(4);
+        let consumer = tokio::spawn(BatchConsumer {
+            db: db.clone(),
+            client: reqwest::Client::new(),
+            metrics: metrics.clone(),
+            sizer: BatchSizer::new(),
+            limit: None,
+            known_ids: HashSet::new(),
+            fingerprints: HashMap::new(),
+            checkpoint: None,
+            last_run: None,
+            webhook: None,
+        }.run(rx));
+
+        let producers: Vec<_> = ["acme", "globex", "initech"].into_iter().map(|slug| {
+            let tx = tx.clone();
+            tokio::spawn(async move {
+                for n in 0..150 {
+                    tx.send(BatchItem::Job(Box::new(job(slug, n)))).await.unwrap();
+                }
+                tx.send(done(slug)).await.unwrap();
+            })
+        }).collect();
+        drop(tx);
+        for producer in producers {
+            producer.await.unwrap();
+        }
+        consumer.await.unwrap().unwrap();
+
+        let inserted = db.inserted.lock().unwrap();
+        assert_eq!(inserted.len(), 450);
+        assert_eq!(inserted.iter().collect::<HashSet<_>>().len(), 450);
+        assert_eq!(metrics.jobs_inserted.load(Ordering::SeqCst), 450);
+    }
+
+    #[tokio::test]
+    async fn test_consumer_dedupes_across_flushes() {
+        let db = Arc::new(SlowDb::default());
+        let metrics = Arc::new(Metrics::default());
+        let (tx, rx) = mpsc::channel(8);
+        let consumer = tokio::spawn(BatchConsumer {
+            db: db.clone(),
+            client: reqwest::Client::new(),
+            metrics: metrics.clone(),
+            sizer: BatchSizer::new(),
+            limit: None,
+            known_ids: HashSet::new(),
+            fingerprints: HashMap::new(),
+            checkpoint: None,
+            last_run: None,
+            webhook: None,
+        }.run(rx));
+        // Exactly one full batch, so the repost arrives after a flush
+        let batch = BatchSizer::new().size();
+        for n in 0..batch {
+            tx.send(BatchItem::Job(Box::new(job("acme", n)))).await.unwrap();
+        }
+        // Listed again on a second board
+        let repost = Job { id: "acme-repost".to_string(), ..job("acme", 0) };
+        tx.send(BatchItem::Job(Box::new(repost))).await.unwrap();
+        drop(tx);
+        consumer.await.unwrap().unwrap();
+
+        assert_eq!(db.inserted.lock().unwrap().len(), batch);
+        assert_eq!(metrics.jobs_deduped.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_consumer_marks_stored_jobs_seen() {
+        let db = Arc::new(SlowDb::default());
+        let (tx, rx) = mpsc::channel(8);
+        let consumer = tokio::spawn(BatchConsumer {
+            db: db.clone(),
+            client: reqwest::Client::new(),
+            metrics: Arc::new(Metrics::default()),
+            sizer: BatchSizer::new(),
+            limit: None,
+            known_ids: HashSet::from(["acme-0".to_string()]),
+            fingerprints: HashMap::new(),
+            checkpoint: None,
+            last_run: None,
+            webhook: None,
+        }.run(rx));
+        for n in 0..2 {
+            tx.send(BatchItem::Job(Box::new(job("acme", n)))).await.unwrap();
+        }
+        drop(tx);
+        consumer.await.unwrap().unwrap();
+
+        assert_eq!(*db.inserted.lock().unwrap(), vec!["acme-1"]);
+        assert_eq!(*db.seen.lock().unwrap(), vec!["acme-0"]);
+    }
+
+    #[tokio::test]
+    async fn test_consumer_checkpoints_companies_without_new_jobs() {
+        let path = std::env::temp_dir().join(format!("zapply_batch_checkpoint_{}.json", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let db = Arc::new(SlowDb::default());
+        let (tx, rx) = mpsc::channel(8);
+        let consumer = tokio::spawn(BatchConsumer {
+            db: db.clone(),
+            client: reqwest::Client::new(),
+            metrics: Arc::new(Metrics::default()),
+            sizer: BatchSizer::new(),
+            limit: None,
+            known_ids: HashSet::from(["acme-0".to_string()]),
+            fingerprints: HashMap::new(),
+            checkpoint: Some((path.clone(), Checkpoint::new())),
+            last_run: None,
+            webhook: None,
+        }.run(rx));
+        tx.send(BatchItem::Job(Box::new(job("acme", 0)))).await.unwrap();
+        tx.send(done("acme")).await.unwrap();
+        drop(tx);
+        consumer.await.unwrap().unwrap();
+
+        assert!(db.inserted.lock().unwrap().is_empty());
+        assert_eq!(Checkpoint::load(&path).unwrap().completed_slugs, vec!["acme"]);
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_consumer_records_last_run_after_insert() {
+        let path = std::env::temp_dir().join(format!("zapply_batch_last_run_{}.json", std::process::id()));
+        let path = path.to_str().unwrap().to_string();
+        let db = Arc::new(SlowDb { fail_next_insert: AtomicBool::new(true), ..Default::default() });
+        let last_run = Arc::new(LastRuns::new());
+        let (tx, rx) = mpsc::channel(8);
+        let consumer = tokio::spawn(BatchConsumer {
+            db: db.clone(),
+            client: reqwest::Client::new(),
+            metrics: Arc::new(Metrics::default()),
+            sizer: BatchSizer::new(),
+            limit: None,
+            known_ids: HashSet::new(),
+            fingerprints: HashMap::new(),
+            checkpoint: None,
+            last_run: Some((path.clone(), last_run.clone())),
+            webhook: None,
+        }.run(rx));
+        // The first, full batch fails to insert, so acme's jobs are lost
+        for n in 0..BatchSizer::new().size() {
+            tx.send(BatchItem::Job(Box::new(job("acme", n)))).await.unwrap();
+        }
+        tx.send(done("acme")).await.unwrap();
+        tx.send(BatchItem::Job(Box::new(job("globex", 0)))).await.unwrap();
+        tx.send(done("globex")).await.unwrap();
+        drop(tx);
+        consumer.await.unwrap().unwrap();
+
+        assert_eq!(*db.inserted.lock().unwrap(), vec!["globex-0"]);
+        let now = Utc::now();
+        assert_eq!(last_run.updated_after("acme", now), None);
+        assert!(last_run.updated_after("globex", now).is_some());
+        std::fs::remove_file(&path).unwrap();
+    }
+
+    #[test]
+    fn test_batch_size_grows_when_fast() {
+        let sizer = BatchSizer::new();
+        sizer.record(Duration::from_millis(100));
+        assert_eq!(sizer.size(), 200);
+        for _ in 0..5 {
+            sizer.record(Duration::from_millis(100));
+        }
+        assert_eq!(sizer.size(), MAX_BATCH_SIZE);
+    }
+
+    #[test]
+    fn test_batch_size_shrinks_when_slow() {
+        let sizer = BatchSizer::new();
+        sizer.record(Duration::from_secs(5));
+        assert_eq!(sizer.size(), 50);
+        for _ in 0..5 {
+            sizer.record(Duration::from_secs(5));
+        }
+        assert_eq!(sizer.size(), MIN_BATCH_SIZE);
+    }
+
+    #[test]
+    fn test_batch_size_uses_average() {
+        let sizer = BatchSizer::new();
+        sizer.record(Duration::from_secs(1));
+        assert_eq!(sizer.size(), 100);
+        // One slow outlier doesn't push the average over 2s
+        sizer.record(Duration::from_millis(2500));
+        assert_eq!(sizer.size(), 100);
+        sizer.record(Duration::from_secs(3));
+        assert_eq!(sizer.size(), 50);
+    }
+
+    #[test]
+    fn test_job_limit() {
+        let limit = JobLimit::new(250);
+        assert_eq!(limit.reserve(100), 100);
+        assert_eq!(limit.reserve(100), 100);
+        assert!(!limit.reached.load(Ordering::SeqCst));
+        assert_eq!(limit.reserve(100), 50);
+        assert!(limit.reached.load(Ordering::SeqCst));
+        assert_eq!(limit.reserve(100), 0);
+    }
+}
@@ -0,0 +1,71 @@
+use anyhow::{Context, Result};
+use chrono::Utc;
+use serde::{Deserialize, Serialize};
+use std::fs;
+
+/// Progress of a scrape, saved after each batch flush so `--resume` can skip
+/// companies whose jobs are already in the database.
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct Checkpoint {
+    pub completed_slugs: Vec<String>,
+    pub started_at: String,
+    pub last_flush_at: String,
+}
+
+impl Checkpoint {
+    pub fn new() -> Self {
+        let now = Utc::now().to_rfc3339();
+        Self {
+            completed_slugs: Vec::new(),
+            started_at: now.clone(),
+            last_flush_at: now,
+        }
+    }
+
+    pub fn load(path: &str) -> Result<Self> {
+        let content = fs::read_to_string(path).context(format!("Failed to read checkpoint {}", path))?;
+        serde_json::from_str(&content).context(format!("Invalid checkpoint {}", path))
+    }
+
+    /// Writes to a temp file and renames it over `path`, so an interrupted
+    /// run never leaves a half-written checkpoint.
+    pub fn save(&self, path: &str) -> Result<()> {
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, serde_json::to_string_pretty(self)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+
+    pub fn record_flush(&mut self, slugs: Vec<String>) {
+        self.completed_slugs.extend(slugs);
+        self.last_flush_at = Utc::now().to_rfc3339();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_checkpoint_write_read_cycle() {
+        let path = std::env::temp_dir().join(format!("zapply_checkpoint_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+
+        let mut checkpoint = Checkpoint::new();
+        checkpoint.record_flush(vec!["stripe".to_string(), "canva".to_string()]);
+        checkpoint.save(path).unwrap();
+
+        let mut loaded = Checkpoint::load(path).unwrap();
+        assert_eq!(loaded, checkpoint);
+        assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists());
+
+        loaded.record_flush(vec!["atlassian".to_string()]);
+        loaded.save(path).unwrap();
+        let reloaded = Checkpoint::load(path).unwrap();
+        assert_eq!(reloaded.completed_slugs, vec!["stripe", "canva", "atlassian"]);
+        assert_eq!(reloaded.started_at, checkpoint.started_at);
+
+        fs::remove_file(path).unwrap();
+        assert!(Checkpoint::load(path).is_err());
+    }
+}
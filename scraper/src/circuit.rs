@@ -0,0 +1,152 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct FailureRecord {
+    consecutive_failures: u32,
+    last_failure_at: DateTime<Utc>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    /// Too many consecutive failures; skip the company.
+    Open,
+    /// The reset window has passed; allow one attempt to decide.
+    HalfOpen,
+}
+
+/// Skips companies that keep failing, persisted between runs in `failure_counts.json`.
+pub struct CircuitBreaker {
+    records: Mutex<HashMap<String, FailureRecord>>,
+    max_failures: u32,
+    reset_after: Duration,
+}
+
+impl CircuitBreaker {
+    pub fn new(max_failures: u32, reset_after_days: i64) -> Self {
+        Self {
+            records: Mutex::new(HashMap::new()),
+            max_failures,
+            reset_after: Duration::days(reset_after_days),
+        }
+    }
+
+    /// Loads saved failure counts; a missing file starts with every circuit closed.
+    pub fn load(path: &str, max_failures: u32, reset_after_days: i64) -> Result<Self> {
+        let breaker = Self::new(max_failures, reset_after_days);
+        if std::path::Path::new(path).exists() {
+            let content = fs::read_to_string(path)?;
+            let records = serde_json::from_str(&content).context(format!("Invalid {}", path))?;
+            *breaker.records.lock().unwrap() = records;
+        }
+        Ok(breaker)
+    }
+
+    pub fn save(&self, path: &str) -> Result<()> {
+        let records = self.records.lock().unwrap();
+        fs::write(path, serde_json::to_string_pretty(&*records)?)?;
+        Ok(())
+    }
+
+    pub fn state(&self, slug: &str, now: DateTime<Utc>) -> CircuitState {
+        match self.records.lock().unwrap().get(slug) {
+            Some(r) if r.consecutive_failures >= self.max_failures => {
+                if now - r.last_failure_at >= self.reset_after {
+                    CircuitState::HalfOpen
+                } else {
+                    CircuitState::Open
+                }
+            }
+            _ => CircuitState::Closed,
+        }
+    }
+
+    pub fn record_success(&self, slug: &str) {
+        self.records.lock().unwrap().remove(slug);
+    }
+
+    /// A failed half-open attempt lands here too, re-opening the circuit for another window.
+    pub fn record_failure(&self, slug: &str, now: DateTime<Utc>) {
+        let mut records = self.records.lock().unwrap();
+        let record = records.entry(slug.to_string()).or_insert(FailureRecord {
+            consecutive_failures: 0,
+            last_failure_at: now,
+        });
+        record.consecutive_failures += 1;
+        record.last_failure_at = now;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_circuit_opens_after_max_failures() {
+        let breaker = CircuitBreaker::new(3, 7);
+        let now = Utc::now();
+
+        breaker.record_failure("acme", now);
+        breaker.record_failure("acme", now);
+        assert_eq!(breaker.state("acme", now), CircuitState::Closed);
+        breaker.record_failure("acme", now);
+        assert_eq!(breaker.state("acme", now), CircuitState::Open);
+        assert_eq!(breaker.state("other", now), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuit_success_resets_count() {
+        let breaker = CircuitBreaker::new(3, 7);
+        let now = Utc::now();
+
+        breaker.record_failure("acme", now);
+        breaker.record_failure("acme", now);
+        breaker.record_success("acme");
+        breaker.record_failure("acme", now);
+        assert_eq!(breaker.state("acme", now), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuit_half_open_transitions() {
+        let breaker = CircuitBreaker::new(3, 7);
+        let opened_at = Utc::now() - Duration::days(8);
+        for _ in 0..3 {
+            breaker.record_failure("acme", opened_at);
+        }
+        let now = Utc::now();
+        assert_eq!(breaker.state("acme", opened_at + Duration::days(6)), CircuitState::Open);
+        assert_eq!(breaker.state("acme", now), CircuitState::HalfOpen);
+
+        // Trial fails: open again for a fresh window
+        breaker.record_failure("acme", now);
+        assert_eq!(breaker.state("acme", now), CircuitState::Open);
+
+        // Trial succeeds: closed
+        breaker.record_success("acme");
+        assert_eq!(breaker.state("acme", now), CircuitState::Closed);
+    }
+
+    #[test]
+    fn test_circuit_persistence() {
+        let path = std::env::temp_dir().join(format!("zapply_failure_counts_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let now = Utc::now();
+
+        let breaker = CircuitBreaker::new(2, 7);
+        breaker.record_failure("acme", now);
+        breaker.record_failure("acme", now);
+        breaker.save(path).unwrap();
+
+        let loaded = CircuitBreaker::load(path, 2, 7).unwrap();
+        assert_eq!(loaded.state("acme", now), CircuitState::Open);
+        fs::remove_file(path).unwrap();
+
+        let empty = CircuitBreaker::load(path, 2, 7).unwrap();
+        assert_eq!(empty.state("acme", now), CircuitState::Closed);
+    }
+}
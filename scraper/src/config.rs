@@ -1,22 +1,139 @@
+use anyhow::{Context, Result};
+use regex::{Regex, RegexBuilder};
 use std::env;
 
+use crate::webhook::WebhookConfig;
+
 pub struct Config {
-    pub slugs_file: String,
+    /// `SLUGS_FILE` or `--slugs=`, comma-separated; merged in order.
+    pub slugs_files: Vec<String>,
     pub concurrency: usize,
+    /// In-flight requests allowed per API host across all company tasks.
+    pub max_requests_per_domain: usize,
+    /// Description requests in flight per company; 0 means no cap.
+    pub enrichment_concurrency: usize,
     pub keywords_regex: String,
     pub negative_keywords_regex: String,
+    /// Applies to both keyword regexes, so custom patterns don't need `(?i)`.
+    pub keywords_regex_case_insensitive: bool,
+    pub tag_rules_file: Option<String>,
+    /// TOML rules replacing the built-in department categories.
+    pub department_rules_file: Option<String>,
+    /// Tags scored below this are dropped. Title matches are boosted 1.5x, so
+    /// 0.6 keeps a context-distance match (0.5) only when it's in the title.
+    pub min_tag_confidence: f32,
+    /// Distinct texts whose tags are remembered; 0 turns the cache off.
+    pub tag_cache_size: usize,
+    /// Also store a plain-text copy of each description (`Job::text_description`).
+    pub include_text_description: bool,
+    /// Longer descriptions are cut at a sentence or word boundary.
+    pub max_description_chars: Option<usize>,
+    pub cutoff_days_default: i64,
+    pub cutoff_days_eoi: i64,
+    pub circuit_max_failures: u32,
+    pub circuit_reset_after_days: i64,
+    pub rss_title: String,
+    pub rss_link: String,
+    pub rss_description: String,
+    pub webhook: Option<WebhookConfig>,
 }
 
 impl Config {
-    pub fn load() -> Self {
-        Self {
-            slugs_file: env::var("SLUGS_FILE").unwrap_or_else(|_| "slugs.json".to_string()),
+    /// Fails if either keyword regex doesn't compile.
+    pub fn load() -> Result<Self> {
+        let config = Self {
+            slugs_files: env::var("SLUGS_FILE").unwrap_or_else(|_| "slugs.json".to_string())
+                .split(',')
+                .map(|f| f.trim().to_string())
+                .filter(|f| !f.is_empty())
+                .collect(),
             concurrency: env::var("CONCURRENCY")
                 .ok()
                 .and_then(|s| s.parse().ok())
                 .unwrap_or(25),
+            max_requests_per_domain: env::var("MAX_REQUESTS_PER_DOMAIN")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+            enrichment_concurrency: env::var("ENRICHMENT_CONCURRENCY")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(10),
             keywords_regex: env::var("KEYWORDS_REGEX").unwrap_or_else(|_| r"(?i)\b(intern|apprentice|student|trainee|internship|fellowship|undergraduate|junior|jr|graduate|entry[-\s]level|associate)\b".to_string()),
             negative_keywords_regex: env::var("NEGATIVE_KEYWORDS_REGEX").unwrap_or_else(|_| r"(?i)\b(senior|snr|sr|principal|lead|staff|director|vp|head\s+of|manager)\b".to_string()),
-        }
+            keywords_regex_case_insensitive: !env::var("KEYWORDS_REGEX_CASE_INSENSITIVE").is_ok_and(|v| v == "0" || v.eq_ignore_ascii_case("false")),
+            tag_rules_file: env::var("TAG_RULES_FILE").ok(),
+            department_rules_file: env::var("DEPARTMENT_RULES_FILE").ok(),
+            min_tag_confidence: env::var("MIN_TAG_CONFIDENCE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(0.6),
+            tag_cache_size: env::var("TAG_CACHE_SIZE")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(crate::tag::DEFAULT_TAG_CACHE_CAPACITY),
+            include_text_description: env::var("INCLUDE_TEXT_DESCRIPTION").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true")),
+            max_description_chars: env::var("MAX_DESCRIPTION_CHARS").ok().and_then(|s| s.parse().ok()),
+            cutoff_days_default: env::var("CUTOFF_DAYS_DEFAULT")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(60),
+            cutoff_days_eoi: env::var("CUTOFF_DAYS_EOI")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(120),
+            circuit_max_failures: env::var("CIRCUIT_MAX_FAILURES")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(3),
+            circuit_reset_after_days: env::var("CIRCUIT_RESET_AFTER_DAYS")
+                .ok()
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(7),
+            rss_title: env::var("RSS_TITLE").unwrap_or_else(|_| "zapply".to_string()),
+            rss_link: env::var("RSS_LINK").unwrap_or_else(|_| "https://github.com/amooo-ooo/zapply".to_string()),
+            rss_description: env::var("RSS_DESCRIPTION").unwrap_or_else(|_| "Internship and early career roles".to_string()),
+            webhook: env::var("WEBHOOK_URL").ok().filter(|u| !u.is_empty()).map(|url| WebhookConfig {
+                url,
+                max_jobs_per_payload: env::var("WEBHOOK_MAX_JOBS")
+                    .ok()
+                    .and_then(|s| s.parse().ok())
+                    .unwrap_or(50),
+                include_description: env::var("WEBHOOK_INCLUDE_DESCRIPTION").is_ok_and(|v| v == "1" || v.eq_ignore_ascii_case("true")),
+            }),
+        };
+        config.keyword_regex()?;
+        config.negative_keyword_regex()?;
+        Ok(config)
+    }
+
+    pub fn keyword_regex(&self) -> Result<Regex> {
+        build_keyword_regex(&self.keywords_regex, self.keywords_regex_case_insensitive)
+            .context("Invalid KEYWORDS_REGEX")
+    }
+
+    pub fn negative_keyword_regex(&self) -> Result<Regex> {
+        build_keyword_regex(&self.negative_keywords_regex, self.keywords_regex_case_insensitive)
+            .context("Invalid NEGATIVE_KEYWORDS_REGEX")
+    }
+}
+
+fn build_keyword_regex(pattern: &str, case_insensitive: bool) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(case_insensitive).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_keyword_regex_case_flag() {
+        let regex = build_keyword_regex(r"\bintern\b", true).unwrap();
+        assert!(regex.is_match("Software INTERN"));
+        let regex = build_keyword_regex(r"\bintern\b", false).unwrap();
+        assert!(!regex.is_match("Software INTERN"));
+        // An inline flag still wins over the setting
+        assert!(build_keyword_regex(r"(?i)\bintern\b", false).unwrap().is_match("Intern"));
+        assert!(build_keyword_regex(r"(intern", true).is_err());
     }
 }
@@ -0,0 +1,98 @@
+use std::collections::{BTreeMap, HashSet};
+use std::sync::Mutex;
+
+use crate::models::Job;
+
+/// `--filter-countries=US,AU` and `--exclude-countries=CN,RU`, applied to
+/// normalized jobs. Both lists can be given at once.
+#[derive(Default)]
+pub struct CountryFilter {
+    /// When set, only these country codes are kept; jobs with no country are dropped.
+    allow: Option<HashSet<String>>,
+    exclude: HashSet<String>,
+    /// Dropped jobs per country code ("none" when unresolved), for the end-of-run log.
+    dropped: Mutex<BTreeMap<String, usize>>,
+}
+
+impl CountryFilter {
+    pub fn new(allow: Option<&str>, exclude: Option<&str>) -> Self {
+        Self {
+            allow: allow.map(parse_codes),
+            exclude: exclude.map(parse_codes).unwrap_or_default(),
+            dropped: Mutex::new(BTreeMap::new()),
+        }
+    }
+
+    /// Whether `job` passes both lists; rejected jobs are counted.
+    pub fn allows(&self, job: &Job) -> bool {
+        let code = job.country_code.as_deref().map(str::to_uppercase);
+        let allowed = match (&self.allow, &code) {
+            (Some(allow), Some(code)) => allow.contains(code),
+            (Some(_), None) => false,
+            (None, _) => true,
+        };
+        let excluded = code.as_ref().is_some_and(|c| self.exclude.contains(c));
+        if allowed && !excluded {
+            return true;
+        }
+        let key = code.unwrap_or_else(|| "none".to_string());
+        *self.dropped.lock().unwrap().entry(key).or_insert(0) += 1;
+        false
+    }
+
+    /// "CN 4, none 2, RU 1", most dropped first.
+    pub fn dropped_summary(&self) -> Option<String> {
+        let dropped = self.dropped.lock().unwrap();
+        if dropped.is_empty() {
+            return None;
+        }
+        let mut counts: Vec<(&String, &usize)> = dropped.iter().collect();
+        counts.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+        Some(counts.iter().map(|(code, n)| format!("{} {}", code, n)).collect::<Vec<_>>().join(", "))
+    }
+}
+
+fn parse_codes(list: &str) -> HashSet<String> {
+    list.split(',')
+        .map(|c| c.trim().to_uppercase())
+        .filter(|c| !c.is_empty())
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(country_code: Option<&str>) -> Job {
+        Job { country_code: country_code.map(String::from), ..Job::fixture("lever-1") }
+    }
+
+    #[test]
+    fn test_allowlist() {
+        let filter = CountryFilter::new(Some("us, au,gb"), None);
+        assert!(filter.allows(&job(Some("US"))));
+        assert!(filter.allows(&job(Some("gb"))));
+        assert!(!filter.allows(&job(Some("DE"))));
+        assert!(!filter.allows(&job(None)));
+        assert_eq!(filter.dropped_summary().as_deref(), Some("DE 1, none 1"));
+    }
+
+    #[test]
+    fn test_blocklist_and_stacking() {
+        let exclude = CountryFilter::new(None, Some("CN,RU"));
+        assert!(!exclude.allows(&job(Some("CN"))));
+        assert!(!exclude.allows(&job(Some("CN"))));
+        assert!(exclude.allows(&job(Some("US"))));
+        // Unresolved locations only fail an allowlist
+        assert!(exclude.allows(&job(None)));
+        assert_eq!(exclude.dropped_summary().as_deref(), Some("CN 2"));
+
+        let both = CountryFilter::new(Some("US,CN"), Some("CN"));
+        assert!(both.allows(&job(Some("US"))));
+        assert!(!both.allows(&job(Some("CN"))));
+
+        let none = CountryFilter::default();
+        assert!(none.allows(&job(None)));
+        assert_eq!(none.dropped_summary(), None);
+    }
+}
@@ -0,0 +1,113 @@
+use once_cell::sync::Lazy;
+use regex::Regex;
+use std::collections::HashMap;
+
+use crate::models::Job;
+
+// "(m/w/d)", "(f/m/x)", "(h/f)" etc. on European listings
+static GENDER_MARKER: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\(\s*[mwfdxh](\s*/\s*[mwfdxh])+\s*\)").unwrap());
+static NON_ALNUM: Lazy<Regex> = Lazy::new(|| Regex::new(r"[^\p{L}\p{N}]+").unwrap());
+
+const FILLER_WORDS: &[&str] = &[
+    "a", "an", "the", "and", "or", "of", "for", "to", "in", "at", "with",
+    "job", "role", "position", "opening", "opportunity", "vacancy", "hiring",
+];
+
+/// Drops jobs that a company lists more than once, e.g. on two ATS boards.
+/// Remembers every job it has checked, keyed by (normalized company name,
+/// normalized title, location), so each check is a single lookup. The name
+/// rather than the slug, since each board of a company has its own slug.
+#[derive(Default)]
+pub struct Deduplicator {
+    seen: HashMap<(String, String, String), String>,
+}
+
+impl Deduplicator {
+    /// Lowercases, strips punctuation and gender markers, and removes filler words.
+    pub fn normalize_title(title: &str) -> String {
+        let title = GENDER_MARKER.replace_all(title, " ").to_lowercase();
+        NON_ALNUM.split(&title)
+            .filter(|w| !w.is_empty() && !FILLER_WORDS.contains(w))
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+
+    fn key(job: &Job) -> (String, String, String) {
+        (
+            NON_ALNUM.replace_all(&job.company.to_lowercase(), "").into_owned(),
+            Self::normalize_title(&job.title),
+            job.location.trim().to_lowercase(),
+        )
+    }
+
+    /// True if a job with another id but the same key was checked before.
+    /// Otherwise the job is remembered for later checks.
+    pub fn is_duplicate(&mut self, job: &Job) -> bool {
+        match self.seen.get(&Self::key(job)) {
+            Some(id) => *id != job.id,
+            None => {
+                self.seen.insert(Self::key(job), job.id.clone());
+                false
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn job(id: &str, slug: &str, title: &str, location: &str) -> Job {
+        Job {
+            title: title.to_string(),
+            company: slug.to_string(),
+            slug: slug.to_string(),
+            location: location.to_string(),
+            ..Job::fixture(id)
+        }
+    }
+
+    #[test]
+    fn test_normalize_title() {
+        assert_eq!(Deduplicator::normalize_title("Software Engineering Intern (m/w/d)"), "software engineering intern");
+        assert_eq!(Deduplicator::normalize_title("Intern - Data Science, Summer 2025"), "intern data science summer 2025");
+        assert_eq!(Deduplicator::normalize_title("Internship Opportunity for the Marketing Team"), "internship marketing team");
+    }
+
+    #[test]
+    fn test_real_world_duplicates() {
+        let mut dedup = Deduplicator::default();
+        assert!(!dedup.is_duplicate(&job("greenhouse-1", "canva", "Graduate Software Engineer", "Sydney, New South Wales, Australia")));
+        assert!(!dedup.is_duplicate(&job("greenhouse-2", "n26", "Working Student Finance (f/m/x)", "Berlin, Germany")));
+        assert!(!dedup.is_duplicate(&job("lever-3", "stripe", "Software Engineer, Intern", "Toronto, Ontario, Canada")));
+
+        assert!(dedup.is_duplicate(&job("workable-9", "canva", "Graduate Software Engineer", "Sydney, New South Wales, Australia")));
+        assert!(dedup.is_duplicate(&job("lever-9", "n26", "Working Student - Finance", "Berlin, Germany")));
+        assert!(dedup.is_duplicate(&job("greenhouse-9", "stripe", "Software Engineer Intern", "Toronto, Ontario, Canada")));
+    }
+
+    #[test]
+    fn test_not_duplicates() {
+        let mut dedup = Deduplicator::default();
+        let first = job("greenhouse-1", "canva", "Graduate Software Engineer", "Sydney, New South Wales, Australia");
+        assert!(!dedup.is_duplicate(&first));
+
+        // Same listing (same id) is not its own duplicate
+        assert!(!dedup.is_duplicate(&first));
+        // Different location, company, or title
+        assert!(!dedup.is_duplicate(&job("lever-2", "canva", "Graduate Software Engineer", "Melbourne, Victoria, Australia")));
+        assert!(!dedup.is_duplicate(&job("lever-3", "atlassian", "Graduate Software Engineer", "Sydney, New South Wales, Australia")));
+        assert!(!dedup.is_duplicate(&job("lever-4", "canva", "Graduate Data Engineer", "Sydney, New South Wales, Australia")));
+    }
+
+    #[test]
+    fn test_two_boards_of_one_company() {
+        let mut dedup = Deduplicator::default();
+        let mut greenhouse = job("greenhouse-1", "canva", "Graduate Software Engineer", "Sydney, New South Wales, Australia");
+        greenhouse.company = "Canva".to_string();
+        let mut lever = job("lever-1", "canva-careers", "Graduate Software Engineer", "Sydney, New South Wales, Australia");
+        lever.company = "canva".to_string();
+        assert!(!dedup.is_duplicate(&greenhouse));
+        assert!(dedup.is_duplicate(&lever));
+    }
+}
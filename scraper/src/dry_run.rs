@@ -0,0 +1,161 @@
+use anyhow::{Context, Result};
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::fmt::Write as _;
+use std::io::Write as _;
+use std::sync::Mutex;
+
+use crate::{DbQuery, JobDb};
+
+/// Statements shown from each end of the run with `--dry-run-db`.
+const SHOWN_QUERIES: usize = 5;
+
+/// Runs the whole scrape but only collects the SQL it would execute
+/// (`--dry-run-db`). The first and last few statements are printed once the
+/// run finishes; with `--sql-output=path.sql` every statement is written to
+/// that file instead, ready to review or replay. Geo table seeding is left
+/// out, and every job counts as new.
+pub struct DryRunDb {
+    path: Option<String>,
+    queries: Mutex<CollectedQueries>,
+}
+
+#[derive(Default)]
+struct CollectedQueries {
+    total: usize,
+    first: Vec<String>,
+    last: VecDeque<String>,
+}
+
+impl DryRunDb {
+    pub fn new() -> Self {
+        Self { path: None, queries: Mutex::new(CollectedQueries::default()) }
+    }
+
+    /// Truncates `path` so each run's file only holds that run's SQL.
+    pub fn to_file(path: &str) -> Result<Self> {
+        std::fs::File::create(path).with_context(|| format!("Failed to create {}", path))?;
+        Ok(Self { path: Some(path.to_string()), queries: Mutex::new(CollectedQueries::default()) })
+    }
+
+    fn summary(&self) -> String {
+        let queries = self.queries.lock().unwrap();
+        let mut out = String::new();
+        writeln!(out, "-- {} queries would be executed", queries.total).ok();
+        for sql in &queries.first {
+            writeln!(out, "{};", sql).ok();
+        }
+        let hidden = queries.total - queries.first.len() - queries.last.len();
+        if hidden > 0 {
+            writeln!(out, "-- ... {} more ...", hidden).ok();
+        }
+        for sql in &queries.last {
+            writeln!(out, "{};", sql).ok();
+        }
+        out
+    }
+}
+
+#[async_trait::async_trait]
+impl JobDb for DryRunDb {
+    async fn execute_batch(&self, queries: &[DbQuery]) -> Result<()> {
+        if let Some(path) = &self.path {
+            let file = std::fs::OpenOptions::new().append(true).open(path)?;
+            let mut out = std::io::BufWriter::new(file);
+            for query in queries {
+                writeln!(out, "{};", query.to_sql())?;
+            }
+            out.flush()?;
+        }
+
+        let mut collected = self.queries.lock().unwrap();
+        for query in queries {
+            collected.total += 1;
+            if collected.first.len() < SHOWN_QUERIES {
+                collected.first.push(query.to_sql());
+                continue;
+            }
+            if collected.last.len() == SHOWN_QUERIES {
+                collected.last.pop_front();
+            }
+            collected.last.push_back(query.to_sql());
+        }
+        Ok(())
+    }
+
+    /// Nothing to connect to; the default would record a `SELECT 1`.
+    async fn health_check(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+        Ok(HashSet::new())
+    }
+
+    async fn initialize_geo_tables(&self, _countries: &HashMap<String, String>, _regions: &HashMap<String, String>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn finalize(&self) -> Result<()> {
+        match &self.path {
+            Some(path) => eprintln!("Wrote {} queries to {}", self.queries.lock().unwrap().total, path),
+            None => print!("{}", self.summary()),
+        }
+        Ok(())
+    }
+
+    fn persists_jobs(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::Value;
+
+    fn queries(range: std::ops::Range<usize>) -> Vec<DbQuery> {
+        range.map(|n| DbQuery {
+            sql: "UPDATE jobs SET last_seen = ?1 WHERE id = ?2".to_string(),
+            params: vec![Value::from("2025-01-01"), Value::from(format!("lever-{}", n))],
+            context: None,
+        }).collect()
+    }
+
+    #[tokio::test]
+    async fn test_summary_shows_first_and_last_queries() {
+        let db = DryRunDb::new();
+        db.health_check().await.unwrap();
+        db.execute_batch(&queries(0..8)).await.unwrap();
+        db.execute_batch(&queries(8..12)).await.unwrap();
+
+        let summary = db.summary();
+        let lines: Vec<&str> = summary.lines().collect();
+        assert_eq!(lines[0], "-- 12 queries would be executed");
+        assert_eq!(lines[1], "UPDATE jobs SET last_seen = '2025-01-01' WHERE id = 'lever-0';");
+        assert_eq!(lines[5], "UPDATE jobs SET last_seen = '2025-01-01' WHERE id = 'lever-4';");
+        assert_eq!(lines[6], "-- ... 2 more ...");
+        assert_eq!(lines[7], "UPDATE jobs SET last_seen = '2025-01-01' WHERE id = 'lever-7';");
+        assert_eq!(lines[11], "UPDATE jobs SET last_seen = '2025-01-01' WHERE id = 'lever-11';");
+
+        // Short runs print every query without a gap
+        let db = DryRunDb::new();
+        db.execute_batch(&queries(0..3)).await.unwrap();
+        assert_eq!(db.summary().lines().count(), 4);
+    }
+
+    #[tokio::test]
+    async fn test_sql_output_writes_every_query() {
+        let path = std::env::temp_dir().join(format!("zapply_dry_run_{}.sql", std::process::id()));
+        let path = path.to_str().unwrap();
+        std::fs::write(path, "stale").unwrap();
+
+        let db = DryRunDb::to_file(path).unwrap();
+        db.execute_batch(&queries(0..12)).await.unwrap();
+        let sql = std::fs::read_to_string(path).unwrap();
+        assert_eq!(sql.lines().count(), 12);
+        assert!(sql.starts_with("UPDATE jobs SET last_seen = '2025-01-01' WHERE id = 'lever-0';\n"), "{}", sql);
+        assert!(!sql.contains("stale"));
+
+        std::fs::remove_file(path).unwrap();
+    }
+}
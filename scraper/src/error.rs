@@ -0,0 +1,83 @@
+use std::fmt;
+
+use crate::models::AtsType;
+
+/// Categorized scrape failures, so metrics and logs can tell a dead endpoint
+/// from a schema change.
+#[derive(Debug)]
+pub enum ScraperError {
+    /// Non-success status; `status` is 0 when no response was received.
+    HttpError { status: u16, url: String },
+    ParseError { ats: AtsType, company: String, detail: String },
+    RateLimited { url: String, retry_after: Option<u64> },
+    DbError { detail: String },
+    EnrichmentError { job_id: String, detail: String },
+    /// Skipped without a request because the company's circuit is open. Not a
+    /// failure: it stays out of failure counts, metrics and the health report.
+    CircuitOpen { company: String },
+}
+
+impl ScraperError {
+    /// Short label for metrics (`zapply_failures_total{kind="..."}`).
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Self::HttpError { .. } => "http",
+            Self::ParseError { .. } => "parse",
+            Self::RateLimited { .. } => "rate_limited",
+            Self::DbError { .. } => "db",
+            Self::EnrichmentError { .. } => "enrichment",
+            Self::CircuitOpen { .. } => "circuit_open",
+        }
+    }
+
+    pub fn from_reqwest(e: reqwest::Error, url: &str) -> Self {
+        Self::HttpError {
+            status: e.status().map(|s| s.as_u16()).unwrap_or(0),
+            url: url.to_string(),
+        }
+    }
+}
+
+impl fmt::Display for ScraperError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::HttpError { status: 0, url } => write!(f, "Request failed for {}", url),
+            Self::HttpError { status, url } => write!(f, "HTTP {} for {}", status, url),
+            Self::ParseError { ats, company, detail } => write!(f, "{:?} parsing failed for {}: {}", ats, company, detail),
+            Self::RateLimited { url, retry_after: Some(secs) } => write!(f, "Rate limited by {} (retry after {}s)", url, secs),
+            Self::RateLimited { url, retry_after: None } => write!(f, "Rate limited by {}", url),
+            Self::DbError { detail } => write!(f, "Database error: {}", detail),
+            Self::EnrichmentError { job_id, detail } => write!(f, "Enrichment failed for {}: {}", job_id, detail),
+            Self::CircuitOpen { company } => write!(f, "{}: SKIPPED (circuit open)", company),
+        }
+    }
+}
+
+impl std::error::Error for ScraperError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_scraper_error_display() {
+        let err = ScraperError::HttpError { status: 404, url: "https://example.com".to_string() };
+        assert_eq!(err.to_string(), "HTTP 404 for https://example.com");
+        assert_eq!(err.kind(), "http");
+
+        let err = ScraperError::ParseError { ats: AtsType::Lever, company: "Acme".to_string(), detail: "missing field `id`".to_string() };
+        assert_eq!(err.to_string(), "Lever parsing failed for Acme: missing field `id`");
+
+        let err = ScraperError::RateLimited { url: "https://example.com".to_string(), retry_after: Some(30) };
+        assert_eq!(err.to_string(), "Rate limited by https://example.com (retry after 30s)");
+        assert_eq!(err.kind(), "rate_limited");
+
+        let err = ScraperError::CircuitOpen { company: "Acme".to_string() };
+        assert_eq!(err.to_string(), "Acme: SKIPPED (circuit open)");
+        assert_eq!(err.kind(), "circuit_open");
+
+        // Usable anywhere anyhow is
+        let any: anyhow::Error = ScraperError::DbError { detail: "locked".to_string() }.into();
+        assert_eq!(any.to_string(), "Database error: locked");
+    }
+}
@@ -0,0 +1,426 @@
+use anyhow::{Context, Result, bail};
+use arrow_array::builder::{ListBuilder, StringBuilder};
+use arrow_array::{ArrayRef, Float64Array, Int64Array, RecordBatch, StringArray, TimestampMillisecondArray};
+use arrow_schema::{DataType, Field, Schema, TimeUnit};
+use parquet::arrow::ArrowWriter;
+use parquet::basic::Compression;
+use parquet::file::properties::WriterProperties;
+use std::collections::{HashMap, HashSet};
+use std::io::Write;
+use std::sync::{Arc, Mutex};
+
+use crate::models::Job;
+use crate::{DbQuery, JobDb};
+
+/// `--output-format=json|ndjson`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum OutputFormat {
+    /// One pretty-printed array.
+    Json,
+    /// One compact object per line.
+    Ndjson,
+}
+
+impl OutputFormat {
+    pub fn parse(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "ndjson" | "jsonl" => Ok(Self::Ndjson),
+            _ => bail!("Unknown output format '{}' (expected json or ndjson)", s),
+        }
+    }
+}
+
+/// Collects jobs instead of storing them, so `main` can print them to stdout
+/// once every company is done. Clones share the same buffer.
+#[derive(Clone)]
+pub struct JsonStdoutExport {
+    pub format: OutputFormat,
+    pub jobs: Arc<Mutex<Vec<Job>>>,
+}
+
+impl JsonStdoutExport {
+    pub fn new(format: OutputFormat) -> Self {
+        Self { format, jobs: Arc::new(Mutex::new(Vec::new())) }
+    }
+
+    pub fn write_to(&self, mut out: impl Write) -> Result<()> {
+        let jobs = self.jobs.lock().unwrap();
+        match self.format {
+            OutputFormat::Json => {
+                serde_json::to_writer_pretty(&mut out, &*jobs)?;
+                writeln!(out)?;
+            }
+            OutputFormat::Ndjson => {
+                for job in jobs.iter() {
+                    serde_json::to_writer(&mut out, job)?;
+                    writeln!(out)?;
+                }
+            }
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl JobDb for JsonStdoutExport {
+    async fn execute_batch(&self, _queries: &[DbQuery]) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+        // Nothing is stored between runs, so every job is new
+        Ok(HashSet::new())
+    }
+
+    async fn initialize_geo_tables(&self, _countries: &HashMap<String, String>, _regions: &HashMap<String, String>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn insert_jobs(&self, jobs: &[Job]) -> Result<()> {
+        self.jobs.lock().unwrap().extend_from_slice(jobs);
+        Ok(())
+    }
+
+    fn persists_jobs(&self) -> bool {
+        false
+    }
+}
+
+/// File format for `--also-export-csv=` / `--also-export-ndjson=`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FileFormat {
+    Csv,
+    Ndjson,
+}
+
+const CSV_HEADER: [&str; 16] = [
+    "id", "title", "company", "slug", "ats", "url", "apply_url", "location",
+    "city", "region", "country", "country_code", "posted", "seniority_level", "tags",
+    "logo_url",
+];
+
+/// Appends every inserted batch to a CSV or NDJSON file, usually alongside the
+/// real database through `FanOutDb`. The file is truncated when created.
+pub struct FileExport {
+    pub path: String,
+    pub format: FileFormat,
+}
+
+impl FileExport {
+    pub fn create(path: &str, format: FileFormat) -> Result<Self> {
+        let mut file = std::fs::File::create(path)?;
+        if format == FileFormat::Csv {
+            let mut writer = csv::Writer::from_writer(&mut file);
+            writer.write_record(CSV_HEADER)?;
+            writer.flush()?;
+        }
+        Ok(Self { path: path.to_string(), format })
+    }
+
+    fn append(&self, jobs: &[Job]) -> Result<()> {
+        let file = std::fs::OpenOptions::new().append(true).open(&self.path)?;
+        let mut out = std::io::BufWriter::new(file);
+        match self.format {
+            FileFormat::Csv => {
+                let mut writer = csv::Writer::from_writer(&mut out);
+                for job in jobs {
+                    writer.write_record(csv_row(job))?;
+                }
+                writer.flush()?;
+            }
+            FileFormat::Ndjson => {
+                for job in jobs {
+                    serde_json::to_writer(&mut out, job)?;
+                    writeln!(out)?;
+                }
+            }
+        }
+        out.flush()?;
+        Ok(())
+    }
+}
+
+fn csv_row(job: &Job) -> Vec<String> {
+    let opt = |s: &Option<String>| s.clone().unwrap_or_default();
+    vec![
+        job.id.clone(),
+        job.title.clone(),
+        job.company.clone(),
+        job.slug.clone(),
+        serde_label(&job.ats),
+        job.url.clone(),
+        opt(&job.apply_url),
+        job.location.clone(),
+        opt(&job.city),
+        opt(&job.region),
+        opt(&job.country),
+        opt(&job.country_code),
+        job.posted.map(crate::models::format_date).unwrap_or_default(),
+        serde_label(&job.seniority_level),
+        job.tags.join(";"),
+        opt(&job.logo_url),
+    ]
+}
+
+/// An enum's serialized name, e.g. `AtsType::Lever` -> "lever".
+fn serde_label<T: serde::Serialize>(value: &T) -> String {
+    serde_json::to_value(value).ok()
+        .and_then(|v| v.as_str().map(String::from))
+        .unwrap_or_default()
+}
+
+#[async_trait::async_trait]
+impl JobDb for FileExport {
+    async fn execute_batch(&self, _queries: &[DbQuery]) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+        // Each run starts a fresh file
+        Ok(HashSet::new())
+    }
+
+    async fn initialize_geo_tables(&self, _countries: &HashMap<String, String>, _regions: &HashMap<String, String>) -> Result<()> {
+        Ok(())
+    }
+
+    /// The file was created up front; make sure it can still be appended to.
+    async fn health_check(&self) -> Result<()> {
+        std::fs::OpenOptions::new().append(true).open(&self.path)
+            .with_context(|| format!("{} is not writable", self.path))?;
+        Ok(())
+    }
+
+    async fn insert_jobs(&self, jobs: &[Job]) -> Result<()> {
+        self.append(jobs)
+    }
+
+    fn persists_jobs(&self) -> bool {
+        false
+    }
+}
+
+/// `--parquet=`: buffers every inserted job and writes one Parquet file in
+/// `finalize`, since row groups can't be appended to cheaply.
+pub struct ParquetExport {
+    pub path: String,
+    jobs: Mutex<Vec<Job>>,
+}
+
+impl ParquetExport {
+    pub fn new(path: &str) -> Self {
+        Self { path: path.to_string(), jobs: Mutex::new(Vec::new()) }
+    }
+
+    fn write(&self) -> Result<()> {
+        let batch = parquet_batch(&self.jobs.lock().unwrap())?;
+        let file = std::fs::File::create(&self.path)?;
+        let props = WriterProperties::builder().set_compression(Compression::SNAPPY).build();
+        let mut writer = ArrowWriter::try_new(file, batch.schema(), Some(props))?;
+        writer.write(&batch)?;
+        writer.close()?;
+        Ok(())
+    }
+}
+
+/// Scalar `Job` fields as Utf8/Int64/Float64 (enums by their serialized
+/// name, `posted` as a UTC millisecond timestamp), lists as List<Utf8>.
+fn parquet_batch(jobs: &[Job]) -> Result<RecordBatch> {
+    let utf8 = |name: &str, nullable: bool| Field::new(name, DataType::Utf8, nullable);
+    let list = |name: &str| Field::new(name, DataType::List(Arc::new(Field::new("item", DataType::Utf8, true))), false);
+    let strings = |get: fn(&Job) -> &str| -> ArrayRef {
+        Arc::new(jobs.iter().map(|j| Some(get(j))).collect::<StringArray>())
+    };
+    let optional = |get: fn(&Job) -> &Option<String>| -> ArrayRef {
+        Arc::new(jobs.iter().map(|j| get(j).as_deref()).collect::<StringArray>())
+    };
+    let lists = |get: fn(&Job) -> &Vec<String>| -> ArrayRef {
+        let mut builder = ListBuilder::new(StringBuilder::new());
+        for job in jobs {
+            builder.append_value(get(job).iter().map(Some));
+        }
+        Arc::new(builder.finish())
+    };
+
+    let columns: Vec<(Field, ArrayRef)> = vec![
+        (utf8("id", false), strings(|j| &j.id)),
+        (utf8("ats_job_id", false), strings(|j| &j.ats_job_id)),
+        (utf8("title", false), strings(|j| &j.title)),
+        (utf8("description", false), strings(|j| &j.description)),
+        (Field::new("description_length", DataType::Int64, false),
+            Arc::new(jobs.iter().map(|j| j.description_length as i64).collect::<Int64Array>())),
+        (Field::new("description_quality_score", DataType::Float64, false),
+            Arc::new(jobs.iter().map(|j| j.description_quality_score as f64).collect::<Float64Array>())),
+        (utf8("text_description", true), optional(|j| &j.text_description)),
+        (utf8("description_hash", false), strings(|j| &j.description_hash)),
+        (utf8("company", false), strings(|j| &j.company)),
+        (utf8("slug", false), strings(|j| &j.slug)),
+        (utf8("ats", false), Arc::new(jobs.iter().map(|j| Some(serde_label(&j.ats))).collect::<StringArray>())),
+        (utf8("url", false), strings(|j| &j.url)),
+        (utf8("apply_url", true), optional(|j| &j.apply_url)),
+        (utf8("company_url", true), optional(|j| &j.company_url)),
+        (utf8("company_industry", true), optional(|j| &j.company_industry)),
+        (utf8("company_stage", true), optional(|j| &j.company_stage)),
+        (utf8("logo_url", true), optional(|j| &j.logo_url)),
+        (utf8("location", false), strings(|j| &j.location)),
+        (utf8("city", true), optional(|j| &j.city)),
+        (utf8("region", true), optional(|j| &j.region)),
+        (utf8("country", true), optional(|j| &j.country)),
+        (utf8("country_code", true), optional(|j| &j.country_code)),
+        (utf8("metro_area", true), optional(|j| &j.metro_area)),
+        (utf8("preferred_timezone", true), optional(|j| &j.preferred_timezone)),
+        (Field::new("posted", DataType::Timestamp(TimeUnit::Millisecond, Some("UTC".into())), true),
+            Arc::new(jobs.iter().map(|j| j.posted.map(|p| p.timestamp_millis())).collect::<TimestampMillisecondArray>().with_timezone("UTC"))),
+        (utf8("seniority_level", false), Arc::new(jobs.iter().map(|j| Some(serde_label(&j.seniority_level))).collect::<StringArray>())),
+        (utf8("employment_type", true), Arc::new(jobs.iter().map(|j| j.employment_type.as_ref().map(serde_label)).collect::<StringArray>())),
+        (list("departments"), lists(|j| &j.departments)),
+        (utf8("normalized_department", true), optional(|j| &j.normalized_department)),
+        (list("offices"), lists(|j| &j.offices)),
+        (list("tags"), lists(|j| &j.tags)),
+        (list("degree_levels"), lists(|j| &j.degree_levels)),
+        (list("subject_areas"), lists(|j| &j.subject_areas)),
+        (list("benefits"), lists(|j| &j.benefits)),
+    ];
+    let (fields, arrays): (Vec<Field>, Vec<ArrayRef>) = columns.into_iter().unzip();
+    Ok(RecordBatch::try_new(Arc::new(Schema::new(fields)), arrays)?)
+}
+
+#[async_trait::async_trait]
+impl JobDb for ParquetExport {
+    async fn execute_batch(&self, _queries: &[DbQuery]) -> Result<()> {
+        Ok(())
+    }
+
+    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+        // Each run writes a fresh file
+        Ok(HashSet::new())
+    }
+
+    async fn initialize_geo_tables(&self, _countries: &HashMap<String, String>, _regions: &HashMap<String, String>) -> Result<()> {
+        Ok(())
+    }
+
+    /// Nothing is written until `finalize`, so check the path up front.
+    async fn health_check(&self) -> Result<()> {
+        std::fs::File::create(&self.path).with_context(|| format!("{} is not writable", self.path))?;
+        Ok(())
+    }
+
+    async fn insert_jobs(&self, jobs: &[Job]) -> Result<()> {
+        self.jobs.lock().unwrap().extend_from_slice(jobs);
+        Ok(())
+    }
+
+    async fn finalize(&self) -> Result<()> {
+        self.write().with_context(|| format!("Failed to write {}", self.path))
+    }
+
+    fn persists_jobs(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_stdout_export_formats() {
+        assert_eq!(OutputFormat::parse("NDJSON").unwrap(), OutputFormat::Ndjson);
+        assert!(OutputFormat::parse("csv").is_err());
+
+        let export = JsonStdoutExport::new(OutputFormat::Ndjson);
+        export.clone().insert_jobs(&[Job::fixture("lever-1"), Job::fixture("lever-2")]).await.unwrap();
+        let mut out = Vec::new();
+        export.write_to(&mut out).unwrap();
+        let lines: Vec<Job> = String::from_utf8(out).unwrap().lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(lines[1].id, "lever-2");
+
+        let export = JsonStdoutExport { format: OutputFormat::Json, ..export };
+        let mut out = Vec::new();
+        export.write_to(&mut out).unwrap();
+        let jobs: Vec<Job> = serde_json::from_slice(&out).unwrap();
+        assert_eq!(jobs.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn test_file_export_appends_batches() {
+        let path = std::env::temp_dir().join(format!("zapply_export_{}.csv", std::process::id()));
+        let path = path.to_str().unwrap();
+        let export = FileExport::create(path, FileFormat::Csv).unwrap();
+        let mut tagged = Job::fixture("lever-1");
+        tagged.title = "Intern, Platform".to_string();
+        tagged.tags = vec!["Go".to_string(), "Rust".to_string()];
+        export.insert_jobs(&[tagged]).await.unwrap();
+        export.insert_jobs(&[Job::fixture("lever-2")]).await.unwrap();
+
+        let mut reader = csv::Reader::from_path(path).unwrap();
+        assert_eq!(reader.headers().unwrap().len(), CSV_HEADER.len());
+        let rows: Vec<csv::StringRecord> = reader.records().map(|r| r.unwrap()).collect();
+        assert_eq!(rows.len(), 2);
+        assert_eq!(&rows[0][1], "Intern, Platform");
+        assert_eq!(&rows[0][4], "lever");
+        assert_eq!(&rows[0][14], "Go;Rust");
+        assert_eq!(&rows[1][0], "lever-2");
+
+        assert!(export.health_check().await.is_ok());
+
+        // Re-creating starts over
+        FileExport::create(path, FileFormat::Ndjson).unwrap();
+        assert_eq!(std::fs::read_to_string(path).unwrap(), "");
+        std::fs::remove_file(path).unwrap();
+        assert!(export.health_check().await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_parquet_export_round_trips() {
+        use arrow_array::{Array, ListArray};
+        use parquet::arrow::arrow_reader::ParquetRecordBatchReaderBuilder;
+
+        let path = std::env::temp_dir().join(format!("zapply_export_{}.parquet", std::process::id()));
+        let path = path.to_str().unwrap();
+        let export = ParquetExport::new(path);
+        export.health_check().await.unwrap();
+        let mut tagged = Job::fixture("lever-1");
+        tagged.tags = vec!["Go".to_string(), "Rust".to_string()];
+        tagged.city = Some("Sydney".to_string());
+        tagged.posted = Some(chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap());
+        export.insert_jobs(&[tagged]).await.unwrap();
+        export.insert_jobs(&[Job::fixture("lever-2")]).await.unwrap();
+        export.finalize().await.unwrap();
+
+        let reader = ParquetRecordBatchReaderBuilder::try_new(std::fs::File::open(path).unwrap()).unwrap().build().unwrap();
+        let batches: Vec<RecordBatch> = reader.map(|b| b.unwrap()).collect();
+        assert_eq!(batches.len(), 1);
+        let batch = &batches[0];
+        assert_eq!(batch.num_rows(), 2);
+        assert_eq!(batch.num_columns(), 34);
+
+        let column = |name: &str| batch.column_by_name(name).unwrap().clone();
+        let ids = column("id");
+        let ids = ids.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(ids.value(1), "lever-2");
+        let ats = column("ats");
+        assert_eq!(ats.as_any().downcast_ref::<StringArray>().unwrap().value(0), "lever");
+        let city = column("city");
+        let city = city.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!(city.value(0), "Sydney");
+        assert!(city.is_null(1));
+        let posted = column("posted");
+        let posted = posted.as_any().downcast_ref::<TimestampMillisecondArray>().unwrap();
+        assert_eq!(posted.value(0), 1_700_000_000_000);
+        assert!(posted.is_null(1));
+        let tags = column("tags");
+        let tags = tags.as_any().downcast_ref::<ListArray>().unwrap();
+        let first = tags.value(0);
+        let first = first.as_any().downcast_ref::<StringArray>().unwrap();
+        assert_eq!((first.value(0), first.value(1)), ("Go", "Rust"));
+        assert_eq!(tags.value(1).len(), 0);
+        std::fs::remove_file(path).unwrap();
+    }
+}
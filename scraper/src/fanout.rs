@@ -0,0 +1,154 @@
+use anyhow::{Result, bail};
+use futures::future::join_all;
+use std::collections::{HashMap, HashSet};
+use tracing::warn;
+
+use crate::models::Job;
+use crate::{DbQuery, JobDb};
+
+/// Writes to several backends at once, e.g. D1 plus `--also-export-csv=`.
+/// A failing backend is logged and skipped; only a write that fails
+/// everywhere is an error.
+pub struct FanOutDb {
+    backends: Vec<Box<dyn JobDb>>,
+}
+
+impl FanOutDb {
+    pub fn new(backends: Vec<Box<dyn JobDb>>) -> Self {
+        Self { backends }
+    }
+
+    /// Logs each failure and returns the successes, or an error if there were none.
+    fn collect<T>(&self, op: &str, results: Vec<Result<T>>) -> Result<Vec<T>> {
+        let mut ok = Vec::with_capacity(results.len());
+        for (i, result) in results.into_iter().enumerate() {
+            match result {
+                Ok(value) => ok.push(value),
+                Err(e) => warn!("Backend {} failed during {}: {:#}", i, op, e),
+            }
+        }
+        if ok.is_empty() && !self.backends.is_empty() {
+            bail!("Every backend failed during {}", op);
+        }
+        Ok(ok)
+    }
+}
+
+#[async_trait::async_trait]
+impl JobDb for FanOutDb {
+    async fn execute_batch(&self, queries: &[DbQuery]) -> Result<()> {
+        let results = join_all(self.backends.iter().map(|db| db.execute_batch(queries))).await;
+        self.collect("execute_batch", results).map(|_| ())
+    }
+
+    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+        let results = join_all(self.backends.iter().map(|db| db.get_existing_ids())).await;
+        Ok(self.collect("get_existing_ids", results)?.into_iter().flatten().collect())
+    }
+
+    async fn get_job_fingerprints(&self) -> Result<HashMap<String, String>> {
+        let results = join_all(self.backends.iter().map(|db| db.get_job_fingerprints())).await;
+        Ok(self.collect("get_job_fingerprints", results)?.into_iter().flatten().collect())
+    }
+
+    async fn initialize_geo_tables(&self, countries: &HashMap<String, String>, regions: &HashMap<String, String>) -> Result<()> {
+        let results = join_all(self.backends.iter().map(|db| db.initialize_geo_tables(countries, regions))).await;
+        self.collect("initialize_geo_tables", results).map(|_| ())
+    }
+
+    async fn health_check(&self) -> Result<()> {
+        let results = join_all(self.backends.iter().map(|db| db.health_check())).await;
+        self.collect("health_check", results).map(|_| ())
+    }
+
+    async fn finalize(&self) -> Result<()> {
+        let results = join_all(self.backends.iter().map(|db| db.finalize())).await;
+        self.collect("finalize", results).map(|_| ())
+    }
+
+    /// True as soon as one backend is a database; exports alongside it don't count.
+    fn persists_jobs(&self) -> bool {
+        self.backends.iter().any(|db| db.persists_jobs())
+    }
+
+    /// Each backend builds its own writes; exports never see SQL.
+    async fn insert_jobs(&self, jobs: &[Job]) -> Result<()> {
+        let results = join_all(self.backends.iter().map(|db| db.insert_jobs(jobs))).await;
+        self.collect("insert_jobs", results).map(|_| ())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::{Arc, Mutex};
+
+    /// Records inserted ids; `fail` makes every call error.
+    struct MemoryDb {
+        existing: HashSet<String>,
+        inserted: Arc<Mutex<Vec<String>>>,
+        fail: bool,
+    }
+
+    impl MemoryDb {
+        fn new(existing: &[&str], fail: bool) -> (Self, Arc<Mutex<Vec<String>>>) {
+            let inserted = Arc::new(Mutex::new(Vec::new()));
+            let db = Self { existing: existing.iter().map(|s| s.to_string()).collect(), inserted: inserted.clone(), fail };
+            (db, inserted)
+        }
+
+        fn check(&self) -> Result<()> {
+            if self.fail { bail!("backend down") }
+            Ok(())
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl JobDb for MemoryDb {
+        async fn execute_batch(&self, _queries: &[DbQuery]) -> Result<()> {
+            self.check()
+        }
+        async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+            self.check()?;
+            Ok(self.existing.iter().cloned().collect())
+        }
+        async fn initialize_geo_tables(&self, _: &HashMap<String, String>, _: &HashMap<String, String>) -> Result<()> {
+            self.check()
+        }
+        async fn insert_jobs(&self, jobs: &[Job]) -> Result<()> {
+            self.check()?;
+            self.inserted.lock().unwrap().extend(jobs.iter().map(|j| j.id.clone()));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fan_out_writes_everywhere_and_tolerates_failures() {
+        let (primary, primary_rows) = MemoryDb::new(&["lever-1"], false);
+        let (broken, _) = MemoryDb::new(&[], true);
+        let (export, export_rows) = MemoryDb::new(&["lever-2"], false);
+        let db = FanOutDb::new(vec![Box::new(primary), Box::new(broken), Box::new(export)]);
+
+        let ids = db.get_existing_ids().await.unwrap();
+        assert_eq!(ids, HashSet::from(["lever-1".to_string(), "lever-2".to_string()]));
+
+        db.insert_jobs(&[Job::fixture("lever-3")]).await.unwrap();
+        assert_eq!(*primary_rows.lock().unwrap(), vec!["lever-3"]);
+        assert_eq!(*export_rows.lock().unwrap(), vec!["lever-3"]);
+
+        let (down, _) = MemoryDb::new(&[], true);
+        let all_down = FanOutDb::new(vec![Box::new(down)]);
+        assert!(all_down.insert_jobs(&[Job::fixture("lever-4")]).await.is_err());
+    }
+
+    #[test]
+    fn test_fan_out_persists_only_with_a_database() {
+        let (db, _) = MemoryDb::new(&[], false);
+        let path = std::env::temp_dir().join(format!("zapply_fanout_{}.ndjson", std::process::id()));
+        let path = path.to_str().unwrap();
+        let export = || Box::new(crate::export::FileExport::create(path, crate::export::FileFormat::Ndjson).unwrap());
+        assert!(FanOutDb::new(vec![Box::new(db), export()]).persists_jobs());
+        assert!(!FanOutDb::new(vec![export()]).persists_jobs());
+        std::fs::remove_file(path).unwrap();
+    }
+}
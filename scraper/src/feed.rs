@@ -0,0 +1,182 @@
+use anyhow::{Context, Result};
+use rss::{Category, Channel, Guid, Item};
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io::BufReader;
+
+use crate::config::Config;
+use crate::models::Job;
+use crate::{DbQuery, JobDb};
+
+const FEED_DESCRIPTION_CHARS: usize = 500;
+
+/// Writes jobs to an RSS 2.0 file (`--rss=feed.xml`) instead of a database.
+/// New jobs are added to the top of the feed; existing items are kept.
+pub struct RssExport {
+    pub path: String,
+    pub title: String,
+    pub link: String,
+    pub description: String,
+}
+
+impl RssExport {
+    pub fn new(path: &str, config: &Config) -> Self {
+        Self {
+            path: path.to_string(),
+            title: config.rss_title.clone(),
+            link: config.rss_link.clone(),
+            description: config.rss_description.clone(),
+        }
+    }
+
+    fn load_channel(&self) -> Result<Channel> {
+        if !std::path::Path::new(&self.path).exists() {
+            let mut channel = Channel::default();
+            channel.set_title(self.title.as_str());
+            channel.set_link(self.link.as_str());
+            channel.set_description(self.description.as_str());
+            return Ok(channel);
+        }
+        let file = fs::File::open(&self.path)?;
+        Channel::read_from(BufReader::new(file)).context(format!("Invalid RSS feed {}", self.path))
+    }
+
+    fn job_item(job: &Job) -> Item {
+        let mut item = Item::default();
+        item.set_title(format!("{} at {}", job.title, job.company));
+        item.set_link(job.url.clone());
+        item.set_description(truncate_chars(&job.description, FEED_DESCRIPTION_CHARS));
+        if let Some(posted) = job.posted {
+            item.set_pub_date(posted.to_rfc2822());
+        }
+        item.set_categories(job.tags.iter().map(|t| {
+            let mut category = Category::default();
+            category.set_name(t.as_str());
+            category
+        }).collect::<Vec<_>>());
+
+        // Job ids aren't URLs, so the guid must not claim to be a permalink
+        let mut guid = Guid::default();
+        guid.set_value(job.id.as_str());
+        guid.set_permalink(false);
+        item.set_guid(guid);
+        item
+    }
+}
+
+fn truncate_chars(s: &str, max: usize) -> String {
+    match s.char_indices().nth(max) {
+        Some((idx, _)) => format!("{}…", &s[..idx]),
+        None => s.to_string(),
+    }
+}
+
+#[async_trait::async_trait]
+impl JobDb for RssExport {
+    async fn execute_batch(&self, _queries: &[DbQuery]) -> Result<()> {
+        // Nothing to run: insert_jobs writes the feed directly
+        Ok(())
+    }
+
+    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+        if !std::path::Path::new(&self.path).exists() {
+            return Ok(HashSet::new());
+        }
+        let channel = self.load_channel()?;
+        Ok(channel.items().iter()
+            .filter_map(|i| i.guid().map(|g| g.value().to_string()))
+            .collect())
+    }
+
+    async fn initialize_geo_tables(&self, _countries: &HashMap<String, String>, _regions: &HashMap<String, String>) -> Result<()> {
+        Ok(())
+    }
+
+    async fn insert_jobs(&self, jobs: &[Job]) -> Result<()> {
+        if jobs.is_empty() { return Ok(()); }
+
+        let mut channel = self.load_channel()?;
+        let existing: HashSet<String> = channel.items().iter()
+            .filter_map(|i| i.guid().map(|g| g.value().to_string()))
+            .collect();
+
+        let mut items: Vec<Item> = jobs.iter()
+            .filter(|j| !existing.contains(&j.id))
+            .map(Self::job_item)
+            .collect();
+        items.extend(channel.items().iter().cloned());
+        channel.set_items(items);
+
+        let tmp_path = format!("{}.tmp", self.path);
+        let file = fs::File::create(&tmp_path)?;
+        channel.pretty_write_to(file, b' ', 2)?;
+        fs::rename(&tmp_path, &self.path)?;
+        Ok(())
+    }
+
+    fn persists_jobs(&self) -> bool {
+        false
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AtsType;
+    use chrono::{DateTime, Utc};
+
+    fn job(id: &str, title: &str, tags: &[&str]) -> Job {
+        Job {
+            title: title.to_string(),
+            description: format!("<p>{}</p>", "x".repeat(600)),
+            company: "Acme & Co".to_string(),
+            ats: AtsType::Greenhouse,
+            url: format!("https://example.com/jobs/{}", id),
+            posted: DateTime::parse_from_rfc3339("2025-01-15T09:30:00Z").ok().map(|d| d.with_timezone(&Utc)),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Job::fixture(id)
+        }
+    }
+
+    fn export(path: &str) -> RssExport {
+        RssExport {
+            path: path.to_string(),
+            title: "Test Feed".to_string(),
+            link: "https://example.com".to_string(),
+            description: "Test jobs".to_string(),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_rss_feed_well_formed() {
+        let path = std::env::temp_dir().join(format!("zapply_feed_{}.xml", std::process::id()));
+        let path = path.to_str().unwrap();
+        let feed = export(path);
+
+        feed.insert_jobs(&[job("greenhouse-1", "Intern <Backend>", &["Rust", "Remote"])]).await.unwrap();
+        feed.insert_jobs(&[job("greenhouse-2", "Graduate Engineer", &[]), job("greenhouse-1", "Intern <Backend>", &[])]).await.unwrap();
+
+        let xml = fs::read_to_string(path).unwrap();
+        assert!(xml.contains("<rss version=\"2.0\""));
+        assert!(xml.contains("Intern &lt;Backend&gt; at Acme &amp; Co"));
+
+        let channel = Channel::read_from(xml.as_bytes()).unwrap();
+        assert_eq!(channel.title(), "Test Feed");
+        assert_eq!(channel.items().len(), 2);
+
+        let newest = &channel.items()[0];
+        assert_eq!(newest.guid().unwrap().value(), "greenhouse-2");
+        let first = &channel.items()[1];
+        assert_eq!(first.guid().unwrap().value(), "greenhouse-1");
+        assert!(!first.guid().unwrap().is_permalink());
+        assert_eq!(first.link(), Some("https://example.com/jobs/greenhouse-1"));
+        assert_eq!(first.pub_date(), Some("Wed, 15 Jan 2025 09:30:00 +0000"));
+        assert_eq!(first.categories().iter().map(|c| c.name()).collect::<Vec<_>>(), vec!["Rust", "Remote"]);
+        assert!(first.description().unwrap().chars().count() <= FEED_DESCRIPTION_CHARS + 1);
+
+        let ids = feed.get_existing_ids().await.unwrap();
+        assert_eq!(ids, HashSet::from(["greenhouse-1".to_string(), "greenhouse-2".to_string()]));
+
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,194 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs;
+
+use crate::error::ScraperError;
+use crate::models::CompanyEntry;
+use crate::stats::percentile;
+
+/// How far an ATS's zero-job rate may rise over the baseline before it is
+/// flagged, in percentage points.
+const ZERO_JOB_RATE_TOLERANCE: f64 = 0.10;
+
+/// Per-company results collected during a run with `--health-report`.
+#[derive(Default)]
+pub struct HealthRecorder {
+    per_ats: BTreeMap<String, AtsSamples>,
+}
+
+#[derive(Default)]
+struct AtsSamples {
+    companies: usize,
+    zero_jobs: usize,
+    http_errors: usize,
+    parse_errors: usize,
+    jobs: usize,
+    response_ms: Vec<usize>,
+}
+
+impl HealthRecorder {
+    /// `result` holds the number of jobs the board parsed to, before any
+    /// filtering. `elapsed_ms` covers the whole company, board fetch through
+    /// enrichment. Companies skipped by an open circuit say nothing about
+    /// their parser and are left out.
+    pub fn record_company(&mut self, company: &CompanyEntry, result: Result<usize, &ScraperError>, elapsed_ms: u64) {
+        if let Err(ScraperError::CircuitOpen { .. }) = result {
+            return;
+        }
+        let ats = serde_json::to_string(&company.ats_type).unwrap_or_default().trim_matches('"').to_string();
+        let entry = self.per_ats.entry(ats).or_default();
+        entry.companies += 1;
+        entry.response_ms.push(elapsed_ms as usize);
+        match result {
+            Ok(0) => entry.zero_jobs += 1,
+            Ok(jobs) => entry.jobs += jobs,
+            Err(ScraperError::ParseError { .. }) => entry.parse_errors += 1,
+            Err(ScraperError::HttpError { .. } | ScraperError::RateLimited { .. }) => entry.http_errors += 1,
+            Err(_) => {}
+        }
+    }
+
+    pub fn report(&self, generated_at: DateTime<Utc>) -> AtsHealthReport {
+        let per_ats = self.per_ats.iter().map(|(ats, s)| {
+            let mut response_ms = s.response_ms.clone();
+            response_ms.sort_unstable();
+            (ats.clone(), AtsHealth {
+                companies: s.companies,
+                zero_jobs: s.zero_jobs,
+                http_errors: s.http_errors,
+                parse_errors: s.parse_errors,
+                avg_jobs_per_company: s.jobs as f64 / s.companies.max(1) as f64,
+                p50_response_ms: percentile(&response_ms, 50),
+                p95_response_ms: percentile(&response_ms, 95),
+            })
+        }).collect();
+        AtsHealthReport { generated_at, per_ats, degraded: vec![] }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize, PartialEq)]
+pub struct AtsHealth {
+    pub companies: usize,
+    /// Companies whose board was fetched fine but parsed to no jobs at all.
+    pub zero_jobs: usize,
+    /// Includes rate limiting and requests that got no response.
+    pub http_errors: usize,
+    pub parse_errors: usize,
+    /// Parsed jobs, before the keyword and cutoff filters.
+    pub avg_jobs_per_company: f64,
+    pub p50_response_ms: usize,
+    pub p95_response_ms: usize,
+}
+
+impl AtsHealth {
+    pub fn zero_job_rate(&self) -> f64 {
+        self.zero_jobs as f64 / self.companies.max(1) as f64
+    }
+}
+
+/// Per-ATS parser health after a full scrape, written to `health_report.json`
+/// with `--health-report`. Copying a good report to `health_baseline.json`
+/// makes later runs flag ATS types whose zero-job rate has grown.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct AtsHealthReport {
+    pub generated_at: DateTime<Utc>,
+    pub per_ats: BTreeMap<String, AtsHealth>,
+    /// ATS types whose zero-job rate rose more than 10 points over the baseline.
+    #[serde(default)]
+    pub degraded: Vec<String>,
+}
+
+impl AtsHealthReport {
+    /// A missing baseline means nothing to compare against.
+    pub fn load_baseline(path: &str) -> Result<Option<Self>> {
+        if !std::path::Path::new(path).exists() {
+            return Ok(None);
+        }
+        let content = fs::read_to_string(path)?;
+        serde_json::from_str(&content).map(Some).context(format!("Invalid {}", path))
+    }
+
+    /// Fills `degraded`. ATS types missing from the baseline aren't flagged.
+    pub fn compare(&mut self, baseline: &AtsHealthReport) {
+        self.degraded = self.per_ats.iter()
+            .filter(|(ats, health)| baseline.per_ats.get(*ats)
+                .is_some_and(|base| health.zero_job_rate() - base.zero_job_rate() > ZERO_JOB_RATE_TOLERANCE))
+            .map(|(ats, _)| ats.clone())
+            .collect();
+    }
+
+    pub fn write_to(&self, path: &str) -> Result<()> {
+        fs::write(path, serde_json::to_string_pretty(self)?).with_context(|| format!("Failed to write {}", path))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AtsType;
+
+    fn company(ats_type: AtsType) -> CompanyEntry {
+        CompanyEntry { name: "Acme".to_string(), slug: "acme".to_string(), ats_type, ..Default::default() }
+    }
+
+    #[test]
+    fn test_report_per_ats() {
+        let mut recorder = HealthRecorder::default();
+        let lever = company(AtsType::Lever);
+        recorder.record_company(&lever, Ok(4), 100);
+        recorder.record_company(&lever, Ok(0), 300);
+        recorder.record_company(&lever, Err(&ScraperError::HttpError { status: 503, url: "u".to_string() }), 200);
+        recorder.record_company(&lever, Err(&ScraperError::ParseError { ats: AtsType::Lever, company: "Acme".to_string(), detail: "d".to_string() }), 900);
+        recorder.record_company(&company(AtsType::Ashby), Ok(2), 50);
+        recorder.record_company(&lever, Err(&ScraperError::CircuitOpen { company: "Acme".to_string() }), 0);
+
+        let report = recorder.report(Utc::now());
+        let health = &report.per_ats["lever"];
+        assert_eq!(health, &AtsHealth {
+            companies: 4,
+            zero_jobs: 1,
+            http_errors: 1,
+            parse_errors: 1,
+            avg_jobs_per_company: 1.0,
+            p50_response_ms: 200,
+            p95_response_ms: 900,
+        });
+        assert_eq!(report.per_ats["ashby"].companies, 1);
+    }
+
+    #[test]
+    fn test_compare_flags_zero_job_rate_increase() {
+        let mut recorder = HealthRecorder::default();
+        for jobs in [0, 0, 3, 3, 3] {
+            recorder.record_company(&company(AtsType::Greenhouse), Ok(jobs), 10);
+            recorder.record_company(&company(AtsType::Lever), Ok(jobs), 10);
+        }
+        recorder.record_company(&company(AtsType::Workable), Ok(0), 10);
+        let baseline = recorder.report(Utc::now());
+
+        let mut recorder = HealthRecorder::default();
+        // Greenhouse goes from 40% to 80% zero-job companies, Lever to 50%
+        for jobs in [0, 0, 0, 0, 3] {
+            recorder.record_company(&company(AtsType::Greenhouse), Ok(jobs), 10);
+        }
+        for jobs in [0, 0, 0, 3, 3, 3] {
+            recorder.record_company(&company(AtsType::Lever), Ok(jobs), 10);
+        }
+        recorder.record_company(&company(AtsType::Ashby), Ok(0), 10);
+        let mut report = recorder.report(Utc::now());
+        report.compare(&baseline);
+        assert_eq!(report.degraded, vec!["greenhouse".to_string()]);
+
+        // The written report works as the next run's baseline
+        let path = std::env::temp_dir().join(format!("zapply_health_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        report.write_to(path).unwrap();
+        let loaded = AtsHealthReport::load_baseline(path).unwrap().unwrap();
+        assert_eq!(loaded.per_ats, report.per_ats);
+        assert_eq!(loaded.degraded, vec!["greenhouse".to_string()]);
+        std::fs::remove_file(path).unwrap();
+        assert!(AtsHealthReport::load_baseline(path).unwrap().is_none());
+    }
+}
@@ -0,0 +1,302 @@
+//! End-to-end `process_company` runs against a local `wiremock` server serving
+//! the golden-test fixtures from `tests/fixtures/`.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+
+use chrono::{TimeZone, Utc};
+use once_cell::sync::Lazy;
+use serde_json::json;
+use wiremock::matchers::{body_json, method, path, path_regex, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+use crate::circuit::CircuitBreaker;
+use crate::country_filter::CountryFilter;
+use crate::normalization::DepartmentNormalizer;
+use crate::config::Config;
+use crate::error::ScraperError;
+use crate::location::LocationEngine;
+use crate::metrics::Metrics;
+use crate::ratelimit::DomainRateLimiter;
+use crate::models::{AtsType, CompanyEntry, HttpMethod, Job, SeniorityLevel};
+use crate::tag::{EducationDetector, TagEngine};
+use crate::{Cutoffs, Enrichment, ScrapedCompany, process_company};
+
+static TAG_ENGINE: Lazy<Arc<TagEngine>> = Lazy::new(|| Arc::new(TagEngine::new()));
+
+/// A handful of real GeoNames rows from `tests/fixtures/geo/`.
+static LOCATION_ENGINE: Lazy<Arc<LocationEngine>> = Lazy::new(|| {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/geo");
+    let mut engine = LocationEngine::new();
+    engine.load_geonames(
+        &format!("{}/cities15000.txt", dir),
+        &format!("{}/admin1CodesASCII.txt", dir),
+        &format!("{}/countryInfo.txt", dir),
+    ).unwrap();
+    Arc::new(engine)
+});
+
+fn fixture(name: &str) -> String {
+    let path = format!("{}/tests/fixtures/{}_sample.json", env!("CARGO_MANIFEST_DIR"), name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e))
+}
+
+fn company(ats_type: AtsType, api_url: String) -> CompanyEntry {
+    CompanyEntry {
+        name: "Acme".to_string(),
+        ats_type,
+        slug: "acme".to_string(),
+        api_url,
+        domain: Some("acme.com".to_string()),
+        ..Default::default()
+    }
+}
+
+/// Mounts `response` at `route` on a fresh server and scrapes it, sorted by id
+/// since enrichment finishes out of order.
+async fn scrape(ats_type: AtsType, route: &str, response: ResponseTemplate) -> Result<Vec<Job>, ScraperError> {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path(route))
+        .respond_with(response)
+        .expect(1)
+        .mount(&server)
+        .await;
+    run(company(ats_type, format!("{}{}", server.uri(), route))).await
+}
+
+async fn run(company: CompanyEntry) -> Result<Vec<Job>, ScraperError> {
+    run_with_enrichment(company, Enrichment { enabled: true, concurrency: 10 }).await
+}
+
+async fn run_with_enrichment(company: CompanyEntry, enrichment: Enrichment) -> Result<Vec<Job>, ScraperError> {
+    let limiter = DomainRateLimiter::new(Config::load().unwrap().max_requests_per_domain);
+    run_with_limiter(company, enrichment, &limiter, &CircuitBreaker::new(3, 7)).await
+}
+
+async fn run_with_limiter(company: CompanyEntry, enrichment: Enrichment, limiter: &DomainRateLimiter, breaker: &CircuitBreaker) -> Result<Vec<Job>, ScraperError> {
+    let config = Config::load().unwrap();
+    // Fixtures are dated 2025, so nothing may be dropped as too old
+    let epoch = Utc.with_ymd_and_hms(2000, 1, 1, 0, 0, 0).unwrap();
+    let result = process_company(
+        &reqwest::Client::new(),
+        &company,
+        &config.keyword_regex().unwrap(),
+        &config.negative_keyword_regex().unwrap(),
+        TAG_ENGINE.clone(),
+        Arc::new(EducationDetector::new()),
+        Arc::new(DepartmentNormalizer::new()),
+        LOCATION_ENGINE.clone(),
+        &Metrics::default(),
+        Cutoffs { default: epoch, eoi: epoch },
+        breaker,
+        limiter,
+        &CountryFilter::default(),
+        None,
+        enrichment,
+        None,
+    ).await;
+    result.map(|ScrapedCompany { mut jobs, .. }| {
+        jobs.sort_by(|a, b| a.id.cmp(&b.id));
+        jobs
+    })
+}
+
+fn json_fixture(name: &str) -> ResponseTemplate {
+    ResponseTemplate::new(200).set_body_raw(fixture(name), "application/json")
+}
+
+#[tokio::test]
+async fn test_greenhouse_end_to_end() {
+    let jobs = scrape(AtsType::Greenhouse, "/v1/boards/acme/jobs", json_fixture("greenhouse")).await.unwrap();
+    assert_eq!(jobs.len(), 2);
+
+    let intern = &jobs[0];
+    assert_eq!(intern.id, "greenhouse-7012345002");
+    assert_eq!(intern.company_url.as_deref(), Some("acme.com"));
+    assert_eq!(intern.city.as_deref(), Some("San Francisco"));
+    assert_eq!(intern.region.as_deref(), Some("California"));
+    assert_eq!(intern.country_code.as_deref(), Some("US"));
+    assert_eq!(intern.seniority_level, SeniorityLevel::Intern);
+    assert!(intern.tags.contains(&"Python".to_string()), "{:?}", intern.tags);
+    assert!(intern.tags.contains(&"Education Optional".to_string()));
+
+    let graduate = &jobs[1];
+    assert_eq!(graduate.city.as_deref(), Some("London"));
+    assert_eq!(graduate.country.as_deref(), Some("United Kingdom"));
+    assert_eq!(graduate.seniority_level, SeniorityLevel::EntryLevel);
+    assert!(!graduate.description.contains("script"));
+}
+
+#[tokio::test]
+async fn test_lever_end_to_end() {
+    let jobs = scrape(AtsType::Lever, "/v0/postings/acme", json_fixture("lever")).await.unwrap();
+    assert_eq!(jobs.len(), 2);
+
+    let intern = jobs.iter().find(|j| j.title == "Backend Engineering Intern").unwrap();
+    assert_eq!(intern.departments, vec!["Platform".to_string()]);
+    assert_eq!(intern.city.as_deref(), Some("Toronto"));
+    assert_eq!(intern.country_code.as_deref(), Some("CA"));
+    assert!(intern.tags.contains(&"Rust".to_string()));
+}
+
+#[tokio::test]
+async fn test_ashby_end_to_end() {
+    let jobs = scrape(AtsType::Ashby, "/posting-api/job-board/acme", json_fixture("ashby")).await.unwrap();
+    assert_eq!(jobs.len(), 2);
+
+    let intern = jobs.iter().find(|j| j.title == "Machine Learning Intern").unwrap();
+    // "New York" isn't a GeoNames city name, so only the region resolves
+    assert_eq!(intern.city, None);
+    assert_eq!(intern.region.as_deref(), Some("New York"));
+    assert_eq!(intern.country_code.as_deref(), Some("US"));
+    assert!(intern.tags.contains(&"PyTorch".to_string()));
+
+    let remote = jobs.iter().find(|j| j.title == "Associate Solutions Engineer").unwrap();
+    assert!(remote.tags.contains(&"Remote".to_string()), "{:?}", remote.tags);
+}
+
+#[tokio::test]
+async fn test_recruitee_end_to_end() {
+    let jobs = scrape(AtsType::Recruitee, "/api/offers", json_fixture("recruitee")).await.unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].id, "recruitee-1645321");
+    assert_eq!(jobs[0].city.as_deref(), Some("Amsterdam"));
+    assert_eq!(jobs[0].country_code.as_deref(), Some("NL"));
+    assert!(jobs[0].tags.contains(&"TypeScript".to_string()));
+}
+
+#[tokio::test]
+async fn test_http_errors_are_categorized() {
+    let err = scrape(AtsType::Lever, "/v0/postings/acme", ResponseTemplate::new(503)).await.unwrap_err();
+    assert!(matches!(err, ScraperError::HttpError { status: 503, .. }), "{}", err);
+
+    let err = scrape(AtsType::Lever, "/v0/postings/acme", ResponseTemplate::new(429).insert_header("Retry-After", "30")).await.unwrap_err();
+    assert!(matches!(err, ScraperError::RateLimited { retry_after: Some(30), .. }), "{}", err);
+
+    let err = scrape(AtsType::Lever, "/v0/postings/acme", ResponseTemplate::new(200).set_body_string("<html>")).await.unwrap_err();
+    assert_eq!(err.kind(), "parse");
+}
+
+#[tokio::test]
+async fn test_post_request_body() {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/v0/postings/acme"))
+        .and(body_json(json!({"limit": 20, "offset": null, "searchText": ""})))
+        .respond_with(json_fixture("lever"))
+        .expect(1)
+        .mount(&server)
+        .await;
+
+    let company = CompanyEntry {
+        http_method: Some(HttpMethod::Post),
+        request_body: Some(json!({"limit": 20, "offset": "{cursor}", "searchText": ""})),
+        ..company(AtsType::Lever, format!("{}/v0/postings/acme", server.uri()))
+    };
+    // Later pages substitute the cursor
+    assert_eq!(company.request_body_for(Some("20")).unwrap()["offset"], "20");
+    let jobs = run(company).await.unwrap();
+    assert_eq!(jobs.len(), 2);
+}
+
+#[tokio::test]
+async fn test_recruitee_pagination() {
+    let server = MockServer::start().await;
+    let offer = |id: usize| json!({
+        "id": id,
+        "title": format!("Trainee Developer {}", id),
+        "careers_url": format!("https://acme.recruitee.com/o/{}", id),
+        "description": "<p>Learn TypeScript.</p>",
+        "location": "Amsterdam, Netherlands",
+        "published_at": "2025-02-07 09:30:00 UTC",
+    });
+    for page in 1..=3 {
+        let ids = (page - 1) * 100 + 1..=page * 100;
+        Mock::given(method("GET")).and(path("/api/offers"))
+            .and(query_param("page[size]", "100")).and(query_param("page[number]", page.to_string()))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+                "offers": ids.map(offer).collect::<Vec<_>>(),
+                "meta": {"current_page": page, "total_pages": 3},
+            })))
+            .expect(1).mount(&server).await;
+    }
+
+    let jobs = run(company(AtsType::Recruitee, format!("{}/api/offers", server.uri()))).await.unwrap();
+    assert_eq!(jobs.len(), 300);
+    let ids: HashSet<&str> = jobs.iter().map(|j| j.id.as_str()).collect();
+    assert!((1..=300).all(|id| ids.contains(format!("recruitee-{}", id).as_str())));
+}
+
+#[tokio::test]
+async fn test_no_enrich_skips_detail_requests() {
+    let server = MockServer::start().await;
+    let board = json!([{
+        "id": "b1",
+        "name": "Software Engineering Intern",
+        "url": format!("{}/p/b1", server.uri()),
+        "published_date": "2025-02-07T09:30:00Z",
+    }]);
+    Mock::given(method("GET")).and(path("/json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(board))
+        .expect(1).mount(&server).await;
+    Mock::given(method("GET")).and(path("/p/b1"))
+        .respond_with(ResponseTemplate::new(200).set_body_string(
+            r#"<script type="application/ld+json">{"description": "<p>Write Rust.</p>"}</script>"#,
+        ))
+        .expect(0).mount(&server).await;
+
+    let jobs = run_with_enrichment(company(AtsType::Breezy, format!("{}/json", server.uri())), Enrichment { enabled: false, concurrency: 10 }).await.unwrap();
+    assert_eq!(jobs.len(), 1);
+    assert_eq!(jobs[0].description, "");
+}
+
+#[tokio::test]
+async fn test_enrichment_concurrency_limits_detail_requests() {
+    let server = MockServer::start().await;
+    let board: Vec<_> = (1..=3).map(|i| json!({
+        "id": format!("b{}", i),
+        "name": format!("Software Engineering Intern {}", i),
+        "url": format!("{}/p/b{}", server.uri(), i),
+        "published_date": "2025-02-07T09:30:00Z",
+    })).collect();
+    Mock::given(method("GET")).and(path("/json"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(board))
+        .mount(&server).await;
+    let delay = std::time::Duration::from_millis(300);
+    Mock::given(method("GET")).and(path_regex("^/p/b[0-9]$"))
+        .respond_with(ResponseTemplate::new(200).set_delay(delay).set_body_string(
+            r#"<script type="application/ld+json">{"description": "<p>Write Rust.</p>"}</script>"#,
+        ))
+        .mount(&server).await;
+    let board_url = format!("{}/json", server.uri());
+
+    let started = std::time::Instant::now();
+    let jobs = run_with_enrichment(company(AtsType::Breezy, board_url.clone()), Enrichment { enabled: true, concurrency: 1 }).await.unwrap();
+    assert_eq!(jobs.len(), 3);
+    assert!(jobs.iter().all(|j| j.description.contains("Write Rust")));
+    assert!(started.elapsed() >= delay * 3, "one at a time: {:?}", started.elapsed());
+
+    // 0 lifts the cap, so the three requests overlap
+    let started = std::time::Instant::now();
+    run_with_enrichment(company(AtsType::Breezy, board_url), Enrichment { enabled: true, concurrency: 0 }).await.unwrap();
+    assert!(started.elapsed() < delay * 3, "unlimited: {:?}", started.elapsed());
+
+    // Detail requests share the per-domain limit with the board fetch
+    let started = std::time::Instant::now();
+    let jobs = run_with_limiter(company(AtsType::Breezy, format!("{}/json", server.uri())), Enrichment { enabled: true, concurrency: 0 }, &DomainRateLimiter::new(1), &CircuitBreaker::new(3, 7)).await.unwrap();
+    assert_eq!(jobs.len(), 3);
+    assert!(started.elapsed() >= delay * 3, "one per domain: {:?}", started.elapsed());
+}
+
+#[tokio::test]
+async fn test_open_circuit_skips_without_a_request() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET")).respond_with(json_fixture("lever")).expect(0).mount(&server).await;
+    let breaker = CircuitBreaker::new(1, 7);
+    breaker.record_failure("acme", Utc::now());
+
+    let limiter = DomainRateLimiter::new(1);
+    let result = run_with_limiter(company(AtsType::Lever, format!("{}/v0/postings/acme", server.uri())), Enrichment { enabled: true, concurrency: 10 }, &limiter, &breaker).await;
+    assert!(matches!(result, Err(ScraperError::CircuitOpen { .. })), "{:?}", result);
+}
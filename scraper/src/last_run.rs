@@ -0,0 +1,94 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+
+/// How long a board may go on incremental fetches before it is fetched in
+/// full again. Only a full fetch bumps `last_seen` on jobs that haven't
+/// changed, so this keeps open postings fresh and lets closed ones go stale.
+const FULL_FETCH_INTERVAL_HOURS: i64 = 24;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+struct LastRun {
+    /// Start of the last run whose jobs all made it into the database.
+    started_at: DateTime<Utc>,
+    /// Start of the last such run that fetched the whole board.
+    full_fetch_at: DateTime<Utc>,
+}
+
+/// Per-slug runs persisted in `last_run.json`. Greenhouse boards are fetched
+/// with `updated_after` set to the last run's start, unless `--force-full`.
+pub struct LastRuns {
+    runs: Mutex<HashMap<String, LastRun>>,
+}
+
+impl LastRuns {
+    pub fn new() -> Self {
+        Self { runs: Mutex::new(HashMap::new()) }
+    }
+
+    /// A missing file means every board is fetched in full.
+    pub fn load(path: &str) -> Result<Self> {
+        let last_runs = Self::new();
+        if std::path::Path::new(path).exists() {
+            let content = fs::read_to_string(path)?;
+            let runs = serde_json::from_str(&content).context(format!("Invalid {}", path))?;
+            *last_runs.runs.lock().unwrap() = runs;
+        }
+        Ok(last_runs)
+    }
+
+    /// `None` when the company has no recorded run, or when its last full
+    /// fetch is more than `FULL_FETCH_INTERVAL_HOURS` old.
+    pub fn updated_after(&self, slug: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+        let runs = self.runs.lock().unwrap();
+        let run = runs.get(slug)?;
+        (now - run.full_fetch_at < Duration::hours(FULL_FETCH_INTERVAL_HOURS)).then_some(run.started_at)
+    }
+
+    /// Called once the company's jobs are inserted; `full_fetch` when the
+    /// board was fetched without `updated_after`. Rewrites `path` straight away.
+    pub fn record(&self, slug: &str, started_at: DateTime<Utc>, full_fetch: bool, path: &str) -> Result<()> {
+        let mut runs = self.runs.lock().unwrap();
+        let full_fetch_at = match runs.get(slug) {
+            Some(run) if !full_fetch => run.full_fetch_at,
+            _ => started_at,
+        };
+        runs.insert(slug.to_string(), LastRun { started_at, full_fetch_at });
+        // Written under the lock so concurrent flushes can't interleave renames
+        let tmp_path = format!("{}.tmp", path);
+        fs::write(&tmp_path, serde_json::to_string_pretty(&*runs)?)?;
+        fs::rename(&tmp_path, path)?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_updated_after_until_full_fetch_is_due() {
+        let path = std::env::temp_dir().join(format!("zapply_last_run_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let now = Utc::now();
+
+        let last_runs = LastRuns::new();
+        assert_eq!(last_runs.updated_after("stripe", now), None);
+
+        last_runs.record("stripe", now - Duration::hours(20), true, path).unwrap();
+        last_runs.record("stripe", now - Duration::hours(2), false, path).unwrap();
+        assert_eq!(last_runs.updated_after("stripe", now), Some(now - Duration::hours(2)));
+        // A day after the last full fetch the board is fetched in full again
+        assert_eq!(last_runs.updated_after("stripe", now + Duration::hours(4)), None);
+
+        let loaded = LastRuns::load(path).unwrap();
+        assert_eq!(loaded.updated_after("stripe", now), Some(now - Duration::hours(2)));
+        assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists());
+
+        fs::remove_file(path).unwrap();
+        assert_eq!(LastRuns::load(path).unwrap().updated_after("stripe", now), None);
+    }
+}
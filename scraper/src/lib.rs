@@ -0,0 +1,7 @@
+//! Library target for the benchmarks in `benches/`; the scraper itself is `main.rs`.
+#![allow(clippy::collapsible_if)]
+
+pub mod location;
+pub mod models;
+pub mod parsers;
+pub mod tag;
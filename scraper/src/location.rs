@@ -1,15 +1,24 @@
+use std::borrow::Cow;
 use std::collections::HashMap;
 use serde::{Deserialize, Serialize};
 use crate::models::WorkMode;
 use std::fs::File;
-use std::io::{BufRead, BufReader};
+use std::io::{BufRead, BufReader, BufWriter, Write};
 use anyhow::Result;
-use log::info;
+use tracing::{debug, info};
 
 const REMOTE_KEYWORDS: &[&str] = &["remote", "anywhere", "wfh"];
 const HYBRID_KEYWORDS: &[&str] = &["hybrid"];
+const GLOBAL_KEYWORDS: &[&str] = &["worldwide", "global", "internationally", "any location"];
 
+// Fuzzy city matching: only the most populated cities are candidates, and only
+// inputs long enough that a couple of typos can't turn them into another city.
+const FUZZY_CANDIDATE_LIMIT: usize = 500;
+const FUZZY_MIN_LEN: usize = 7;
+const FUZZY_MAX_DISTANCE: usize = 2;
 
+
+use once_cell::sync::Lazy;
 use regex::Regex;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -19,10 +28,30 @@ pub struct LocationInfo {
     pub country: Option<String>,
     pub country_code: Option<String>,
     pub work_mode: WorkMode,
+    pub metro_area: Option<String>,
+    /// IANA zone or `UTC±HH:MM` named in the text; see `extract_timezone`.
+    pub preferred_timezone: Option<String>,
 }
 
 impl LocationInfo {
+    /// How specific the match was: a city is certain, a bare country barely says anything.
+    pub fn confidence(&self) -> f32 {
+        if self.city.is_some() {
+            1.0
+        } else if self.region.is_some() {
+            0.75
+        } else if self.country.is_some() {
+            0.5
+        } else {
+            0.0
+        }
+    }
+
     pub fn display_format(&self) -> String {
+        if self.work_mode == WorkMode::Global && self.city.is_none() && self.region.is_none() && self.country.is_none() {
+            return "Global (Remote)".to_string();
+        }
+
         let mut parts = Vec::with_capacity(3);
         
         if let Some(city) = &self.city {
@@ -47,6 +76,57 @@ impl LocationInfo {
     }
 }
 
+#[derive(Debug, Deserialize)]
+struct MetroAreaEntry {
+    cities: Vec<String>,
+    /// GeoNames admin1 ids (e.g. "US.CA"). When set, a city only counts if it
+    /// is in one of these regions ("Cambridge, MA" is not Greater London).
+    #[serde(default)]
+    regions: Vec<String>,
+}
+
+/// Maps cities to the metropolitan area they belong to ("Oakland" -> "Bay Area").
+#[derive(Debug, Default)]
+pub struct MetroAreaMap {
+    // lowercase city name -> [(metro name, allowed region ids)]
+    by_city: HashMap<String, Vec<(String, Vec<String>)>>,
+}
+
+impl MetroAreaMap {
+    /// Loads a `metro_areas.json` file of the form
+    /// `{ "Bay Area": { "cities": ["San Jose", ...], "regions": ["US.CA"] } }`.
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)?;
+        let entries: HashMap<String, MetroAreaEntry> = serde_json::from_str(&content)?;
+        Ok(Self::from_entries(entries))
+    }
+
+    fn from_entries(entries: HashMap<String, MetroAreaEntry>) -> Self {
+        let mut by_city: HashMap<String, Vec<(String, Vec<String>)>> = HashMap::new();
+        for (metro, entry) in entries {
+            for city in entry.cities {
+                by_city.entry(city.to_lowercase()).or_default().push((metro.clone(), entry.regions.clone()));
+            }
+        }
+        Self { by_city }
+    }
+
+    pub fn lookup(&self, city: &str, region_id: &str) -> Option<&str> {
+        self.by_city.get(&city.to_lowercase())?
+            .iter()
+            .find(|(_, regions)| regions.is_empty() || regions.iter().any(|r| r == region_id))
+            .map(|(metro, _)| metro.as_str())
+    }
+
+    pub fn len(&self) -> usize {
+        self.by_city.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.by_city.is_empty()
+    }
+}
+
 pub struct LocationEngine {
     // Map of name -> Vec of possible locations (sorted by population DESC)
     pub cities: HashMap<String, Vec<GeoName>>,
@@ -58,11 +138,19 @@ pub struct LocationEngine {
     region_lookup: HashMap<String, (String, String)>,  // normalised country_code.name/code -> (id, name)
     admin1_lookup: HashMap<String, String>,            // normalised region code -> country code (e.g., "tx" -> "US")
 
+    // Memory-mapped alternative to `cities` (see `build_index`)
+    city_index: Option<CityIndex>,
+
+    // Lowercase city keys of the most populated cities, sorted by population DESC
+    fuzzy_candidates: Vec<String>,
+
+    pub metro_areas: MetroAreaMap,
+
     // compiled regex for keyword removal
     keyword_regex: Regex,
 }
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, Serialize, Deserialize)]
 pub struct GeoName {
     pub name: String,
     pub country_code: String,
@@ -70,11 +158,319 @@ pub struct GeoName {
     pub admin1: String,
 }
 
+/// ISO 3166-1 alpha-2 codes with the names GeoNames' `countryInfo.txt` uses,
+/// so parsers can spell out a reported country without the GeoNames tables.
+const COUNTRY_NAMES: &[(&str, &str)] = &[
+    ("AD", "Andorra"), ("AE", "United Arab Emirates"), ("AF", "Afghanistan"),
+    ("AG", "Antigua and Barbuda"), ("AI", "Anguilla"), ("AL", "Albania"), ("AM", "Armenia"),
+    ("AO", "Angola"), ("AQ", "Antarctica"), ("AR", "Argentina"), ("AS", "American Samoa"),
+    ("AT", "Austria"), ("AU", "Australia"), ("AW", "Aruba"), ("AX", "Aland Islands"),
+    ("AZ", "Azerbaijan"), ("BA", "Bosnia and Herzegovina"), ("BB", "Barbados"),
+    ("BD", "Bangladesh"), ("BE", "Belgium"), ("BF", "Burkina Faso"), ("BG", "Bulgaria"),
+    ("BH", "Bahrain"), ("BI", "Burundi"), ("BJ", "Benin"), ("BL", "Saint Barthelemy"),
+    ("BM", "Bermuda"), ("BN", "Brunei"), ("BO", "Bolivia"),
+    ("BQ", "Bonaire, Saint Eustatius and Saba"), ("BR", "Brazil"), ("BS", "Bahamas"),
+    ("BT", "Bhutan"), ("BV", "Bouvet Island"), ("BW", "Botswana"), ("BY", "Belarus"),
+    ("BZ", "Belize"), ("CA", "Canada"), ("CC", "Cocos Islands"),
+    ("CD", "Democratic Republic of the Congo"), ("CF", "Central African Republic"),
+    ("CG", "Republic of the Congo"), ("CH", "Switzerland"), ("CI", "Ivory Coast"),
+    ("CK", "Cook Islands"), ("CL", "Chile"), ("CM", "Cameroon"), ("CN", "China"),
+    ("CO", "Colombia"), ("CR", "Costa Rica"), ("CU", "Cuba"), ("CV", "Cabo Verde"),
+    ("CW", "Curacao"), ("CX", "Christmas Island"), ("CY", "Cyprus"), ("CZ", "Czechia"),
+    ("DE", "Germany"), ("DJ", "Djibouti"), ("DK", "Denmark"), ("DM", "Dominica"),
+    ("DO", "Dominican Republic"), ("DZ", "Algeria"), ("EC", "Ecuador"), ("EE", "Estonia"),
+    ("EG", "Egypt"), ("EH", "Western Sahara"), ("ER", "Eritrea"), ("ES", "Spain"),
+    ("ET", "Ethiopia"), ("FI", "Finland"), ("FJ", "Fiji"), ("FK", "Falkland Islands"),
+    ("FM", "Micronesia"), ("FO", "Faroe Islands"), ("FR", "France"), ("GA", "Gabon"),
+    ("GB", "United Kingdom"), ("GD", "Grenada"), ("GE", "Georgia"), ("GF", "French Guiana"),
+    ("GG", "Guernsey"), ("GH", "Ghana"), ("GI", "Gibraltar"), ("GL", "Greenland"), ("GM", "Gambia"),
+    ("GN", "Guinea"), ("GP", "Guadeloupe"), ("GQ", "Equatorial Guinea"), ("GR", "Greece"),
+    ("GS", "South Georgia and the South Sandwich Islands"), ("GT", "Guatemala"), ("GU", "Guam"),
+    ("GW", "Guinea-Bissau"), ("GY", "Guyana"), ("HK", "Hong Kong"),
+    ("HM", "Heard Island and McDonald Islands"), ("HN", "Honduras"), ("HR", "Croatia"),
+    ("HT", "Haiti"), ("HU", "Hungary"), ("ID", "Indonesia"), ("IE", "Ireland"), ("IL", "Israel"),
+    ("IM", "Isle of Man"), ("IN", "India"), ("IO", "British Indian Ocean Territory"),
+    ("IQ", "Iraq"), ("IR", "Iran"), ("IS", "Iceland"), ("IT", "Italy"), ("JE", "Jersey"),
+    ("JM", "Jamaica"), ("JO", "Jordan"), ("JP", "Japan"), ("KE", "Kenya"), ("KG", "Kyrgyzstan"),
+    ("KH", "Cambodia"), ("KI", "Kiribati"), ("KM", "Comoros"), ("KN", "Saint Kitts and Nevis"),
+    ("KP", "North Korea"), ("KR", "South Korea"), ("KW", "Kuwait"), ("KY", "Cayman Islands"),
+    ("KZ", "Kazakhstan"), ("LA", "Laos"), ("LB", "Lebanon"), ("LC", "Saint Lucia"),
+    ("LI", "Liechtenstein"), ("LK", "Sri Lanka"), ("LR", "Liberia"), ("LS", "Lesotho"),
+    ("LT", "Lithuania"), ("LU", "Luxembourg"), ("LV", "Latvia"), ("LY", "Libya"), ("MA", "Morocco"),
+    ("MC", "Monaco"), ("MD", "Moldova"), ("ME", "Montenegro"), ("MF", "Saint Martin"),
+    ("MG", "Madagascar"), ("MH", "Marshall Islands"), ("MK", "North Macedonia"), ("ML", "Mali"),
+    ("MM", "Myanmar"), ("MN", "Mongolia"), ("MO", "Macao"), ("MP", "Northern Mariana Islands"),
+    ("MQ", "Martinique"), ("MR", "Mauritania"), ("MS", "Montserrat"), ("MT", "Malta"),
+    ("MU", "Mauritius"), ("MV", "Maldives"), ("MW", "Malawi"), ("MX", "Mexico"), ("MY", "Malaysia"),
+    ("MZ", "Mozambique"), ("NA", "Namibia"), ("NC", "New Caledonia"), ("NE", "Niger"),
+    ("NF", "Norfolk Island"), ("NG", "Nigeria"), ("NI", "Nicaragua"), ("NL", "The Netherlands"),
+    ("NO", "Norway"), ("NP", "Nepal"), ("NR", "Nauru"), ("NU", "Niue"), ("NZ", "New Zealand"),
+    ("OM", "Oman"), ("PA", "Panama"), ("PE", "Peru"), ("PF", "French Polynesia"),
+    ("PG", "Papua New Guinea"), ("PH", "Philippines"), ("PK", "Pakistan"), ("PL", "Poland"),
+    ("PM", "Saint Pierre and Miquelon"), ("PN", "Pitcairn"), ("PR", "Puerto Rico"),
+    ("PS", "Palestinian Territory"), ("PT", "Portugal"), ("PW", "Palau"), ("PY", "Paraguay"),
+    ("QA", "Qatar"), ("RE", "Reunion"), ("RO", "Romania"), ("RS", "Serbia"), ("RU", "Russia"),
+    ("RW", "Rwanda"), ("SA", "Saudi Arabia"), ("SB", "Solomon Islands"), ("SC", "Seychelles"),
+    ("SD", "Sudan"), ("SE", "Sweden"), ("SG", "Singapore"), ("SH", "Saint Helena"),
+    ("SI", "Slovenia"), ("SJ", "Svalbard and Jan Mayen"), ("SK", "Slovakia"),
+    ("SL", "Sierra Leone"), ("SM", "San Marino"), ("SN", "Senegal"), ("SO", "Somalia"),
+    ("SR", "Suriname"), ("SS", "South Sudan"), ("ST", "Sao Tome and Principe"),
+    ("SV", "El Salvador"), ("SX", "Sint Maarten"), ("SY", "Syria"), ("SZ", "Eswatini"),
+    ("TC", "Turks and Caicos Islands"), ("TD", "Chad"), ("TF", "French Southern Territories"),
+    ("TG", "Togo"), ("TH", "Thailand"), ("TJ", "Tajikistan"), ("TK", "Tokelau"),
+    ("TL", "Timor Leste"), ("TM", "Turkmenistan"), ("TN", "Tunisia"), ("TO", "Tonga"),
+    ("TR", "Turkey"), ("TT", "Trinidad and Tobago"), ("TV", "Tuvalu"), ("TW", "Taiwan"),
+    ("TZ", "Tanzania"), ("UA", "Ukraine"), ("UG", "Uganda"),
+    ("UM", "United States Minor Outlying Islands"), ("US", "United States"), ("UY", "Uruguay"),
+    ("UZ", "Uzbekistan"), ("VA", "Vatican"), ("VC", "Saint Vincent and the Grenadines"),
+    ("VE", "Venezuela"), ("VG", "British Virgin Islands"), ("VI", "U.S. Virgin Islands"),
+    ("VN", "Vietnam"), ("VU", "Vanuatu"), ("WF", "Wallis and Futuna"), ("WS", "Samoa"),
+    ("XK", "Kosovo"), ("YE", "Yemen"), ("YT", "Mayotte"), ("ZA", "South Africa"), ("ZM", "Zambia"),
+    ("ZW", "Zimbabwe"),
+];
+
+/// Lowercased code or name -> display name.
+static COUNTRY_DISPLAY: Lazy<HashMap<String, &'static str>> = Lazy::new(|| {
+    let mut lookup: HashMap<String, &'static str> = COUNTRY_NAMES.iter()
+        .flat_map(|(code, name)| [(code.to_lowercase(), *name), (name.to_lowercase(), *name)])
+        .collect();
+    lookup.insert("usa".to_string(), "United States");
+    lookup.insert("uk".to_string(), "United Kingdom");
+    lookup
+});
+
+/// Full country name for an ISO 3166-1 alpha-2 code or a name, in any case
+/// ("nz", "NZ", "new zealand" -> "New Zealand"). Unknown values come back trimmed.
+pub fn normalize_country_display(raw: &str) -> String {
+    let raw = raw.trim();
+    COUNTRY_DISPLAY.get(&raw.to_lowercase())
+        .map(|name| name.to_string())
+        .unwrap_or_else(|| raw.to_string())
+}
+
+const OFFICE_MIN_CONFIDENCE: f32 = 0.5;
+/// "San Francisco Office", "Berlin HQ" -> the place name.
+static OFFICE_SUFFIX: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\s*[-(]?\s*\b(office|hq|headquarters|hub)\)?$").unwrap());
+
+/// Timezones written out in job locations and titles, checked in order so
+/// "Australian Eastern" wins over "Eastern time". Bare "ET"/"PT" are left out;
+/// "PT" is as often part-time.
+static TIMEZONE_PATTERNS: Lazy<Vec<(Regex, &'static str)>> = Lazy::new(|| {
+    [
+        (r"(?i)\baustralian?\s+eastern\b", "Australia/Sydney"),
+        (r"(?i)\baustralian?\s+central\b", "Australia/Adelaide"),
+        (r"(?i)\baustralian?\s+western\b", "Australia/Perth"),
+        (r"(?i)\bcentral\s+european\b", "Europe/Berlin"),
+        (r"(?i)\beastern\s+european\b", "Europe/Athens"),
+        (r"(?i)\bwestern\s+european\b", "Europe/Lisbon"),
+        (r"(?i)\bus\s+eastern\b|\beastern\s+(time|standard|daylight|timezone|time\s+zone)\b", "America/New_York"),
+        (r"(?i)\bus\s+central\b|\bcentral\s+(time|standard|daylight|timezone|time\s+zone)\b", "America/Chicago"),
+        (r"(?i)\bus\s+mountain\b|\bmountain\s+(time|standard|daylight|timezone|time\s+zone)\b", "America/Denver"),
+        (r"(?i)\bus\s+pacific\b|\bpacific\s+(time|standard|daylight|timezone|time\s+zone)\b", "America/Los_Angeles"),
+        // Abbreviations are matched in capitals only, so "est" in "Est. 2010" or "cet" in a word don't count
+        (r"\b(EST|EDT)\b", "America/New_York"),
+        (r"\b(CST|CDT)\b", "America/Chicago"),
+        (r"\b(MST|MDT)\b", "America/Denver"),
+        (r"\b(PST|PDT)\b", "America/Los_Angeles"),
+        (r"\b(GMT|BST)\b", "Europe/London"),
+        (r"\bWET\b", "Europe/Lisbon"),
+        (r"\b(CET|CEST)\b", "Europe/Berlin"),
+        (r"\b(EET|EEST)\b", "Europe/Athens"),
+        (r"\bIST\b", "Asia/Kolkata"),
+        (r"\bSGT\b", "Asia/Singapore"),
+        (r"\bHKT\b", "Asia/Hong_Kong"),
+        (r"\bJST\b", "Asia/Tokyo"),
+        (r"\bKST\b", "Asia/Seoul"),
+        (r"\b(AEST|AEDT)\b", "Australia/Sydney"),
+        (r"\b(ACST|ACDT)\b", "Australia/Adelaide"),
+        (r"\bAWST\b", "Australia/Perth"),
+        (r"\b(NZST|NZDT)\b", "Pacific/Auckland"),
+        (r"\bUTC\b", "UTC"),
+    ]
+    .into_iter()
+    .map(|(pattern, zone)| (Regex::new(pattern).unwrap(), zone))
+    .collect()
+});
+
+static UTC_OFFSET: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(?:UTC|GMT)\s*([+\-−])\s*(\d{1,2})(?::?(\d{2}))?\b").unwrap()
+});
+
+/// The timezone a location or title asks for, e.g. "Remote (EST preferred)"
+/// -> "America/New_York", or "UTC+5:30" -> "UTC+05:30". Named zones are
+/// checked against chrono-tz; offsets must be a real whole, half or quarter hour.
+pub fn extract_timezone(text: &str) -> Option<String> {
+    if let Some(caps) = UTC_OFFSET.captures(text) {
+        let hours: u32 = caps[2].parse().ok()?;
+        let minutes: u32 = caps.get(3).map_or(Some(0), |m| m.as_str().parse().ok())?;
+        // An impossible offset is a typo, not a reason to fall back to plain UTC
+        if hours > 14 || minutes >= 60 || !minutes.is_multiple_of(15) {
+            return None;
+        }
+        let sign = if &caps[1] == "+" { '+' } else { '-' };
+        return Some(format!("UTC{}{:02}:{:02}", sign, hours, minutes));
+    }
+    TIMEZONE_PATTERNS.iter()
+        .find(|(regex, _)| regex.is_match(text))
+        .and_then(|(_, zone)| zone.parse::<chrono_tz::Tz>().ok())
+        .map(|tz| tz.name().to_string())
+}
+
+/// Bumped whenever `GeoCache` or `GeoName` changes shape.
+const GEO_CACHE_VERSION: u32 = 1;
+
+/// On-disk form of the parsed GeoNames tables (`save_cache` / `load_cache`).
+#[derive(Serialize, Deserialize)]
+struct GeoCache<'a> {
+    version: u32,
+    cities: Cow<'a, HashMap<String, Vec<GeoName>>>,
+    regions: Cow<'a, HashMap<String, String>>,
+    countries: Cow<'a, HashMap<String, String>>,
+    country_lookup: Cow<'a, HashMap<String, (String, String)>>,
+    region_lookup: Cow<'a, HashMap<String, (String, String)>>,
+    admin1_lookup: Cow<'a, HashMap<String, String>>,
+}
+
+/// Parses a `cities15000.txt` row into its lookup keys (name and ASCII name,
+/// lowercased) and the entry itself.
+fn parse_city_line(line: &str) -> Option<(Vec<String>, GeoName)> {
+    let parts: Vec<&str> = line.split('\t').collect();
+    if parts.len() < 15 { return None; }
+
+    let original_name = parts[1];
+    let name_lower = original_name.to_lowercase();
+    let asciiname_lower = parts[2].to_lowercase();
+
+    let entry = GeoName {
+        name: original_name.to_string(),
+        country_code: parts[8].to_string(),
+        population: parts[14].parse().unwrap_or(0),
+        admin1: parts[10].to_string(),
+    };
+
+    let mut keys = vec![name_lower.clone()];
+    if asciiname_lower != name_lower {
+        keys.push(asciiname_lower);
+    }
+    Some((keys, entry))
+}
+
+// --- City Index ---
+//
+// Layout: magic, u64 record count, one u64 offset per record (relative to the
+// start of the record area), then newline-terminated records of
+// `key\tname\tcountry_code\tpopulation\tadmin1`, sorted by key.
+
+const CITY_INDEX_MAGIC: &[u8; 8] = b"ZCIDX01\n";
+
+struct CityIndex {
+    mmap: memmap2::Mmap,
+    count: usize,
+}
+
+impl CityIndex {
+    fn write(path: &str, records: &[(String, GeoName)]) -> Result<()> {
+        let mut offsets = Vec::with_capacity(records.len());
+        let mut data = Vec::new();
+        for (key, entry) in records {
+            offsets.push(data.len() as u64);
+            writeln!(data, "{}\t{}\t{}\t{}\t{}", key, entry.name, entry.country_code, entry.population, entry.admin1)?;
+        }
+
+        let mut out = BufWriter::new(File::create(path)?);
+        out.write_all(CITY_INDEX_MAGIC)?;
+        out.write_all(&(records.len() as u64).to_le_bytes())?;
+        for offset in offsets {
+            out.write_all(&offset.to_le_bytes())?;
+        }
+        out.write_all(&data)?;
+        out.flush()?;
+        Ok(())
+    }
+
+    fn open(path: &str) -> Result<Self> {
+        let file = File::open(path)?;
+        // SAFETY: the index is a read-only artifact written by `build_index`;
+        // it is not expected to be modified while the scraper is running.
+        let mmap = unsafe { memmap2::Mmap::map(&file)? };
+
+        if mmap.len() < 16 || &mmap[..8] != CITY_INDEX_MAGIC {
+            return Err(anyhow::anyhow!("Invalid city index file: {}", path));
+        }
+        let count = usize::try_from(u64::from_le_bytes(mmap[8..16].try_into()?))?;
+        let data_start = count.checked_mul(8)
+            .and_then(|n| n.checked_add(16))
+            .filter(|&n| n <= mmap.len())
+            .ok_or_else(|| anyhow::anyhow!("Truncated city index file: {}", path))?;
+        // Checked once here so `record` can slice without bounds checks failing
+        let data_len = (mmap.len() - data_start) as u64;
+        if !mmap[16..data_start].chunks_exact(8).all(|b| u64::from_le_bytes(b.try_into().unwrap()) <= data_len) {
+            return Err(anyhow::anyhow!("Corrupt city index file: {}", path));
+        }
+        Ok(Self { mmap, count })
+    }
+
+    fn len(&self) -> usize {
+        self.count
+    }
+
+    fn record(&self, i: usize) -> &str {
+        let table = &self.mmap[16..16 + self.count * 8];
+        let data = &self.mmap[16 + self.count * 8..];
+        let start = u64::from_le_bytes(table[i * 8..i * 8 + 8].try_into().unwrap()) as usize;
+        let end = data[start..].iter().position(|&b| b == b'\n').map_or(data.len(), |p| start + p);
+        std::str::from_utf8(&data[start..end]).unwrap_or("")
+    }
+
+    fn parse_record(record: &str) -> Option<(&str, GeoName)> {
+        let mut fields = record.split('\t');
+        let key = fields.next()?;
+        let entry = GeoName {
+            name: fields.next()?.to_string(),
+            country_code: fields.next()?.to_string(),
+            population: fields.next()?.parse().unwrap_or(0),
+            admin1: fields.next().unwrap_or_default().to_string(),
+        };
+        Some((key, entry))
+    }
+
+    fn key(&self, i: usize) -> &str {
+        let record = self.record(i);
+        record.split('\t').next().unwrap_or("")
+    }
+
+    /// All entries for `key`, sorted by population DESC.
+    fn lookup(&self, key: &str) -> Vec<GeoName> {
+        // Lower-bound binary search for the first record with this key
+        let (mut lo, mut hi) = (0, self.count);
+        while lo < hi {
+            let mid = lo + (hi - lo) / 2;
+            if self.key(mid) < key { lo = mid + 1; } else { hi = mid; }
+        }
+
+        (lo..self.count)
+            .map_while(|i| Self::parse_record(self.record(i)).filter(|(k, _)| *k == key))
+            .map(|(_, entry)| entry)
+            .collect()
+    }
+
+    fn iter(&self) -> impl Iterator<Item = (&str, GeoName)> + '_ {
+        (0..self.count).filter_map(|i| Self::parse_record(self.record(i)))
+    }
+}
+
+impl Default for LocationEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl LocationEngine {
     pub fn new() -> Self {
-        let pattern = format!(r"\b({}|{})\b", 
+        let pattern = format!(r"\b({}|{}|{})\b", 
             REMOTE_KEYWORDS.join("|"), 
-            HYBRID_KEYWORDS.join("|")
+            HYBRID_KEYWORDS.join("|"),
+            GLOBAL_KEYWORDS.join("|")
         );
 
         Self {
@@ -84,14 +480,46 @@ impl LocationEngine {
             country_lookup: HashMap::new(),
             region_lookup: HashMap::new(),
             admin1_lookup: HashMap::new(),
+            city_index: None,
+            fuzzy_candidates: Vec::new(),
+            metro_areas: MetroAreaMap::default(),
             keyword_regex: Regex::new(&pattern).expect("Invalid regex pattern"),
         }
     }
 
     pub fn load_geonames(&mut self, cities_path: &str, admin_path: &str, country_path: &str) -> Result<()> {
         info!("Loading location data...");
-        
-        // Load Country Info
+        self.load_countries(country_path)?;
+        self.load_regions(admin_path)?;
+
+        // Load Cities
+        info!("Loading cities (this may take a few seconds)...");
+        let file = File::open(cities_path)?;
+        let reader = BufReader::new(file);
+
+        let mut count = 0;
+        for line in reader.lines() {
+            let line = line?;
+            let Some((keys, entry)) = parse_city_line(&line) else { continue; };
+
+            for key in keys {
+                self.cities.entry(key).or_default().push(entry.clone());
+            }
+            count += 1;
+        }
+
+        // Sort by population
+        info!("Finalizing city data index...");
+        for entries in self.cities.values_mut() {
+            entries.sort_by_key(|e| std::cmp::Reverse(e.population));
+        }
+        self.build_fuzzy_candidates();
+
+        info!("Location engine ready (loaded {} cities).", count);
+        Ok(())
+    }
+
+    pub fn load_countries(&mut self, country_path: &str) -> Result<()> {
         info!("Loading countries...");
         let file = File::open(country_path)?;
         let reader = BufReader::new(file);
@@ -114,8 +542,10 @@ impl LocationEngine {
         // Add common aliases
         self.country_lookup.insert("usa".to_string(), ("US".to_string(), "United States".to_string()));
         self.country_lookup.insert("uk".to_string(), ("GB".to_string(), "United Kingdom".to_string()));
+        Ok(())
+    }
 
-        // Load Admin1 Codes
+    pub fn load_regions(&mut self, admin_path: &str) -> Result<()> {
         info!("Loading regions...");
         let file = File::open(admin_path)?;
         let reader = BufReader::new(file);
@@ -146,58 +576,150 @@ impl LocationEngine {
             
             self.regions.insert(id, name);
         }
+        Ok(())
+    }
 
-        // Load Cities
-        info!("Loading cities (this may take a few seconds)...");
+    /// One-time conversion of `cities15000.txt` into a sorted index file that
+    /// `load_from_index` can memory-map instead of building the city HashMap.
+    pub fn build_index(cities_path: &str, out_path: &str) -> Result<()> {
+        info!("Building city index from {}...", cities_path);
         let file = File::open(cities_path)?;
         let reader = BufReader::new(file);
 
-        let mut count = 0;
+        let mut records: Vec<(String, GeoName)> = Vec::new();
         for line in reader.lines() {
             let line = line?;
-            let parts: Vec<&str> = line.split('\t').collect();
-            if parts.len() < 15 { continue; }
-
-            let original_name = parts[1];
-            let name_lower = original_name.to_lowercase();
-            let asciiname_lower = parts[2].to_lowercase();
-            let country_code = parts[8].to_string();
-            let population: u32 = parts[14].parse().unwrap_or(0);
-            let admin1 = parts[10].to_string();
-
-            let entry = GeoName {
-                name: original_name.to_string(),
-                country_code,
-                population,
-                admin1,
-            };
-
-            self.cities.entry(name_lower.clone()).or_default().push(entry.clone());
-            if asciiname_lower != name_lower {
-                 self.cities.entry(asciiname_lower).or_default().push(entry);
+            let Some((keys, entry)) = parse_city_line(&line) else { continue; };
+            for key in keys {
+                records.push((key, entry.clone()));
             }
-            count += 1;
         }
 
-        // Sort by population
-        info!("Finalizing city data index...");
-        for entries in self.cities.values_mut() {
-            entries.sort_by(|a, b| b.population.cmp(&a.population));
-        }
+        // Sorted by key, then population DESC so the first hit is the best match
+        records.sort_by(|(ka, a), (kb, b)| ka.cmp(kb).then(b.population.cmp(&a.population)));
+        CityIndex::write(out_path, &records)?;
 
-        info!("Location engine ready (loaded {} cities).", count);
+        info!("City index written to {} ({} entries).", out_path, records.len());
+        Ok(())
+    }
+
+    /// Use a city index built by `build_index` for city lookups. Countries and
+    /// regions still come from `load_countries` / `load_regions`.
+    pub fn load_from_index(&mut self, path: &str) -> Result<()> {
+        info!("Loading city index from {}...", path);
+        let index = CityIndex::open(path)?;
+        info!("Location engine ready (indexed {} city names).", index.len());
+        self.city_index = Some(index);
+        self.build_fuzzy_candidates();
+        Ok(())
+    }
+
+    /// Writes the parsed GeoNames tables so later runs can skip the text files.
+    pub fn save_cache(&self, path: &str) -> Result<()> {
+        let cache = GeoCache {
+            version: GEO_CACHE_VERSION,
+            cities: Cow::Borrowed(&self.cities),
+            regions: Cow::Borrowed(&self.regions),
+            countries: Cow::Borrowed(&self.countries),
+            country_lookup: Cow::Borrowed(&self.country_lookup),
+            region_lookup: Cow::Borrowed(&self.region_lookup),
+            admin1_lookup: Cow::Borrowed(&self.admin1_lookup),
+        };
+        let tmp_path = format!("{}.tmp", path);
+        let mut writer = BufWriter::new(File::create(&tmp_path)?);
+        bincode::serialize_into(&mut writer, &cache)?;
+        writer.flush()?;
+        drop(writer);
+        std::fs::rename(&tmp_path, path)?;
+        info!("Location cache written to {}.", path);
         Ok(())
     }
 
+    /// Loads tables written by `save_cache`. Metro areas are not cached.
+    pub fn load_cache(path: &str) -> Result<Self> {
+        info!("Loading location cache from {}...", path);
+        let cache: GeoCache = bincode::deserialize_from(BufReader::new(File::open(path)?))?;
+        if cache.version != GEO_CACHE_VERSION {
+            anyhow::bail!("{} is cache version {}, expected {}", path, cache.version, GEO_CACHE_VERSION);
+        }
+        let mut engine = Self::new();
+        engine.cities = cache.cities.into_owned();
+        engine.regions = cache.regions.into_owned();
+        engine.countries = cache.countries.into_owned();
+        engine.country_lookup = cache.country_lookup.into_owned();
+        engine.region_lookup = cache.region_lookup.into_owned();
+        engine.admin1_lookup = cache.admin1_lookup.into_owned();
+        engine.build_fuzzy_candidates();
+        info!("Location engine ready (loaded {} city names from cache).", engine.cities.len());
+        Ok(engine)
+    }
+
+    /// True if `cache_path` exists and is newer than every source file.
+    pub fn cache_is_fresh(cache_path: &str, sources: &[&str]) -> bool {
+        let modified = |path: &str| std::fs::metadata(path).and_then(|m| m.modified()).ok();
+        let Some(cached_at) = modified(cache_path) else { return false; };
+        sources.iter().all(|source| modified(source).is_some_and(|t| t <= cached_at))
+    }
+
+    fn find_cities(&self, key: &str) -> Option<Cow<'_, [GeoName]>> {
+        if let Some(entries) = self.cities.get(key) {
+            return Some(Cow::Borrowed(entries.as_slice()));
+        }
+        let entries = self.city_index.as_ref()?.lookup(key);
+        if entries.is_empty() { None } else { Some(Cow::Owned(entries)) }
+    }
+
+    /// An office name in the same display form as job locations, or the raw
+    /// name when it only resolves to a country or not at all.
+    pub fn normalize_office(&self, raw: &str) -> String {
+        let trimmed = raw.trim();
+        let place = OFFICE_SUFFIX.replace(trimmed, "");
+        let loc = self.resolve(&place);
+        if loc.confidence() > OFFICE_MIN_CONFIDENCE {
+            let formatted = loc.display_format();
+            if !formatted.is_empty() {
+                return formatted;
+            }
+        }
+        trimmed.to_string()
+    }
+
+    /// A location from fields the ATS already split out, skipping the string
+    /// parsing in `resolve`. `None` unless the country and the region (code or
+    /// name) are both known, so the caller can fall back to `resolve`. The
+    /// work mode and timezone come from the ATS's own label, `name`.
+    pub fn resolve_structured(&self, city: &str, region: &str, country: &str, name: &str) -> Option<LocationInfo> {
+        let city = city.trim();
+        if city.is_empty() { return None; }
+        let (code, country_name) = self.country_lookup.get(&country.trim().to_lowercase())?.clone();
+        let (region_id, region_name) = self.region_lookup
+            .get(&format!("{}.{}", code.to_lowercase(), region.trim().to_lowercase()))?
+            .clone();
+        let (_, work_mode) = self.extract_work_mode_and_clean(name);
+        Some(LocationInfo {
+            city: Some(city.to_string()),
+            region: Some(region_name),
+            country: Some(country_name),
+            country_code: Some(code),
+            work_mode,
+            metro_area: self.metro_areas.lookup(city, &region_id).map(String::from),
+            preferred_timezone: extract_timezone(name),
+        })
+    }
+
     pub fn resolve(&self, raw: &str) -> LocationInfo {
+        LocationInfo { preferred_timezone: extract_timezone(raw), ..self.resolve_place(raw) }
+    }
+
+    fn resolve_place(&self, raw: &str) -> LocationInfo {
         let (raw_clean, work_mode) = self.extract_work_mode_and_clean(raw);
 
         if raw_clean.is_empty() {
-             return LocationInfo { city: None, region: None, country: None, country_code: None, work_mode };
+             return LocationInfo { city: None, region: None, country: None, country_code: None, work_mode, metro_area: None, preferred_timezone: None };
         }
 
         // Split on comma, pipe, or slash
-        let parts: Vec<&str> = raw_clean.split(|c| c == ',' || c == '|' || c == '/')
+        let parts: Vec<&str> = raw_clean.split([',', '|', '/'])
             .map(|s| s.trim())
             .filter(|s| !s.is_empty())
             .collect();
@@ -221,6 +743,7 @@ impl LocationEngine {
         // Check for keywords and remove them in a single pass to ensure consistency
         let mut detected_remote = false;
         let mut detected_hybrid = false;
+        let mut detected_global = false;
 
         raw_clean = self.keyword_regex.replace_all(&raw_clean, |caps: &regex::Captures| {
             let s = caps.get(0).unwrap().as_str();
@@ -228,11 +751,16 @@ impl LocationEngine {
                 detected_remote = true;
             } else if HYBRID_KEYWORDS.contains(&s) {
                 detected_hybrid = true;
+            } else if GLOBAL_KEYWORDS.contains(&s) {
+                detected_global = true;
             }
             ""
         }).to_string();
 
-        if detected_remote {
+        // "Global - Remote" is still global; an unrestricted location outranks plain remote
+        if detected_global {
+            work_mode = WorkMode::Global;
+        } else if detected_remote {
             work_mode = WorkMode::Remote;
         } else if detected_hybrid {
             work_mode = WorkMode::Hybrid;
@@ -261,7 +789,7 @@ impl LocationEngine {
         let idx = if country_found.is_some() {
              if parts.len() >= 2 { Some(parts.len() - 2) } else { None }
         } else {
-             if parts.len() >= 1 { Some(parts.len() - 1) } else { None }
+             if !parts.is_empty() { Some(parts.len() - 1) } else { None }
         }?;
 
         let part = parts[idx];
@@ -296,7 +824,7 @@ impl LocationEngine {
 
         if let Some(idx) = city_part_idx {
             let city_part = parts[idx];
-            if let Some(matches) = self.cities.get(city_part) {
+            if let Some(matches) = self.find_cities(city_part) {
                 let best = matches.iter().find(|m| {
                     if let Some((c_code, _)) = country_found {
                         if m.country_code != *c_code { return false; }
@@ -308,14 +836,7 @@ impl LocationEngine {
                     true
                 }).unwrap_or(&matches[0]);
 
-                let region_key = format!("{}.{}", best.country_code, best.admin1);
-                return Some(LocationInfo {
-                    city: Some(best.name.clone()),
-                    region: self.regions.get(&region_key).cloned(),
-                    country: self.countries.get(&best.country_code).cloned(),
-                    country_code: Some(best.country_code.clone()),
-                    work_mode,
-                });
+                return Some(self.location_from_geoname(best, work_mode));
             }
         }
         None
@@ -341,27 +862,74 @@ impl LocationEngine {
                 country: if c_name.is_empty() { None } else { Some(c_name) },
                 country_code: if c_code.is_empty() { None } else { Some(c_code) },
                 work_mode,
+                metro_area: None,
+                preferred_timezone: None,
             };
         }
 
         // Token-based fallback search (if no structure matched)
         for part in parts {
             for token in part.split_whitespace() {
-                if let Some(matches) = self.cities.get(token) {
-                     let best = &matches[0];
-                     let region_key = format!("{}.{}", best.country_code, best.admin1);
-                     return LocationInfo {
-                         city: Some(best.name.clone()),
-                         region: self.regions.get(&region_key).cloned(),
-                         country: self.countries.get(&best.country_code).cloned(),
-                         country_code: Some(best.country_code.clone()),
-                         work_mode,
-                     };
+                if let Some(matches) = self.find_cities(token) {
+                     return self.location_from_geoname(&matches[0], work_mode);
                 }
             }
         }
 
-        LocationInfo { city: None, region: None, country: None, country_code: None, work_mode }
+        // Last resort: every part failed exact matching, so try to correct typos
+        if let Some(best) = self.fuzzy_match_city(parts) {
+            return self.location_from_geoname(&best, work_mode);
+        }
+
+        LocationInfo { city: None, region: None, country: None, country_code: None, work_mode, metro_area: None, preferred_timezone: None }
+    }
+
+    fn location_from_geoname(&self, best: &GeoName, work_mode: WorkMode) -> LocationInfo {
+        let region_key = format!("{}.{}", best.country_code, best.admin1);
+        LocationInfo {
+            city: Some(best.name.clone()),
+            region: self.regions.get(&region_key).cloned(),
+            country: self.countries.get(&best.country_code).cloned(),
+            country_code: Some(best.country_code.clone()),
+            work_mode,
+            metro_area: self.metro_areas.lookup(&best.name, &region_key).map(String::from),
+            preferred_timezone: None,
+        }
+    }
+
+    fn build_fuzzy_candidates(&mut self) {
+        let mut candidates: Vec<(String, u32)> = self.cities.iter()
+            .map(|(key, entries)| (key.clone(), entries.first().map_or(0, |e| e.population)))
+            .collect();
+        if let Some(index) = &self.city_index {
+            candidates.extend(index.iter().map(|(key, entry)| (key.to_string(), entry.population)));
+        }
+        candidates.sort_by_key(|(_, population)| std::cmp::Reverse(*population));
+        candidates.dedup_by(|a, b| a.0 == b.0);
+        self.fuzzy_candidates = candidates.into_iter()
+            .take(FUZZY_CANDIDATE_LIMIT)
+            .map(|(key, _)| key)
+            .collect();
+    }
+
+    fn fuzzy_match_city(&self, parts: &[&str]) -> Option<GeoName> {
+        let mut best: Option<(&str, &str, usize)> = None; // (input, candidate, distance)
+
+        for part in parts {
+            if part.chars().count() < FUZZY_MIN_LEN { continue; }
+            for candidate in &self.fuzzy_candidates {
+                let distance = strsim::levenshtein(part, candidate);
+                // Candidates are sorted by population, so ties keep the bigger city
+                if distance <= FUZZY_MAX_DISTANCE && best.is_none_or(|(_, _, d)| distance < d) {
+                    best = Some((part, candidate, distance));
+                }
+            }
+        }
+
+        let (input, candidate, distance) = best?;
+        let found = self.find_cities(candidate)?.first()?.clone();
+        debug!("Fuzzy location match: '{}' -> '{}' (distance {})", input, found.name, distance);
+        Some(found)
     }
 
     #[cfg(test)]
@@ -382,6 +950,7 @@ impl LocationEngine {
             population: 1000000,
             admin1: "CA".to_string(),
         }]);
+        engine.build_fuzzy_candidates();
         
         engine
     }
@@ -448,6 +1017,8 @@ mod tests {
             country: Some("Singapore".to_string()),
             country_code: Some("SG".to_string()),
             work_mode: WorkMode::InOffice,
+            metro_area: None,
+            preferred_timezone: None,
         };
         assert_eq!(loc.display_format(), "Singapore");
 
@@ -457,7 +1028,286 @@ mod tests {
             country: Some("United States".to_string()),
             country_code: Some("US".to_string()),
             work_mode: WorkMode::InOffice,
+            metro_area: None,
+            preferred_timezone: None,
+        };
+        assert_eq!(loc.display_format(), "New York, United States");
+    }
+
+    #[test]
+    fn test_resolve_global() {
+        let engine = LocationEngine::new_mock();
+
+        for raw in ["Worldwide", "Global - Remote", "Remote (Internationally)", "Any location", "GLOBAL"] {
+            let loc = engine.resolve(raw);
+            assert_eq!(loc.work_mode, WorkMode::Global, "{}", raw);
+            assert!(loc.city.is_none(), "{}", raw);
+            assert_eq!(loc.display_format(), "Global (Remote)", "{}", raw);
+        }
+
+        // Global hiring with a home base keeps the resolved location
+        let loc = engine.resolve("Worldwide, San Jose, CA, US");
+        assert_eq!(loc.work_mode, WorkMode::Global);
+        assert_eq!(loc.city.as_deref(), Some("San Jose"));
+        assert_eq!(loc.display_format(), "San Jose, California, United States");
+
+        // Plain remote is unaffected
+        let loc = engine.resolve("Remote");
+        assert_eq!(loc.work_mode, WorkMode::Remote);
+        assert_eq!(loc.display_format(), "");
+    }
+
+    #[test]
+    fn test_resolve_fuzzy() {
+        let mut engine = LocationEngine::new_mock();
+        engine.countries.insert("BR".to_string(), "Brazil".to_string());
+        engine.countries.insert("DE".to_string(), "Germany".to_string());
+        engine.cities.insert("san francisco".to_string(), vec![GeoName {
+            name: "San Francisco".to_string(),
+            country_code: "US".to_string(),
+            population: 864816,
+            admin1: "CA".to_string(),
+        }]);
+        engine.cities.insert("são paulo".to_string(), vec![GeoName {
+            name: "São Paulo".to_string(),
+            country_code: "BR".to_string(),
+            population: 10021295,
+            admin1: "27".to_string(),
+        }]);
+        engine.cities.insert("frankfurt".to_string(), vec![GeoName {
+            name: "Frankfurt am Main".to_string(),
+            country_code: "DE".to_string(),
+            population: 650000,
+            admin1: "05".to_string(),
+        }]);
+        engine.build_fuzzy_candidates();
+
+        let loc = engine.resolve("San Fransisco");
+        assert_eq!(loc.city.as_deref(), Some("San Francisco"));
+        assert_eq!(loc.country_code.as_deref(), Some("US"));
+
+        let loc = engine.resolve("Sao Paulo");
+        assert_eq!(loc.city.as_deref(), Some("São Paulo"));
+        assert_eq!(loc.country.as_deref(), Some("Brazil"));
+
+        let loc = engine.resolve("Remote - Frankfut");
+        assert_eq!(loc.city.as_deref(), Some("Frankfurt am Main"));
+        assert_eq!(loc.work_mode, WorkMode::Remote);
+
+        // Short inputs never fuzzy match
+        assert!(engine.resolve("Paros").city.is_none());
+
+        // Too far from any known city
+        assert!(engine.resolve("Somewhere over the rainbow").city.is_none());
+    }
+
+    #[test]
+    fn test_resolve_metro_area() {
+        let mut engine = LocationEngine::new_mock();
+        engine.admin1_lookup.insert("ca".to_string(), "US".to_string());
+        engine.cities.insert("oakland".to_string(), vec![GeoName {
+            name: "Oakland".to_string(),
+            country_code: "US".to_string(),
+            population: 433031,
+            admin1: "CA".to_string(),
+        }]);
+        engine.cities.insert("cambridge".to_string(), vec![GeoName {
+            name: "Cambridge".to_string(),
+            country_code: "US".to_string(),
+            population: 118403,
+            admin1: "MA".to_string(),
+        }]);
+
+        let entries: HashMap<String, MetroAreaEntry> = serde_json::from_value(serde_json::json!({
+            "Bay Area": { "cities": ["San Jose", "Oakland", "Fremont"], "regions": ["US.CA"] },
+            "Greater London": { "cities": ["Croydon", "Cambridge"], "regions": ["GB.ENG"] }
+        })).unwrap();
+        engine.metro_areas = MetroAreaMap::from_entries(entries);
+
+        assert_eq!(engine.resolve("San Jose, CA").metro_area.as_deref(), Some("Bay Area"));
+        assert_eq!(engine.resolve("Remote - Oakland").metro_area.as_deref(), Some("Bay Area"));
+
+        // Region restriction: Cambridge, MA is not in Greater London
+        assert!(engine.resolve("Cambridge").metro_area.is_none());
+
+        // No city, no metro
+        assert!(engine.resolve("California").metro_area.is_none());
+    }
+
+    #[test]
+    fn test_city_index_round_trip() {
+        let dir = std::env::temp_dir();
+        let cities_path = dir.join(format!("zapply_cities_{}.txt", std::process::id()));
+        let index_path = dir.join(format!("zapply_cities_{}.index", std::process::id()));
+
+        let row = |id: u32, name: &str, ascii: &str, cc: &str, admin1: &str, pop: u32| {
+            format!("{id}\t{name}\t{ascii}\t\t0\t0\tP\tPPL\t{cc}\t\t{admin1}\t\t\t\t{pop}\t\t0\tEtc/UTC\t2024-01-01\n")
+        };
+        let rows = [
+            row(1, "Paris", "Paris", "FR", "11", 2138551),
+            row(2, "Paris", "Paris", "US", "TX", 24171),
+            row(3, "São Paulo", "Sao Paulo", "BR", "27", 10021295),
+            row(4, "San Jose", "San Jose", "US", "CA", 1000000),
+        ].concat();
+        std::fs::write(&cities_path, rows).unwrap();
+
+        LocationEngine::build_index(cities_path.to_str().unwrap(), index_path.to_str().unwrap()).unwrap();
+
+        let mut engine = LocationEngine::new();
+        engine.countries.insert("FR".to_string(), "France".to_string());
+        engine.countries.insert("BR".to_string(), "Brazil".to_string());
+        engine.load_from_index(index_path.to_str().unwrap()).unwrap();
+        assert!(engine.cities.is_empty());
+
+        // Most populated entry wins without other context
+        let loc = engine.resolve("Paris");
+        assert_eq!(loc.country_code.as_deref(), Some("FR"));
+        assert_eq!(loc.country.as_deref(), Some("France"));
+
+        // ASCII name is indexed too
+        let loc = engine.resolve("Sao Paulo");
+        assert_eq!(loc.city.as_deref(), Some("São Paulo"));
+
+        // Fuzzy candidates come from the index
+        let loc = engine.resolve("San Josee");
+        assert_eq!(loc.city.as_deref(), Some("San Jose"));
+
+        assert!(engine.resolve("Atlantis").city.is_none());
+
+        let _ = std::fs::remove_file(cities_path);
+        let _ = std::fs::remove_file(index_path);
+    }
+
+    #[test]
+    fn test_city_index_rejects_bad_offsets() {
+        let index_path = std::env::temp_dir().join(format!("zapply_bad_{}.index", std::process::id()));
+        let index_path = index_path.to_str().unwrap();
+        let write = |count: u64, offset: u64| {
+            let mut bytes = CITY_INDEX_MAGIC.to_vec();
+            bytes.extend(count.to_le_bytes());
+            bytes.extend(offset.to_le_bytes());
+            bytes.extend(b"paris\tParis\tFR\t2138551\t11\n");
+            std::fs::write(index_path, bytes).unwrap();
         };
+
+        write(1, 0);
+        assert_eq!(CityIndex::open(index_path).unwrap().key(0), "paris");
+        // An offset past the data, and a count whose table would overflow
+        write(1, 4096);
+        assert!(CityIndex::open(index_path).is_err());
+        write(u64::MAX / 4, 0);
+        assert!(CityIndex::open(index_path).is_err());
+
+        let _ = std::fs::remove_file(index_path);
+    }
+
+    #[test]
+    fn test_geo_cache_round_trip() {
+        let engine = LocationEngine::new_mock();
+        let cache_path = std::env::temp_dir().join(format!("zapply_geo_{}.cache", std::process::id()));
+        let cache_path = cache_path.to_str().unwrap();
+        engine.save_cache(cache_path).unwrap();
+
+        let cached = LocationEngine::load_cache(cache_path).unwrap();
+        for input in ["San Jose, CA", "Sydney, NSW", "Remote - US", "San Josee"] {
+            let (expected, actual) = (engine.resolve(input), cached.resolve(input));
+            assert_eq!(actual.display_format(), expected.display_format(), "{}", input);
+            assert_eq!(actual.city, expected.city, "{}", input);
+        }
+
+        // Freshness compares against every source file
+        assert!(LocationEngine::cache_is_fresh(cache_path, &[]));
+        assert!(!LocationEngine::cache_is_fresh(cache_path, &["does-not-exist.txt"]));
+        assert!(!LocationEngine::cache_is_fresh("does-not-exist.cache", &[]));
+
+        let _ = std::fs::remove_file(cache_path);
+    }
+
+    #[test]
+    fn test_normalize_country_display() {
+        assert_eq!(normalize_country_display("nz"), "New Zealand");
+        assert_eq!(normalize_country_display(" NZ "), "New Zealand");
+        assert_eq!(normalize_country_display("new zealand"), "New Zealand");
+        assert_eq!(normalize_country_display("usa"), "United States");
+        assert_eq!(normalize_country_display("Atlantis"), "Atlantis");
+    }
+
+    #[test]
+    fn test_normalize_office() {
+        let engine = LocationEngine::new_mock();
+        assert_eq!(engine.normalize_office("San Jose, CA"), "San Jose, California, United States");
+        assert_eq!(engine.normalize_office("San Jose Office"), "San Jose, California, United States");
+        assert_eq!(engine.normalize_office(" San Jose (HQ) "), "San Jose, California, United States");
+        // A bare country is too vague to rewrite
+        assert_eq!(engine.normalize_office("USA"), "USA");
+        assert_eq!(engine.normalize_office("Atlantis Office"), "Atlantis Office");
+    }
+
+    #[test]
+    fn test_resolve_structured() {
+        let engine = LocationEngine::new_mock();
+        let loc = engine.resolve_structured("San Jose", "CA", "us", "San Jose").unwrap();
+        assert_eq!(loc.city.as_deref(), Some("San Jose"));
+        assert_eq!(loc.region.as_deref(), Some("California"));
+        assert_eq!(loc.country.as_deref(), Some("United States"));
+        assert_eq!(loc.country_code.as_deref(), Some("US"));
+        assert_eq!(loc.work_mode, WorkMode::InOffice);
+        assert_eq!(engine.resolve_structured("San Jose", "California", "United States", "").unwrap().region.as_deref(), Some("California"));
+
+        // The label still says how and when the job is worked
+        let mut engine = engine;
+        engine.region_lookup.insert("us.ny".to_string(), ("US.NY".to_string(), "New York".to_string()));
+        let loc = engine.resolve_structured("New York", "NY", "US", "New York City (Remote)").unwrap();
+        assert_eq!(loc.work_mode, WorkMode::Remote);
         assert_eq!(loc.display_format(), "New York, United States");
+        let loc = engine.resolve_structured("San Jose", "CA", "US", "San Jose - Hybrid, Pacific Time").unwrap();
+        assert_eq!(loc.work_mode, WorkMode::Hybrid);
+        assert_eq!(loc.preferred_timezone.as_deref(), Some("America/Los_Angeles"));
+
+        // Anything unknown falls back to `resolve`
+        assert!(engine.resolve_structured("San Jose", "Atlantis", "US", "").is_none());
+        assert!(engine.resolve_structured("San Jose", "CA", "Narnia", "").is_none());
+        assert!(engine.resolve_structured(" ", "CA", "US", "").is_none());
+    }
+
+    #[test]
+    fn test_extract_timezone() {
+        let cases = [
+            // US
+            ("Remote - US (EST preferred)", Some("America/New_York")),
+            ("Remote, US Eastern", Some("America/New_York")),
+            ("Chicago (CST)", Some("America/Chicago")),
+            ("Remote (Mountain Time)", Some("America/Denver")),
+            ("Remote - Pacific timezone", Some("America/Los_Angeles")),
+            ("San Francisco, PDT hours", Some("America/Los_Angeles")),
+            // Europe
+            ("London (GMT)", Some("Europe/London")),
+            ("Remote - CET +/- 2 hours", Some("Europe/Berlin")),
+            ("Remote, Central European Time", Some("Europe/Berlin")),
+            ("Athens, EET", Some("Europe/Athens")),
+            // Asia-Pacific
+            ("Bangalore (IST)", Some("Asia/Kolkata")),
+            ("Remote (UTC+5:30)", Some("UTC+05:30")),
+            ("Singapore, SGT", Some("Asia/Singapore")),
+            ("Tokyo (JST)", Some("Asia/Tokyo")),
+            ("Remote - Australian Eastern time", Some("Australia/Sydney")),
+            ("Sydney, AEST", Some("Australia/Sydney")),
+            ("Auckland (NZST)", Some("Pacific/Auckland")),
+            ("Remote, GMT-3", Some("UTC-03:00")),
+            ("Remote (UTC)", Some("UTC")),
+            // Nothing to find
+            ("New York, NY", None),
+            ("Eastern Europe", None),
+            ("Est. start date: June", None),
+            ("Remote (UTC+25)", None),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(extract_timezone(raw).as_deref(), expected, "{}", raw);
+        }
+
+        let engine = LocationEngine::new_mock();
+        let loc = engine.resolve("Remote - US (EST preferred)");
+        assert_eq!(loc.preferred_timezone.as_deref(), Some("America/New_York"));
+        assert_eq!(loc.work_mode, WorkMode::Remote);
     }
 }
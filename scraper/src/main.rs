@@ -1,10 +1,34 @@
-mod models;
-mod parsers;
-mod tag;
-mod location; 
 mod config; 
+mod dedup;
+mod metrics;
+mod checkpoint;
+mod stats;
+mod feed;
+mod ats_detect;
+mod circuit;
+mod error;
+mod batch;
+mod telemetry;
+mod webhook;
+mod export;
+mod fanout;
+mod validate;
+mod schedule;
+mod last_run;
+mod utils;
+mod ratelimit;
+mod country_filter;
+mod normalization;
+mod turso;
+mod dry_run;
+mod regex_test;
+mod health;
+#[cfg(test)]
+mod integration_tests;
+
+use zapply::{location, models, parsers, tag};
 
-use anyhow::{Context, Result};
+use anyhow::{Context, Result, bail};
 use futures::stream::{self, StreamExt};
 use regex::Regex;
 use serde::{Deserialize, Serialize};
@@ -12,18 +36,47 @@ use serde_json::Value;
 use std::collections::{HashSet, HashMap};
 use std::fs;
 use std::sync::{Arc, Mutex};
-use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::atomic::Ordering;
 use std::io::Write;
 use indicatif::{ProgressBar, ProgressStyle};
-use chrono::{DateTime, Duration, Utc};
+use chrono::{DateTime, Duration, SecondsFormat, Utc};
 use once_cell::sync::Lazy;
+use tokio::sync::mpsc;
 
-use crate::models::{Job, CompanyEntry, AtsType, WorkableDetail, SmartRecruitersDetail, RecruiteeDetailResponse};
-use crate::parsers::{AtsParser, clean_html};
-use crate::tag::{TagEngine, EducationDetector};
-use crate::location::LocationEngine;
+use crate::models::{Job, CompanyEntry, AtsType, HttpMethod, WorkableDetail, SmartRecruitersDetail, RecruiteeDetailResponse, format_date};
+use crate::parsers::{AtsParser, Severity, clean_html, clean_html_to_text, truncate_html};
+use crate::tag::{TagEngine, TagHierarchy, TagSource, description_quality, EducationDetector, PaidStatusDetector, ApplicationRequirementsDetector, BenefitsExtractor, detect_seniority};
+use crate::location::{LocationEngine, extract_timezone};
 use crate::config::Config;
-use log::{info, warn, error, debug};
+use crate::dedup::Deduplicator;
+use crate::metrics::{Metrics, NormalizationMetrics, NormalizationProfile, StepTimer};
+use crate::telemetry::{init_tracer_provider, otel_layer};
+use crate::checkpoint::Checkpoint;
+use crate::stats::{ScrapeStats, render_company_report};
+use crate::health::{AtsHealthReport, HealthRecorder};
+use crate::feed::RssExport;
+use crate::export::{FileExport, FileFormat, JsonStdoutExport, OutputFormat, ParquetExport};
+use crate::fanout::FanOutDb;
+use crate::validate::{SlugCheck, check_company, render_validation_report};
+use crate::ats_detect::AtsAutoDetector;
+use crate::circuit::{CircuitBreaker, CircuitState};
+use crate::schedule::ScrapeSchedule;
+use crate::last_run::LastRuns;
+use crate::utils::{job_fingerprint, normalize_domain, sha256_hex};
+use crate::ratelimit::DomainRateLimiter;
+use crate::country_filter::CountryFilter;
+use crate::normalization::DepartmentNormalizer;
+use crate::error::ScraperError;
+use crate::batch::{BatchConsumer, BatchItem, BatchSizer, FinishedCompany, JobLimit, CHANNEL_CAPACITY};
+use crate::turso::TursoDb;
+use crate::dry_run::DryRunDb;
+use crate::regex_test::{TitleCheck, render_title_check, render_title_table};
+use tracing::{info, warn, error, debug};
+use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::MakeWriter;
+use tracing_subscriber::layer::SubscriberExt;
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::util::SubscriberInitExt;
 
 // --- Database Abstraction ---
 
@@ -31,6 +84,9 @@ use log::{info, warn, error, debug};
 pub struct DbQuery {
     pub sql: String,
     pub params: Vec<Value>,
+    /// Debug label for error messages, e.g. "greenhouse:stripe:job_upsert".
+    #[serde(skip)]
+    pub context: Option<String>,
 }
 
 // Static regex for parameter replacement (compiled once)
@@ -45,7 +101,7 @@ impl DbQuery {
         // Create a map of index -> formatted value
         let formatted_params: HashMap<usize, String> = self.params.iter().enumerate().map(|(i, param)| {
              (i + 1, match param {
-                Value::String(s) => format!("'{}'", escape_sql_string(s)),
+                Value::String(s) => format!("'{}'", sanitize_sql_param(s)),
                 Value::Number(n) => n.to_string(),
                 Value::Bool(b) => if *b { "1".to_string() } else { "0".to_string() }, // SQLite uses 1/0 for bools
                 Value::Null => "NULL".to_string(),
@@ -64,74 +120,209 @@ impl DbQuery {
     }
 }
 
-fn escape_sql_string(input: &str) -> String {
-    input.replace('\'', "''")
+/// Makes `input` safe to wrap in single quotes. Inside a quoted literal `--`,
+/// `;`, `/* */` and backticks are plain text, so only the quote needs doubling;
+/// NUL bytes are dropped because SQLite stops reading the statement at one.
+fn sanitize_sql_param(input: &str) -> String {
+    input.replace('\0', "").replace('\'', "''")
+}
+
+/// Distinct query labels in a failed chunk, so the error says which jobs caused it.
+fn batch_context(queries: &[DbQuery]) -> String {
+    const MAX_LABELS: usize = 5;
+    let mut labels: Vec<&str> = Vec::new();
+    for label in queries.iter().filter_map(|q| q.context.as_deref()) {
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    if labels.is_empty() {
+        return "unlabelled queries".to_string();
+    }
+    // Batch-wide deletes say nothing about which job failed, so list them last
+    labels.sort_by_key(|l| l.starts_with("batch:"));
+    let extra = labels.len().saturating_sub(MAX_LABELS);
+    let mut out = labels[..labels.len().min(MAX_LABELS)].join(", ");
+    if extra > 0 {
+        out.push_str(&format!(" (+{} more)", extra));
+    }
+    out
+}
+
+/// Columns the job upsert rewrites when a stored job has changed.
+const JOB_UPSERT_SET: &str = "
+    title = excluded.title,
+    description = excluded.description,
+    company = excluded.company,
+    slug = excluded.slug,
+    ats = excluded.ats,
+    url = excluded.url,
+    company_url = excluded.company_url,
+    location = excluded.location,
+    city = excluded.city,
+    region = excluded.region,
+    country = excluded.country,
+    country_code = excluded.country_code,
+    posted = excluded.posted,
+    metro_area = excluded.metro_area,
+    seniority_level = excluded.seniority_level,
+    apply_url = excluded.apply_url,
+    company_industry = excluded.company_industry,
+    company_stage = excluded.company_stage,
+    logo_url = excluded.logo_url,
+    employment_type = excluded.employment_type,
+    ats_job_id = excluded.ats_job_id,
+    description_length = excluded.description_length,
+    description_quality_score = excluded.description_quality_score,
+    text_description = excluded.text_description,
+    description_hash = excluded.description_hash,
+    normalized_department = excluded.normalized_department,
+    preferred_timezone = excluded.preferred_timezone";
+
+/// Changes worth rewriting a stored job for.
+const JOB_CHANGED: &str = "
+    jobs.title != excluded.title OR
+    jobs.description_hash IS NOT excluded.description_hash OR
+    jobs.location != excluded.location OR
+    jobs.city IS NOT excluded.city OR
+    jobs.region IS NOT excluded.region OR
+    jobs.country IS NOT excluded.country OR
+    jobs.country_code IS NOT excluded.country_code OR
+    jobs.metro_area IS NOT excluded.metro_area OR
+    jobs.seniority_level IS NOT excluded.seniority_level OR
+    jobs.apply_url IS NOT excluded.apply_url OR
+    jobs.company_industry IS NOT excluded.company_industry OR
+    jobs.company_stage IS NOT excluded.company_stage OR
+    jobs.logo_url IS NOT excluded.logo_url OR
+    jobs.employment_type IS NOT excluded.employment_type OR
+    jobs.ats_job_id IS NOT excluded.ats_job_id OR
+    jobs.normalized_department IS NOT excluded.normalized_department OR
+    jobs.preferred_timezone IS NOT excluded.preferred_timezone";
+
+/// Reads what `get_job_fingerprints` needs, in the order `fingerprint_row` expects.
+const JOB_FINGERPRINTS_SQL: &str = "SELECT id, title, location, description_hash FROM jobs";
+
+/// `(id, fingerprint)` from a `JOB_FINGERPRINTS_SQL` row; NULLs read as empty.
+fn fingerprint_row(id: &str, title: Option<&str>, location: Option<&str>, description_hash: Option<&str>) -> (String, String) {
+    (id.to_string(), job_fingerprint(title.unwrap_or(""), location.unwrap_or(""), description_hash.unwrap_or("")))
+}
+
+/// Columns whose previous values are archived in `job_history` when they change.
+const HISTORY_FIELDS: [&str; 3] = ["title", "description", "location"];
+
+/// Archives the current value of each `HISTORY_FIELDS` column that `job` is
+/// about to change. Has to run before the upsert overwrites them; a job that
+/// isn't in the table yet selects nothing. The row is found by (slug,
+/// ats_job_id) like the upsert, so a job arriving under a new id keeps its
+/// history, filed under the new id.
+fn job_history_query(job: &Job, label: &str, now: DateTime<Utc>) -> DbQuery {
+    let sql = HISTORY_FIELDS.iter().enumerate()
+        .map(|(i, field)| format!("SELECT ?2, '{field}', {field}, ?{n}, ?1 FROM jobs WHERE slug = ?3 AND ats_job_id = ?4 AND {field} IS NOT ?{n}", n = i + 5))
+        .collect::<Vec<_>>()
+        .join(" UNION ALL ");
+    DbQuery {
+        sql: format!("INSERT INTO job_history (job_id, field_name, old_value, new_value, changed_at) {}", sql),
+        params: vec![
+            Value::String(format_date(now)),
+            Value::String(job.id.clone()),
+            Value::String(job.slug.clone()),
+            Value::String(job.ats_job_id.clone()),
+            Value::String(job.title.clone()),
+            Value::String(job.description.clone()),
+            Value::String(job.location.clone()),
+        ],
+        context: Some(format!("{}:job_history", label)),
+    }
+}
+
+/// Inserts for the `countries` and `regions` tables; existing rows are kept.
+fn geo_queries(countries: &HashMap<String, String>, regions: &HashMap<String, String>) -> Vec<DbQuery> {
+    let mut queries = Vec::new();
+    for (code, name) in countries {
+        queries.push(DbQuery {
+            sql: "INSERT OR IGNORE INTO countries (code, name) VALUES (?1, ?2)".to_string(),
+            params: vec![Value::String(code.clone()), Value::String(name.clone())],
+            context: Some("geo:countries".to_string()),
+        });
+    }
+    for (id, name) in regions {
+        let country_code = id.split('.').next().unwrap_or("").to_string();
+        queries.push(DbQuery {
+            sql: "INSERT OR IGNORE INTO regions (id, country_code, name) VALUES (?1, ?2, ?3)".to_string(),
+            params: vec![Value::String(id.clone()), Value::String(country_code), Value::String(name.clone())],
+            context: Some("geo:regions".to_string()),
+        });
+    }
+    queries
 }
 
 #[async_trait::async_trait]
 trait JobDb: Send + Sync {
     async fn execute_batch(&self, queries: &[DbQuery]) -> Result<()>;
     async fn get_existing_ids(&self) -> Result<HashSet<String>>;
+    /// Each stored job's `utils::job_fingerprint`, so the batch consumer can
+    /// tell which jobs scraped again have changed. Backends that don't keep the
+    /// fingerprinted fields return nothing and never see a job as changed.
+    async fn get_job_fingerprints(&self) -> Result<HashMap<String, String>> {
+        Ok(HashMap::new())
+    }
     async fn initialize_geo_tables(&self, countries: &HashMap<String, String>, regions: &HashMap<String, String>) -> Result<()>;
+    /// Run once before scraping so a bad connection fails fast instead of
+    /// after every company has been fetched.
+    async fn health_check(&self) -> Result<()> {
+        self.execute_batch(&[DbQuery { sql: "SELECT 1".to_string(), params: vec![], context: Some("health_check".to_string()) }]).await
+    }
+    /// Bumps `last_seen` on jobs scraped again that were already stored, which
+    /// the batch consumer skips instead of passing to `insert_jobs`.
+    async fn mark_seen(&self, ids: &[String]) -> Result<()> {
+        if ids.is_empty() { return Ok(()); }
+        let ids: Vec<Value> = ids.iter().map(|id| Value::String(id.clone())).collect();
+        self.execute_batch(&[last_seen_query(&ids, Utc::now())]).await
+    }
+    /// Called once every batch has been flushed, for backends that buffer
+    /// jobs and write them all at the end.
+    async fn finalize(&self) -> Result<()> {
+        Ok(())
+    }
+    /// Whether inserted jobs end up in a database. Scheduler, breaker and
+    /// last-run state is only written after runs that do.
+    fn persists_jobs(&self) -> bool {
+        true
+    }
     async fn insert_jobs(&self, jobs: &[Job]) -> Result<()> {
         if jobs.is_empty() { return Ok(()); }
         
         let mut queries = Vec::new();
+        let now = Utc::now();
         
-        // Batch DELETE for junction tables (one query per table for all jobs)
-        if !jobs.is_empty() {
-            let job_ids: Vec<Value> = jobs.iter().map(|j| Value::String(j.id.clone())).collect();
-            let placeholders: String = (1..=job_ids.len()).map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ");
-            
-            queries.push(DbQuery {
-                sql: format!("DELETE FROM job_degree_levels WHERE job_id IN ({})", placeholders),
-                params: job_ids.clone(),
-            });
-            queries.push(DbQuery {
-                sql: format!("DELETE FROM job_subject_areas WHERE job_id IN ({})", placeholders),
-                params: job_ids.clone(),
-            });
-            queries.push(DbQuery {
-                sql: format!("DELETE FROM job_departments WHERE job_id IN ({})", placeholders),
-                params: job_ids.clone(),
-            });
-            queries.push(DbQuery {
-                sql: format!("DELETE FROM job_offices WHERE job_id IN ({})", placeholders),
-                params: job_ids.clone(),
-            });
+        // Batch DELETE for junction tables (one query per table for all jobs).
+        // Keyed on (slug, ats_job_id) rather than id, so the rows of a stored
+        // job that the upsert below moves to a new id are cleared too.
+        let keys: Vec<Value> = jobs.iter()
+            .flat_map(|j| [Value::String(j.slug.clone()), Value::String(j.ats_job_id.clone())])
+            .collect();
+        let rows: String = (0..jobs.len()).map(|i| format!("(?{}, ?{})", 2 * i + 1, 2 * i + 2)).collect::<Vec<_>>().join(", ");
+        for table in ["job_degree_levels", "job_subject_areas", "job_departments", "job_offices", "job_tags", "job_benefits"] {
             queries.push(DbQuery {
-                sql: format!("DELETE FROM job_tags WHERE job_id IN ({})", placeholders),
-                params: job_ids.clone(),
+                sql: format!("DELETE FROM {} WHERE job_id IN (SELECT id FROM jobs WHERE (slug, ats_job_id) IN (VALUES {}))", table, rows),
+                params: keys.clone(),
+                context: Some(format!("batch:{}_delete", table)),
             });
         }
         
         for job in jobs {
-            // UPSERT main job record with change detection
+            let label = format!("{}:{}", serde_json::to_string(&job.ats)?.trim_matches('"'), job.slug);
+            queries.push(job_history_query(job, &label, now));
+
+            // UPSERT main job record with change detection. A job reaching the
+            // same (slug, ats_job_id) under a new id, e.g. after the ATS prefix
+            // changed, takes over the old row; its junction rows were deleted above.
             queries.push(DbQuery {
-                sql: r#"INSERT INTO jobs (id, title, description, company, slug, ats,url, company_url, location, city, region, country, country_code, posted) 
-                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14)
-                        ON CONFLICT(id) DO UPDATE SET
-                            title = excluded.title,
-                            description = excluded.description,
-                            company = excluded.company,
-                            slug = excluded.slug,
-                            ats = excluded.ats,
-                            url = excluded.url,
-                            company_url = excluded.company_url,
-                            location = excluded.location,
-                            city = excluded.city,
-                            region = excluded.region,
-                            country = excluded.country,
-                            country_code = excluded.country_code,
-                            posted = excluded.posted
-                        WHERE 
-                            jobs.title != excluded.title OR
-                            jobs.description != excluded.description OR
-                            jobs.location != excluded.location OR
-                            jobs.city IS NOT excluded.city OR
-                            jobs.region IS NOT excluded.region OR
-                            jobs.country IS NOT excluded.country OR
-                            jobs.country_code IS NOT excluded.country_code"#.to_string(),
+                sql: format!(r#"INSERT INTO jobs (id, title, description, company, slug, ats,url, company_url, location, city, region, country, country_code, posted, metro_area, seniority_level, apply_url, company_industry, company_stage, ats_job_id, description_length, description_quality_score, text_description, description_hash, last_seen, normalized_department, preferred_timezone, logo_url, employment_type) 
+                        VALUES (?1, ?2, ?3, ?4, ?5, ?6, ?7, ?8, ?9, ?10, ?11, ?12, ?13, ?14, ?15, ?16, ?17, ?18, ?19, ?20, ?21, ?22, ?23, ?24, ?25, ?26, ?27, ?28, ?29)
+                        ON CONFLICT(id) DO UPDATE SET {set} WHERE {changed}
+                        ON CONFLICT(slug, ats_job_id) DO UPDATE SET id = excluded.id, {set}"#,
+                    set = JOB_UPSERT_SET, changed = JOB_CHANGED),
                 params: vec![
                     Value::String(job.id.clone()),
                     Value::String(job.title.clone()),
@@ -146,8 +337,24 @@ trait JobDb: Send + Sync {
                     job.region.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
                     job.country.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
                     job.country_code.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
-                    Value::String(job.posted.clone()),
+                    job.posted.map(|p| Value::String(format_date(p))).unwrap_or(Value::Null),
+                    job.metro_area.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
+                    serde_json::to_value(job.seniority_level)?,
+                    job.apply_url.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
+                    job.company_industry.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
+                    job.company_stage.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
+                    Value::String(job.ats_job_id.clone()),
+                    Value::from(job.description_length),
+                    Value::from(job.description_quality_score),
+                    job.text_description.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
+                    Value::String(job.description_hash.clone()),
+                    Value::String(format_date(now)),
+                    job.normalized_department.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
+                    job.preferred_timezone.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
+                    job.logo_url.as_ref().map(|s| Value::String(s.clone())).unwrap_or(Value::Null),
+                    serde_json::to_value(job.employment_type)?,
                 ],
+                context: Some(format!("{}:job_upsert", label)),
             });
 
             // Insert fresh junction table records
@@ -155,12 +362,14 @@ trait JobDb: Send + Sync {
                 queries.push(DbQuery {
                     sql: "INSERT OR IGNORE INTO job_degree_levels (job_id, name) VALUES (?1, ?2)".to_string(),
                     params: vec![Value::String(job.id.clone()), Value::String(degree.clone())],
+                    context: Some(format!("{}:job_degree_levels", label)),
                 });
             }
             for subject in &job.subject_areas {
                 queries.push(DbQuery {
                     sql: "INSERT OR IGNORE INTO job_subject_areas (job_id, name) VALUES (?1, ?2)".to_string(),
                     params: vec![Value::String(job.id.clone()), Value::String(subject.clone())],
+                    context: Some(format!("{}:job_subject_areas", label)),
                 });
             }
 
@@ -168,25 +377,50 @@ trait JobDb: Send + Sync {
                 queries.push(DbQuery {
                     sql: "INSERT OR IGNORE INTO job_departments (job_id, name) VALUES (?1, ?2)".to_string(),
                     params: vec![Value::String(job.id.clone()), Value::String(dept.clone())],
+                    context: Some(format!("{}:job_departments", label)),
                 });
             }
             for office in &job.offices {
                 queries.push(DbQuery {
                     sql: "INSERT OR IGNORE INTO job_offices (job_id, name) VALUES (?1, ?2)".to_string(),
                     params: vec![Value::String(job.id.clone()), Value::String(office.clone())],
+                    context: Some(format!("{}:job_offices", label)),
                 });
             }
             for tag in &job.tags {
                 queries.push(DbQuery {
                     sql: "INSERT OR IGNORE INTO job_tags (job_id, name) VALUES (?1, ?2)".to_string(),
                     params: vec![Value::String(job.id.clone()), Value::String(tag.clone())],
+                    context: Some(format!("{}:job_tags", label)),
+                });
+            }
+            for benefit in &job.benefits {
+                queries.push(DbQuery {
+                    sql: "INSERT OR IGNORE INTO job_benefits (job_id, name) VALUES (?1, ?2)".to_string(),
+                    params: vec![Value::String(job.id.clone()), Value::String(benefit.clone())],
+                    context: Some(format!("{}:job_benefits", label)),
                 });
             }
         }
+
+        // The upsert skips unchanged rows, so last_seen gets its own update
+        let job_ids: Vec<Value> = jobs.iter().map(|j| Value::String(j.id.clone())).collect();
+        queries.push(last_seen_query(&job_ids, now));
         self.execute_batch(&queries).await
     }
 }
 
+fn last_seen_query(job_ids: &[Value], now: DateTime<Utc>) -> DbQuery {
+    let placeholders: String = (2..=job_ids.len() + 1).map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ");
+    let mut params = vec![Value::String(format_date(now))];
+    params.extend_from_slice(job_ids);
+    DbQuery {
+        sql: format!("UPDATE jobs SET last_seen = ?1 WHERE id IN ({})", placeholders),
+        params,
+        context: Some("batch:last_seen".to_string()),
+    }
+}
+
 
 fn run_wrangler(args: Vec<&str>) -> Result<std::process::Output> {
     let mut cmd = if cfg!(windows) {
@@ -205,6 +439,23 @@ struct LocalWranglerD1 {
     database_name: String,
 }
 
+impl LocalWranglerD1 {
+    /// Result rows of one SELECT, as objects keyed by column name.
+    fn query_rows(&self, sql: &str) -> Result<Vec<Value>> {
+        let output = run_wrangler(vec![&self.database_name, "--local", "--command", sql, "--json"])?;
+
+        if !output.status.success() {
+            let err = String::from_utf8_lossy(&output.stderr);
+            return Err(anyhow::anyhow!("Wrangler D1 query failed: {}", err));
+        }
+
+        let stdout = String::from_utf8_lossy(&output.stdout);
+        let json_start = stdout.find('[').or(stdout.find('{')).unwrap_or(0);
+        let data: Value = serde_json::from_str(&stdout[json_start..])?;
+        Ok(data[0]["results"].as_array().cloned().unwrap_or_default())
+    }
+}
+
 #[async_trait::async_trait]
 impl JobDb for LocalWranglerD1 {
     async fn execute_batch(&self, queries: &[DbQuery]) -> Result<()> {
@@ -226,34 +477,35 @@ impl JobDb for LocalWranglerD1 {
 
             if !output.status.success() {
                 let err = String::from_utf8_lossy(&output.stderr);
-                error!("Wrangler D1 execution failed: {}", err);
-                return Err(anyhow::anyhow!("Wrangler D1 execution failed: {}", err));
+                let context = batch_context(chunk);
+                error!("Wrangler D1 execution failed ({}): {}", context, err);
+                return Err(anyhow::anyhow!("Wrangler D1 execution failed ({}): {}", context, err));
             }
         }
         Ok(())
     }
 
-    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
-        let output = run_wrangler(vec![&self.database_name, "--local", "--command", "SELECT id FROM jobs", "--json"])?;
-
+    /// A single `--command` instead of the temp-file transaction `execute_batch` writes.
+    async fn health_check(&self) -> Result<()> {
+        let output = run_wrangler(vec![&self.database_name, "--local", "--command", "SELECT 1", "--json"])
+            .context("Failed to run wrangler")?;
         if !output.status.success() {
             let err = String::from_utf8_lossy(&output.stderr);
-            return Err(anyhow::anyhow!("Wrangler D1 query failed: {}", err));
+            return Err(anyhow::anyhow!("Wrangler D1 health check failed: {}", err));
         }
+        Ok(())
+    }
 
-        let stdout = String::from_utf8_lossy(&output.stdout);
-        let json_start = stdout.find('[').or(stdout.find('{')).unwrap_or(0);
-        let data: Value = serde_json::from_str(&stdout[json_start..])?;
-        
-        let mut ids = HashSet::new();
-        if let Some(results) = data[0]["results"].as_array() {
-            for row in results {
-                if let Some(id) = row["id"].as_str() {
-                    ids.insert(id.to_string());
-                }
-            }
-        }
-        Ok(ids)
+    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+        let rows = self.query_rows("SELECT id FROM jobs")?;
+        Ok(rows.iter().filter_map(|row| row["id"].as_str().map(String::from)).collect())
+    }
+
+    async fn get_job_fingerprints(&self) -> Result<HashMap<String, String>> {
+        let rows = self.query_rows(JOB_FINGERPRINTS_SQL)?;
+        Ok(rows.iter().filter_map(|row| {
+            Some(fingerprint_row(row["id"].as_str()?, row["title"].as_str(), row["location"].as_str(), row["description_hash"].as_str()))
+        }).collect())
     }
 
     async fn initialize_geo_tables(&self, countries: &HashMap<String, String>, regions: &HashMap<String, String>) -> Result<()> {
@@ -262,48 +514,83 @@ impl JobDb for LocalWranglerD1 {
         if output.status.success() {
             let stdout = String::from_utf8_lossy(&output.stdout);
             let json_start = stdout.find('[').or(stdout.find('{')).unwrap_or(0);
-            if let Ok(data) = serde_json::from_str::<Value>(&stdout[json_start..]) {
-                if let Some(results) = data[0]["results"].as_array() {
-                    if let Some(count) = results.first().and_then(|r| r["count"].as_i64()) {
-                        if count > 0 {
-                            info!("Geo tables already initialized ({} countries found). Skipping...", count);
-                            return Ok(());
-                        }
-                    }
-                }
+            if let Ok(data) = serde_json::from_str::<Value>(&stdout[json_start..])
+                && let Some(results) = data[0]["results"].as_array()
+                && let Some(count) = results.first().and_then(|r| r["count"].as_i64())
+                && count > 0
+            {
+                info!("Geo tables already initialized ({} countries found). Skipping...", count);
+                return Ok(());
             }
         }
 
-        let mut queries = Vec::new();
-        for (code, name) in countries {
-            queries.push(DbQuery {
-                sql: "INSERT OR IGNORE INTO countries (code, name) VALUES (?1, ?2)".to_string(),
-                params: vec![Value::String(code.clone()), Value::String(name.clone())],
-            });
-        }
-        for (id, name) in regions {
-            let country_code = id.split('.').next().unwrap_or("").to_string();
-            queries.push(DbQuery {
-                sql: "INSERT OR IGNORE INTO regions (id, country_code, name) VALUES (?1, ?2, ?3)".to_string(),
-                params: vec![Value::String(id.clone()), Value::String(country_code), Value::String(name.clone())],
-            });
-        }
-        self.execute_batch(&queries).await
+        self.execute_batch(&geo_queries(countries, regions)).await
     }
 }
 
+const D1_API_BASE: &str = "https://api.cloudflare.com/client/v4";
+/// Rows per `get_existing_ids` request; one unpaged SELECT can hit D1's 10MB result limit.
+const D1_ID_PAGE_SIZE: usize = 1000;
+
 struct RemoteD1 {
     client: reqwest::Client,
+    /// `D1_API_BASE`, or a mock server in tests.
+    api_base: String,
     account_id: String,
     database_id: String,
     api_token: String,
 }
 
+impl RemoteD1 {
+    fn raw_url(&self) -> String {
+        format!("{}/accounts/{}/d1/database/{}/raw", self.api_base, self.account_id, self.database_id)
+    }
+
+    /// Rows of `select` (a `SELECT ... FROM jobs` whose first column is `id`),
+    /// fetched `D1_ID_PAGE_SIZE` at a time. The raw endpoint returns rows as
+    /// arrays in SELECT column order.
+    async fn paged_rows(&self, select: &str) -> Result<Vec<Value>> {
+        let url = self.raw_url();
+        let mut all = Vec::new();
+        // Keyset pagination: rows inserted mid-read can't shift later pages
+        let mut after = String::new();
+        loop {
+            let payload = DbQuery {
+                sql: format!("{} WHERE id > ?1 ORDER BY id LIMIT {}", select, D1_ID_PAGE_SIZE),
+                params: vec![Value::String(after.clone())],
+                context: None,
+            };
+
+            let resp = self.client.post(&url)
+                .bearer_auth(&self.api_token)
+                .json(&payload)
+                .send()
+                .await?;
+
+            if !resp.status().is_success() {
+                let text = resp.text().await?;
+                return Err(anyhow::anyhow!("D1 API Error: {}", text));
+            }
+
+            let data: Value = resp.json().await?;
+            let rows = data["result"][0]["results"]["rows"].as_array().cloned().unwrap_or_default();
+            let full = rows.len() == D1_ID_PAGE_SIZE;
+            let last = rows.last().and_then(|row| row[0].as_str()).map(String::from);
+            all.extend(rows);
+            match last {
+                Some(last) if full => after = last,
+                _ => break,
+            }
+        }
+        Ok(all)
+    }
+}
+
 #[async_trait::async_trait]
 impl JobDb for RemoteD1 {
     async fn execute_batch(&self, queries: &[DbQuery]) -> Result<()> {
         for chunk in queries.chunks(50) {
-            let url = format!("https://api.cloudflare.com/client/v4/accounts/{}/d1/database/{}/raw", self.account_id, self.database_id);
+            let url = self.raw_url();
             
             // Combine all statements into a single SQL string with semicolons
             let combined_sql: String = chunk.iter()
@@ -320,58 +607,41 @@ impl JobDb for RemoteD1 {
 
             if !resp.status().is_success() {
                 let text = resp.text().await?;
-                return Err(anyhow::anyhow!("D1 API Error: {}", text));
+                return Err(anyhow::anyhow!("D1 API Error ({}): {}", batch_context(chunk), text));
             }
         }
         Ok(())
     }
 
-    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
-        let url = format!("https://api.cloudflare.com/client/v4/accounts/{}/d1/database/{}/query", self.account_id, self.database_id);
-        let payload = DbQuery {
-            sql: "SELECT id FROM jobs".to_string(),
-            params: vec![],
-        };
-
-        let resp = self.client.post(&url)
+    /// Fetches the database's metadata, which checks the token and ids without running SQL.
+    async fn health_check(&self) -> Result<()> {
+        let url = format!("{}/accounts/{}/d1/database/{}", self.api_base, self.account_id, self.database_id);
+        let resp = self.client.get(&url)
             .bearer_auth(&self.api_token)
-            .json(&payload)
             .send()
             .await?;
-
         if !resp.status().is_success() {
+            let status = resp.status();
             let text = resp.text().await?;
-            return Err(anyhow::anyhow!("D1 API Error: {}", text));
+            return Err(anyhow::anyhow!("D1 API Error ({}): {}", status, text));
         }
+        Ok(())
+    }
 
-        let data: Value = resp.json().await?;
-        let mut ids = HashSet::new();
-        if let Some(results) = data["result"][0]["results"].as_array() {
-            for row in results {
-                if let Some(id) = row["id"].as_str() {
-                    ids.insert(id.to_string());
-                }
-            }
-        }
-        Ok(ids)
+    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+        let rows = self.paged_rows("SELECT id FROM jobs").await?;
+        Ok(rows.iter().filter_map(|row| row[0].as_str().map(String::from)).collect())
+    }
+
+    async fn get_job_fingerprints(&self) -> Result<HashMap<String, String>> {
+        let rows = self.paged_rows(JOB_FINGERPRINTS_SQL).await?;
+        Ok(rows.iter().filter_map(|row| {
+            Some(fingerprint_row(row[0].as_str()?, row[1].as_str(), row[2].as_str(), row[3].as_str()))
+        }).collect())
     }
 
     async fn initialize_geo_tables(&self, countries: &HashMap<String, String>, regions: &HashMap<String, String>) -> Result<()> {
-        let mut queries = Vec::new();
-        for (code, name) in countries {
-            queries.push(DbQuery {
-                sql: "INSERT OR IGNORE INTO countries (code, name) VALUES (?1, ?2)".to_string(),
-                params: vec![Value::String(code.clone()), Value::String(name.clone())],
-            });
-        }
-        for (id, name) in regions {
-            let country_code = id.split('.').next().unwrap_or("").to_string();
-            queries.push(DbQuery {
-                sql: "INSERT OR IGNORE INTO regions (id, country_code, name) VALUES (?1, ?2, ?3)".to_string(),
-                params: vec![Value::String(id.clone()), Value::String(country_code), Value::String(name.clone())],
-            });
-        }
-        self.execute_batch(&queries).await
+        self.execute_batch(&geo_queries(countries, regions)).await
     }
 }
 
@@ -384,17 +654,101 @@ fn load_json<T: for<'a> Deserialize<'a>>(path: &str) -> Result<T> {
         .with_context(|| format!("Failed to parse JSON from: {}", path))
 }
 
+/// Concatenates every slugs file in order, ready for `validate_companies`.
+fn load_companies(files: &[String]) -> Result<Vec<CompanyEntry>> {
+    let mut companies = Vec::new();
+    for file in files {
+        let loaded: Vec<CompanyEntry> = load_json(file)
+            .context(format!("Failed to load {}", file))?;
+        info!("Loaded {} companies from {}", loaded.len(), file);
+        companies.extend(loaded);
+    }
+    if files.len() > 1 {
+        info!("Loaded {} companies from {} slugs files", companies.len(), files.len());
+    }
+    Ok(companies)
+}
+
+/// `--ats-type=greenhouse,ashby`, matched case-insensitively against the
+/// `type` spellings in slugs.json.
+fn parse_ats_types(list: &str) -> Result<Vec<AtsType>> {
+    let name = |t: &AtsType| serde_json::to_value(t).ok().and_then(|v| v.as_str().map(String::from)).unwrap_or_default();
+    list.split(',').map(str::trim).filter(|s| !s.is_empty()).map(|requested| {
+        match AtsType::from_name(requested) {
+            AtsType::Unknown => {
+                let available: Vec<String> = AtsType::KNOWN.iter().map(name).collect();
+                bail!("Unknown ATS type {:?} (available: {})", requested, available.join(", "))
+            }
+            ats => Ok(ats),
+        }
+    }).collect()
+}
+
+/// Accepted `stage` values in slugs.json, in their stored spelling.
+const KNOWN_STAGES: &[&str] = &[
+    "Pre-Seed", "Seed", "Series A", "Series B", "Series C", "Series D", "Series E+",
+    "Startup", "Growth", "Private", "Public", "Acquired", "Non-Profit", "Government",
+];
+
+/// Rejects entries whose URLs don't parse and slugs shared by different boards,
+/// since `job.slug` must identify one company. The same board listed twice (as
+/// happens across slugs files) is merged: the first entry wins unless a later
+/// one has a `domain` and it doesn't.
+fn validate_companies(companies: &[CompanyEntry]) -> Result<Vec<CompanyEntry>> {
+    let mut seen: HashMap<&str, usize> = HashMap::new();
+    let mut unique: Vec<&CompanyEntry> = Vec::with_capacity(companies.len());
+    for company in companies {
+        if let Some(&i) = seen.get(company.slug.as_str()) {
+            if unique[i].api_url != company.api_url {
+                bail!("Slug {:?} is used by both {} ({}) and {} ({})",
+                    company.slug, unique[i].name, unique[i].api_url, company.name, company.api_url);
+            }
+            warn!(company = %company.name, "Duplicate slug {}, skipping", company.slug);
+            if unique[i].domain.is_none() && company.domain.is_some() {
+                unique[i] = company;
+            }
+            continue;
+        }
+        seen.insert(&company.slug, unique.len());
+        unique.push(company);
+    }
+
+    let mut valid = Vec::with_capacity(unique.len());
+    for company in unique {
+        reqwest::Url::parse(&company.api_url)
+            .with_context(|| format!("{}: invalid api_url {:?}", company.name, company.api_url))?;
+        if let Some(url) = &company.custom_api_url {
+            reqwest::Url::parse(url)
+                .with_context(|| format!("{}: invalid custom_api_url {:?}", company.name, url))?;
+        }
+        if company.ats_type == AtsType::Unknown && company.custom_api_url.is_none() {
+            warn!(company = %company.name, "No type or custom_api_url; ATS will be guessed from {}", company.api_url);
+        }
+        let mut company = company.clone();
+        if let Some(stage) = &company.stage {
+            // Case-insensitive, but stored with one spelling so filters match
+            let known = KNOWN_STAGES.iter().find(|s| s.eq_ignore_ascii_case(stage.trim()))
+                .with_context(|| format!("{}: unknown stage {:?} (expected one of {})", company.name, stage, KNOWN_STAGES.join(", ")))?;
+            company.stage = Some(known.to_string());
+        }
+        valid.push(company);
+    }
+    Ok(valid)
+}
+
 
 // --- Scraper Implementation ---
 
-async fn enrich_workable(client: &reqwest::Client, job_id: &str, company_slug: &str) -> Result<Option<String>> {
+async fn enrich_workable(client: &reqwest::Client, limiter: &DomainRateLimiter, job_id: &str, company_slug: &str) -> Result<Option<String>> {
     let detail_url = format!("https://apply.workable.com/api/v2/accounts/{}/jobs/{}", 
-        company_slug, job_id.strip_prefix("workable-").unwrap_or(job_id));
+        company_slug, job_id);
     
+    let permit = limiter.acquire(&detail_url).await;
     let resp = client.get(&detail_url).send().await?;
     if !resp.status().is_success() { return Ok(None); }
     
     let detail = resp.json::<WorkableDetail>().await?;
+    drop(permit);
     let mut desc = detail.description.unwrap_or_default();
     if let Some(req) = detail.requirements {
         desc.push_str("<h3>Requirements</h3>");
@@ -407,27 +761,27 @@ async fn enrich_workable(client: &reqwest::Client, job_id: &str, company_slug: &
     Ok(Some(clean_html(&desc)))
 }
 
-async fn enrich_smartrecruiters(client: &reqwest::Client, job_id: &str, company_slug: &str) -> Result<Option<String>> {
-    let job_id = job_id.strip_prefix("smartrecruiters-").unwrap_or(job_id);
+async fn enrich_smartrecruiters(client: &reqwest::Client, limiter: &DomainRateLimiter, job_id: &str, company_slug: &str) -> Result<Option<String>> {
     let detail_url = format!("https://api.smartrecruiters.com/v1/companies/{}/postings/{}", company_slug, job_id);
     
+    let permit = limiter.acquire(&detail_url).await;
     let resp = client.get(&detail_url).send().await?;
     if !resp.status().is_success() { return Ok(None); }
     
     let detail = resp.json::<SmartRecruitersDetail>().await?;
+    drop(permit);
     let mut desc = String::new();
     let sections = &detail.job_ad.sections;
     
     let mut add_section = |section: &Option<crate::models::SmartRecruitersSection>| {
-        if let Some(sec) = section {
-            if let Some(text) = &sec.text {
-                if !text.is_empty() {
-                    if let Some(title) = &sec.title {
-                        desc.push_str(&format!("<h3>{}</h3>", title));
-                    }
-                    desc.push_str(text);
-                }
+        if let Some(sec) = section
+            && let Some(text) = &sec.text
+            && !text.is_empty()
+        {
+            if let Some(title) = &sec.title {
+                desc.push_str(&format!("<h3>{}</h3>", title));
             }
+            desc.push_str(text);
         }
     };
 
@@ -438,14 +792,16 @@ async fn enrich_smartrecruiters(client: &reqwest::Client, job_id: &str, company_
     Ok(Some(clean_html(&desc)))
 }
 
-async fn enrich_recruitee(client: &reqwest::Client, url: &str, company_slug: &str) -> Result<Option<String>> {
+async fn enrich_recruitee(client: &reqwest::Client, limiter: &DomainRateLimiter, url: &str, company_slug: &str) -> Result<Option<String>> {
     let Some(slug) = url.split("/o/").last() else { return Ok(None); };
     let detail_url = format!("https://{}.recruitee.com/api/offers/{}", company_slug, slug);
     
+    let permit = limiter.acquire(&detail_url).await;
     let resp = client.get(&detail_url).send().await?;
     if !resp.status().is_success() { return Ok(None); }
     
     let detail = resp.json::<RecruiteeDetailResponse>().await?;
+    drop(permit);
     let mut desc = detail.offer.description.unwrap_or_default();
     if let Some(req) = detail.offer.requirements {
         desc.push_str("<h3>Requirements</h3>");
@@ -458,33 +814,48 @@ async fn enrich_recruitee(client: &reqwest::Client, url: &str, company_slug: &st
     Ok(Some(clean_html(&desc)))
 }
 
-async fn enrich_breezy(client: &reqwest::Client, url: &str) -> Result<Option<String>> {
+async fn enrich_breezy(client: &reqwest::Client, limiter: &DomainRateLimiter, url: &str) -> Result<Option<String>> {
+    let permit = limiter.acquire(url).await;
     let resp = client.get(url).send().await?;
     if !resp.status().is_success() { return Ok(None); }
     
     let html = resp.text().await?;
-    let re = regex::Regex::new(r#"(?s)<script type="application/ld\+json">(.*?)</script>"#).expect("Invalid regex");
-    
-    let desc = re.captures_iter(&html)
-        .nth(1) // Usually the second one
-        .and_then(|cap| cap.get(1))
-        .and_then(|m| serde_json::from_str::<crate::models::BreezyLdJson>(m.as_str()).ok())
-        .and_then(|ld| ld.description)
-        .map(|d| clean_html(&d));
+    drop(permit);
+    Ok(extract_ld_json_description(&html).map(|d| clean_html(&d)))
+}
+
+static LD_JSON_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r#"(?is)<script[^>]*type\s*=\s*["']application/ld\+json["'][^>]*>(.*?)</script>"#).unwrap()
+});
+
+/// Description from the first LD+JSON block that has one. Breezy pages usually
+/// carry an Organization block before the JobPosting, so don't rely on position.
+fn extract_ld_json_description(html: &str) -> Option<String> {
+    LD_JSON_REGEX.captures_iter(html)
+        .filter_map(|cap| serde_json::from_str::<crate::models::BreezyLdJson>(cap[1].trim()).ok())
+        .find_map(|ld| ld.description.filter(|d| !d.trim().is_empty()))
+}
 
-    Ok(desc)
+/// Jobs whose ATS listing has no description, so `enrich_job` fetches it separately.
+fn needs_enrichment(j: &Job) -> bool {
+    j.description.is_empty()
+        && matches!(j.ats, AtsType::Workable | AtsType::SmartRecruiters | AtsType::Recruitee | AtsType::Breezy)
 }
 
-async fn enrich_job(client: &reqwest::Client, mut j: Job, company_slug: &str) -> Result<Job> {
-    if !j.description.is_empty() { return Ok(j); }
+/// Fills in a missing description from the job's detail page, holding a
+/// per-domain slot like the board fetch. With `enrich` off (`--no-enrich`)
+/// the job is returned as-is.
+#[tracing::instrument(name = "enrich.job", skip_all, fields(job.id = %j.id, job.ats = ?j.ats))]
+async fn enrich_job(client: &reqwest::Client, limiter: &DomainRateLimiter, mut j: Job, company_slug: &str, enrich: bool) -> Result<Job, ScraperError> {
+    if !enrich || !needs_enrichment(&j) { return Ok(j); }
 
     let description = match j.ats {
-        AtsType::Workable => enrich_workable(client, &j.id, company_slug).await?,
-        AtsType::SmartRecruiters => enrich_smartrecruiters(client, &j.id, company_slug).await?,
-        AtsType::Recruitee => enrich_recruitee(client, &j.url, company_slug).await?,
-        AtsType::Breezy => enrich_breezy(client, &j.url).await?,
-        _ => None,
-    };
+        AtsType::Workable => enrich_workable(client, limiter, &j.ats_job_id, company_slug).await,
+        AtsType::SmartRecruiters => enrich_smartrecruiters(client, limiter, &j.ats_job_id, company_slug).await,
+        AtsType::Recruitee => enrich_recruitee(client, limiter, &j.url, company_slug).await,
+        AtsType::Breezy => enrich_breezy(client, limiter, &j.url).await,
+        _ => Ok(None),
+    }.map_err(|e| ScraperError::EnrichmentError { job_id: j.id.clone(), detail: format!("{:#}", e) })?;
 
     if let Some(desc) = description {
         j.description = desc;
@@ -493,52 +864,223 @@ async fn enrich_job(client: &reqwest::Client, mut j: Job, company_slug: &str) ->
     Ok(j)
 }
 
+static PAID_DETECTOR: Lazy<PaidStatusDetector> = Lazy::new(PaidStatusDetector::new);
+static REQUIREMENTS_DETECTOR: Lazy<ApplicationRequirementsDetector> = Lazy::new(ApplicationRequirementsDetector::new);
+static BENEFITS_EXTRACTOR: Lazy<BenefitsExtractor> = Lazy::new(BenefitsExtractor::new);
+/// Per-step `normalize_job` timings, only collected with `ZAPPLY_PROFILE=1`.
+static NORMALIZATION_PROFILE: Lazy<Option<Mutex<NormalizationProfile>>> = Lazy::new(|| {
+    std::env::var("ZAPPLY_PROFILE").is_ok_and(|v| v == "1").then(|| Mutex::new(NormalizationProfile::default()))
+});
+
 fn normalize_job(
     mut j: Job, 
     company: &CompanyEntry, 
     tag_engine: &TagEngine, 
     edu_detector: &EducationDetector, 
-    location_engine: &LocationEngine
+    department_normalizer: &DepartmentNormalizer,
+    location_engine: &LocationEngine,
+    max_description_chars: Option<usize>,
 ) -> Job {
-    j.company_url = company.domain.clone();
-
-    // 1. Detect tags
-    let mut unique_tags = HashSet::new();
-    unique_tags.extend(j.tags);
-    unique_tags.extend(tag_engine.detect_tags(&j.description).into_iter().map(String::from));
-    unique_tags.extend(tag_engine.detect_tags(&j.title).into_iter().map(String::from));
-    j.tags = unique_tags.into_iter().collect();
+    j.company_url = company.domain.as_deref().and_then(normalize_domain);
+    j.company_industry = company.industry.clone();
+    j.company_stage = company.stage.clone();
+    let mut timer = StepTimer::start(NORMALIZATION_PROFILE.is_some());
+
+    // 1. Detect tags. TagEngine spellings go first so they win the
+    // case-insensitive dedupe at the end; the parser's tags go last.
+    let parser_tags = std::mem::take(&mut j.tags);
+    let mut tags: Vec<String> = tag_engine.detect_tags_with_source(&j.title, TagSource::Title).into_iter()
+        .chain(tag_engine.detect_tags_with_source(&j.description, TagSource::Description))
+        .filter(|m| m.confidence >= tag_engine.min_confidence())
+        .map(|m| m.tag.to_string())
+        .collect();
+    let tag_detection_us = timer.lap();
     
     // 2. Detect education info
     let combined_text = format!("{} {}", j.title, j.description);
     let edu_info = edu_detector.detect(&combined_text);
+    let edu_detection_us = timer.lap();
     j.degree_levels = edu_info.degree_levels;
     j.subject_areas = edu_info.subject_areas;
+    let edu_tag = if edu_info.degree_not_required {
+        Some("Education Not Required")
+    } else if edu_info.degree_required {
+        Some("Degree Required")
+    } else {
+        None
+    };
+    let paid_tag = PAID_DETECTOR.detect(&j.title, &j.description).and_then(|status| status.tag());
+    // A structured requirement from the ATS beats guessing from the text
+    const EDU_TAGS: [&str; 3] = ["Education Optional", "Degree Required", "Education Not Required"];
+    let edu_tag = edu_tag.filter(|_| !parser_tags.iter().any(|t| EDU_TAGS.contains(&t.as_str())));
+    tags.extend([edu_tag, paid_tag].into_iter().flatten().map(String::from));
+    tags.extend(REQUIREMENTS_DETECTOR.detect(&j.description).into_iter().map(String::from));
+    // Before truncation, since benefits usually close the description
+    j.benefits = BENEFITS_EXTRACTOR.extract(&j.description);
     
-    // 3. Normalize location
-    let loc_info = location_engine.resolve(&j.location);
+    j.seniority_level = detect_seniority(&j.title);
+    let quality = description_quality(&j.description);
+    j.description_length = quality.word_count;
+    j.description_quality_score = quality.score;
+    if let Some(truncated) = max_description_chars.and_then(|max| truncate_html(&j.description, max)) {
+        debug!("Truncated {} job '{}' description from {} to {} chars", j.company, j.title, j.description.chars().count(), truncated.chars().count());
+        j.description = truncated;
+    }
+    j.description_hash = sha256_hex(&j.description);
+    j.normalized_department = j.departments.first()
+        .and_then(|d| department_normalizer.normalize(d))
+        .map(String::from);
+
+    // 3. Normalize location. A parser that filled in city, region and country
+    // code (Greenhouse's structured form) only needs them looked up.
+    timer.lap();
+    let structured = match (&j.city, &j.region, &j.country_code) {
+        (Some(city), Some(region), Some(code)) => location_engine.resolve_structured(city, region, code, j.location_name.as_deref().unwrap_or("")),
+        _ => None,
+    };
+    let loc_info = structured.unwrap_or_else(|| location_engine.resolve(&j.location));
     let formatted = loc_info.display_format();
     if !formatted.is_empty() {
         j.location = formatted;
     }
+    let mut seen_offices = HashSet::new();
+    j.offices = j.offices.iter()
+        .map(|office| location_engine.normalize_office(office))
+        .filter(|office| seen_offices.insert(office.clone()))
+        .collect();
     j.city = loc_info.city;
     j.region = loc_info.region;
     j.country = loc_info.country;
     j.country_code = loc_info.country_code;
+    j.metro_area = loc_info.metro_area;
+    j.preferred_timezone = loc_info.preferred_timezone.or_else(|| extract_timezone(&j.title));
+    if let Some(profile) = NORMALIZATION_PROFILE.as_ref() {
+        let location_resolution_us = timer.lap();
+        profile.lock().unwrap().record(NormalizationMetrics { tag_detection_us, edu_detection_us, location_resolution_us });
+    }
     
     if loc_info.work_mode != crate::models::WorkMode::InOffice {
-        let mode_str = match loc_info.work_mode {
-            crate::models::WorkMode::Remote => "Remote",
-            crate::models::WorkMode::Hybrid => "Hybrid",
-            _ => "",
+        let mode_tags: &[&str] = match loc_info.work_mode {
+            crate::models::WorkMode::Remote => &["Remote"],
+            crate::models::WorkMode::Hybrid => &["Hybrid"],
+            crate::models::WorkMode::Global => &["Remote", "Global"],
+            _ => &[],
         };
-        if !mode_str.is_empty() {
-            j.tags.push(mode_str.to_string());
-        }
+        tags.extend(mode_tags.iter().map(|t| t.to_string()));
     }
+
+    tags.extend(parser_tags);
+    TagHierarchy::expand_implied(&mut tags);
+    j.tags = dedupe_tags(tags);
     j
 }
 
+/// Drops case-insensitive repeats, keeping the first spelling, and sorts the rest.
+fn dedupe_tags(tags: Vec<String>) -> Vec<String> {
+    let mut seen = HashSet::new();
+    let mut unique: Vec<String> = tags.into_iter()
+        .filter(|t| seen.insert(t.to_lowercase()))
+        .collect();
+    unique.sort_by_cached_key(|t| t.to_lowercase());
+    unique
+}
+
+/// Jobs posted on or before these are dropped (EOI listings stay open longer).
+#[derive(Clone, Copy)]
+struct Cutoffs {
+    default: DateTime<Utc>,
+    eoi: DateTime<Utc>,
+}
+
+/// How `scrape_company` fetches the descriptions a board listing leaves out.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+struct Enrichment {
+    /// Off with `--no-enrich`.
+    enabled: bool,
+    /// Detail requests in flight per company; 0 means no cap.
+    concurrency: usize,
+}
+
+impl Enrichment {
+    /// `ENRICHMENT_CONCURRENCY`, overridden by `--max-enrichment-concurrency=N`.
+    fn from_args(config: &Config, args: &[String]) -> Self {
+        Self {
+            enabled: !args.iter().any(|a| a == "--no-enrich"),
+            concurrency: args.iter()
+                .find_map(|a| a.strip_prefix("--max-enrichment-concurrency="))
+                .and_then(|s| s.parse().ok())
+                .unwrap_or(config.enrichment_concurrency),
+        }
+    }
+
+    fn buffer_size(&self) -> usize {
+        if self.concurrency == 0 { usize::MAX } else { self.concurrency }
+    }
+}
+
+/// Parses `--since=YYYY-MM-DD` as UTC midnight, rejecting future dates.
+fn parse_since(date: &str, now: DateTime<Utc>) -> Result<DateTime<Utc>> {
+    let since = chrono::NaiveDate::parse_from_str(date, "%Y-%m-%d")
+        .context(format!("Invalid --since date '{}', expected YYYY-MM-DD", date))?
+        .and_hms_opt(0, 0, 0)
+        .context("Invalid --since date")?
+        .and_utc();
+    if since > now {
+        return Err(anyhow::anyhow!("--since date {} is in the future", date));
+    }
+    Ok(since)
+}
+
+const FAILURE_COUNTS_FILE: &str = "failure_counts.json";
+const LAST_SCRAPED_FILE: &str = "last_scraped.json";
+const LAST_RUN_FILE: &str = "last_run.json";
+const HEALTH_REPORT_FILE: &str = "health_report.json";
+const HEALTH_BASELINE_FILE: &str = "health_baseline.json";
+
+/// Case-insensitive whole-word match on any of the company's `extra_keywords`.
+fn company_keyword_regex(company: &CompanyEntry) -> Option<Regex> {
+    let keywords: Vec<String> = company.extra_keywords.as_ref()?
+        .iter()
+        .map(|k| k.trim())
+        .filter(|k| !k.is_empty())
+        .map(|k| {
+            // \b next to a symbol (as in "C++") would never match
+            let start = if k.starts_with(|c: char| c.is_alphanumeric()) { r"\b" } else { "" };
+            let end = if k.ends_with(|c: char| c.is_alphanumeric()) { r"\b" } else { "" };
+            format!("{}{}{}", start, regex::escape(k), end)
+        })
+        .collect();
+    if keywords.is_empty() {
+        return None;
+    }
+    Regex::new(&format!("(?i){}", keywords.join("|"))).ok()
+}
+
+/// GET by default; POST with the interpolated `request_body` for APIs that need one.
+fn company_request(client: &reqwest::Client, company: &CompanyEntry, url: &str, cursor: Option<&str>) -> reqwest::RequestBuilder {
+    match company.http_method.unwrap_or_default() {
+        HttpMethod::Get => client.get(url),
+        HttpMethod::Post => {
+            let body = company.request_body_for(cursor).unwrap_or(Value::Object(Default::default()));
+            client.post(url).json(&body)
+        }
+    }
+}
+
+fn matches_keywords(title: &str, keyword_regex: &Regex, company_regex: Option<&Regex>) -> bool {
+    keyword_regex.is_match(title) || company_regex.is_some_and(|r| r.is_match(title))
+}
+
+/// A company's kept jobs, plus how many its board parsed to before the
+/// keyword, cutoff and country filters.
+#[derive(Debug)]
+struct ScrapedCompany {
+    jobs: Vec<Job>,
+    parsed: usize,
+}
+
+#[allow(clippy::too_many_arguments)]
+#[tracing::instrument(name = "scrape.company", skip_all, fields(company.name = %company.name, company.ats = ?company.ats_type))]
 async fn process_company(
     client: &reqwest::Client,
     company: &CompanyEntry,
@@ -546,76 +1088,213 @@ async fn process_company(
     negative_regex: &Regex,
     tag_engine: Arc<TagEngine>,
     edu_detector: Arc<EducationDetector>,
-    location_engine: Arc<LocationEngine>
-) -> Result<Vec<Job>> {
-    let mut url = company.api_url.clone();
-    if company.ats_type == AtsType::Greenhouse && !url.contains("content=true") {
-        url.push_str(if url.contains('?') { "&content=true" } else { "?content=true" });
+    department_normalizer: Arc<DepartmentNormalizer>,
+    location_engine: Arc<LocationEngine>,
+    metrics: &Metrics,
+    cutoffs: Cutoffs,
+    breaker: &CircuitBreaker,
+    limiter: &DomainRateLimiter,
+    country_filter: &CountryFilter,
+    max_description_chars: Option<usize>,
+    enrichment: Enrichment,
+    updated_after: Option<DateTime<Utc>>,
+) -> Result<ScrapedCompany, ScraperError> {
+    if breaker.state(&company.slug, Utc::now()) == CircuitState::Open {
+        return Err(ScraperError::CircuitOpen { company: company.name.clone() });
     }
-    
-    // Debug log for target ATS types
-    if matches!(company.ats_type, AtsType::Greenhouse | AtsType::Ashby) {
-        info!("Processing {:?} for {}: URL={}", company.ats_type, company.name, url);
+
+    let result = scrape_company(client, company, keyword_regex, negative_regex, tag_engine, edu_detector, department_normalizer, location_engine, metrics, cutoffs, limiter, country_filter, max_description_chars, enrichment, updated_after).await;
+    match &result {
+        Ok(_) => breaker.record_success(&company.slug),
+        Err(_) => breaker.record_failure(&company.slug, Utc::now()),
     }
+    result
+}
 
-    let resp = client.get(&url).send().await?;
+/// Fetches one board URL and decodes the JSON body, holding a per-domain slot
+/// until the body is read so a slow board keeps it.
+async fn fetch_board_json(client: &reqwest::Client, company: &CompanyEntry, url: &str, limiter: &DomainRateLimiter) -> Result<Value, ScraperError> {
+    let permit = limiter.acquire(url).await;
+    let resp = company_request(client, company, url, None).send().await.map_err(|e| ScraperError::from_reqwest(e, url))?;
+    if resp.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+        let retry_after = resp.headers()
+            .get(reqwest::header::RETRY_AFTER)
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok());
+        return Err(ScraperError::RateLimited { url: url.to_string(), retry_after });
+    }
     if !resp.status().is_success() {
-        warn!("HTTP {} for {} ({})", resp.status(), url, company.name);
-        return Err(anyhow::anyhow!("HTTP {} for {}", resp.status(), url));
+        return Err(ScraperError::HttpError { status: resp.status().as_u16(), url: url.to_string() });
     }
     
-    let body_text = resp.text().await?;
+    let body_text = resp.text().await.map_err(|e| ScraperError::from_reqwest(e, url))?;
+    drop(permit);
     if matches!(company.ats_type, AtsType::Greenhouse | AtsType::Ashby) {
         debug!("Response for {}: {:.100}...", company.name, body_text);
     }
 
-    let data: Value = serde_json::from_str(&body_text)
-        .map_err(|e| anyhow::anyhow!("JSON decode error for {}: {}", url, e))?;
+    serde_json::from_str(&body_text).map_err(|e| ScraperError::ParseError {
+        ats: company.ats_type,
+        company: company.name.clone(),
+        detail: format!("JSON decode error for {}: {}", url, e),
+    })
+}
+
+const RECRUITEE_PER_PAGE: usize = 100;
+
+/// The first-page URL for a company, with the query parameters its ATS needs.
+/// Greenhouse only includes descriptions with `content=true`, which
+/// `ats_options.include_content: false` turns off for boards that time out,
+/// and only returns jobs changed since `updated_after` when that is set.
+fn board_url(company: &CompanyEntry, updated_after: Option<DateTime<Utc>>) -> String {
+    let mut url = match &company.custom_api_url {
+        Some(custom) if company.ats_type == AtsType::Unknown => custom.clone(),
+        _ => company.api_url.clone(),
+    };
+    let sep = |url: &str| if url.contains('?') { '&' } else { '?' };
+    if company.ats_type == AtsType::Greenhouse
+        && company.ats_option_bool("include_content", true)
+        && !url.contains("content=true")
+    {
+        url.push_str(&format!("{}content=true", sep(&url)));
+    }
+    if let Some(since) = updated_after.filter(|_| company.ats_type == AtsType::Greenhouse) {
+        url = with_query_param(&url, "updated_after", &since.to_rfc3339_opts(SecondsFormat::Secs, true));
+    }
+    if company.ats_type == AtsType::Recruitee && !url.contains("page[size]=") && !url.contains("page%5Bsize%5D=") {
+        url = with_query_param(&url, "page[size]", &RECRUITEE_PER_PAGE.to_string());
+        url = with_query_param(&url, "page[number]", "1");
+    }
+    url
+}
+/// Stops a bad `total_pages` from turning into thousands of requests.
+const RECRUITEE_MAX_PAGES: u64 = 50;
+/// Pause between Recruitee pages so large boards don't trip its rate limit.
+const RECRUITEE_PAGE_DELAY: std::time::Duration = std::time::Duration::from_millis(200);
+
+/// Fetches every page of a Recruitee v2 board (`page[number]`/`page[size]`),
+/// following `meta.current_page` until it reaches `meta.total_pages`, and
+/// returns the first page with all the offers merged into its `offers`. Each
+/// request goes through the shared client, so the global HTTP timeout applies.
+async fn fetch_recruitee_paginated(client: &reqwest::Client, company: &CompanyEntry, url: &str, limiter: &DomainRateLimiter) -> Result<Value, ScraperError> {
+    let mut data = fetch_board_json(client, company, url, limiter).await?;
+    let total_pages = data["meta"]["total_pages"].as_u64().unwrap_or(1).min(RECRUITEE_MAX_PAGES);
+    let mut current_page = data["meta"]["current_page"].as_u64().unwrap_or(1);
+
+    while current_page < total_pages {
+        tokio::time::sleep(RECRUITEE_PAGE_DELAY).await;
+        let page_url = with_query_param(url, "page[number]", &(current_page + 1).to_string());
+        let next = fetch_board_json(client, company, &page_url, limiter).await?;
+        let Some(offers) = next["offers"].as_array().filter(|o| !o.is_empty()) else { break };
+        if let Some(all) = data["offers"].as_array_mut() {
+            all.extend(offers.iter().cloned());
+        }
+        // Older boards leave out `current_page`
+        current_page = next["meta"]["current_page"].as_u64().unwrap_or(current_page + 1).max(current_page + 1);
+    }
+    if total_pages > 1 {
+        let offers = data["offers"].as_array().map_or(0, |o| o.len());
+        info!(company = %company.name, total_pages, offers, "Fetched Recruitee pages");
+    }
+    Ok(data)
+}
+
+/// `url` with `key` set to `value`, replacing any existing value.
+fn with_query_param(url: &str, key: &str, value: &str) -> String {
+    let Ok(mut parsed) = reqwest::Url::parse(url) else { return url.to_string() };
+    let pairs: Vec<(String, String)> = parsed.query_pairs()
+        .filter(|(k, _)| k != key)
+        .map(|(k, v)| (k.into_owned(), v.into_owned()))
+        .collect();
+    parsed.query_pairs_mut().clear().extend_pairs(pairs).append_pair(key, value);
+    parsed.to_string()
+}
+
+#[allow(clippy::too_many_arguments)]
+async fn scrape_company(
+    client: &reqwest::Client,
+    company: &CompanyEntry,
+    keyword_regex: &Regex,
+    negative_regex: &Regex,
+    tag_engine: Arc<TagEngine>,
+    edu_detector: Arc<EducationDetector>,
+    department_normalizer: Arc<DepartmentNormalizer>,
+    location_engine: Arc<LocationEngine>,
+    metrics: &Metrics,
+    cutoffs: Cutoffs,
+    limiter: &DomainRateLimiter,
+    country_filter: &CountryFilter,
+    max_description_chars: Option<usize>,
+    enrichment: Enrichment,
+    updated_after: Option<DateTime<Utc>>,
+) -> Result<ScrapedCompany, ScraperError> {
+    let url = board_url(company, updated_after);
+    
+    // Debug log for target ATS types
+    if matches!(company.ats_type, AtsType::Greenhouse | AtsType::Ashby) {
+        info!(company = %company.name, ats_type = ?company.ats_type, %url, "Processing company");
+    }
+
+    let data = match company.ats_type {
+        AtsType::Recruitee => fetch_recruitee_paginated(client, company, &url, limiter).await?,
+        _ => fetch_board_json(client, company, &url, limiter).await?,
+    };
+
+    for warning in company.ats_type.validate_schema(&data) {
+        match warning.severity {
+            Severity::Critical => warn!(company = %company.name, ats_type = ?company.ats_type, field = %warning.field, "Unexpected board schema: {}", warning.message),
+            Severity::Warning => debug!(company = %company.name, ats_type = ?company.ats_type, field = %warning.field, "Unexpected board schema: {}", warning.message),
+        }
+    }
 
-    let jobs = company.ats_type.parse(company, &data)?;
+    let parse_error = |detail: String| ScraperError::ParseError {
+        ats: company.ats_type,
+        company: company.name.clone(),
+        detail,
+    };
+    let jobs = company.ats_type.parse(company, &data).map_err(|e| parse_error(format!("{:#}", e)))?;
+    let parsed = jobs.len();
     
     // --- Observability Check ---
     if matches!(company.ats_type, AtsType::Greenhouse | AtsType::Ashby) {
         let raw_item_count = company.ats_type.estimate_raw_item_count(&data);
 
         if raw_item_count > 0 && jobs.is_empty() {
-            warn!("PARSING HEALTH ALERT: {} returned {} raw items but parsed 0 jobs. Check schema!", company.name, raw_item_count);
+            warn!(company = %company.name, ats_type = ?company.ats_type, raw_item_count, "PARSING HEALTH ALERT: raw items returned but parsed 0 jobs. Check schema!");
         } else {
-             info!("Parsed {} jobs (from ~{} raw items) for {}", jobs.len(), raw_item_count, company.name);
+             info!(company = %company.name, ats_type = ?company.ats_type, jobs_count = jobs.len(), raw_item_count, "Parsed jobs");
         }
     } else {
-        debug!("Parsed {} jobs for {}", jobs.len(), company.name);
+        debug!(company = %company.name, ats_type = ?company.ats_type, jobs_count = jobs.len(), "Parsed jobs");
     }
     // ---------------------------
 
     
-    let now = Utc::now();
-    let cutoff_default = now - Duration::days(60); 
-    let cutoff_eoi = now - Duration::days(120); 
+    let Cutoffs { default: cutoff_default, eoi: cutoff_eoi } = cutoffs;
+    let company_regex = company_keyword_regex(company);
+    let company_regex = company_regex.as_ref();
 
     let enrichment_stream = stream::iter(jobs)
         .filter_map(|j| async move {
             let is_target = matches!(j.ats, AtsType::Greenhouse | AtsType::Ashby);
             
-            if !keyword_regex.is_match(&j.title) { 
-                if is_target { debug!("Dropping {} job '{}': No keyword match", j.company, j.title); }
+            if !matches_keywords(&j.title, keyword_regex, company_regex) { 
+                if is_target { debug!(source_url = %j.source_url, "Dropping {} job '{}': No keyword match", j.company, j.title); }
                 return None; 
             }
             if negative_regex.is_match(&j.title) { 
-                if is_target { debug!("Dropping {} job '{}': Negative keyword match", j.company, j.title); }
+                if is_target { debug!(source_url = %j.source_url, "Dropping {} job '{}': Negative keyword match", j.company, j.title); }
                 return None; 
             }
             
             let is_eoi = j.title.to_lowercase().contains("expression of interest") || j.title.to_lowercase().contains("eoi");
             let cutoff = if is_eoi { cutoff_eoi } else { cutoff_default };
             
-            if !j.posted.is_empty() {
-                if let Ok(p) = DateTime::parse_from_rfc3339(&j.posted) {
-                    if p.with_timezone(&Utc) <= cutoff { 
-                        if is_target { debug!("Dropping {} job '{}': Too old ({})", j.company, j.title, j.posted); }
-                        return None; 
-                    }
-                }
+            if let Some(posted) = j.posted
+                && posted <= cutoff
+            {
+                if is_target { debug!(source_url = %j.source_url, "Dropping {} job '{}': Too old ({})", j.company, j.title, format_date(posted)); }
+                return None;
             }
             Some(j)
         })
@@ -625,65 +1304,244 @@ async fn process_company(
             let company = company.clone();
             let tag_engine = tag_engine.clone();
             let edu_detector = edu_detector.clone();
+            let department_normalizer = department_normalizer.clone();
             let location_engine = location_engine.clone();
 
             async move {
-                match enrich_job(&client, j, &slug).await {
+                if !enrichment.enabled && needs_enrichment(&j) {
+                    metrics.enrichment_skipped.fetch_add(1, Ordering::SeqCst);
+                }
+                match enrich_job(&client, limiter, j, &slug, enrichment.enabled).await {
                     Ok(enriched) => {
-                         let normalized = normalize_job(enriched, &company, &tag_engine, &edu_detector, &location_engine);
+                         let normalized = normalize_job(enriched, &company, &tag_engine, &edu_detector, &department_normalizer, &location_engine, max_description_chars);
+                         if !country_filter.allows(&normalized) {
+                             debug!(source_url = %normalized.source_url, "Dropping {} job '{}': Country {:?} filtered out", normalized.company, normalized.title, normalized.country_code);
+                             return None;
+                         }
                          Some(normalized)
                     },
-                    Err(_) => None
+                    Err(e) => {
+                        debug!(error_kind = e.kind(), "{}", e);
+                        metrics.enrichment_failures.fetch_add(1, Ordering::SeqCst);
+                        metrics.record_failure(&e);
+                        None
+                    }
                 }
             }
         })
-        .buffer_unordered(10);
+        .buffer_unordered(enrichment.buffer_size());
 
-    let filtered_jobs: Vec<Job> = enrichment_stream
+    let normalized_jobs: Vec<Job> = enrichment_stream
         .filter_map(|res| async { res })
         .collect().await;
 
-    Ok(filtered_jobs)
+    let mut dedup = Deduplicator::default();
+    let mut filtered_jobs: Vec<Job> = Vec::with_capacity(normalized_jobs.len());
+    for job in normalized_jobs {
+        if dedup.is_duplicate(&job) {
+            debug!("Dropping {} job '{}': Duplicate listing", job.company, job.title);
+            metrics.jobs_deduped.fetch_add(1, Ordering::SeqCst);
+            continue;
+        }
+        filtered_jobs.push(job);
+    }
+
+    Ok(ScrapedCompany { jobs: filtered_jobs, parsed })
 }
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+/// Binary copy of the parsed GeoNames files, rebuilt when any of them is newer.
+const GEO_CACHE_FILE: &str = "geonames.cache";
+const GEO_SOURCE_FILES: [&str; 3] = ["cities15000.txt", "admin1CodesASCII.txt", "countryInfo.txt"];
 
-    #[test]
-    fn test_escape_sql_string() {
-        assert_eq!(escape_sql_string("Normal String"), "Normal String");
-        assert_eq!(escape_sql_string("O'Reilly"), "O''Reilly");
-        assert_eq!(escape_sql_string("Multiple ' ' quotes"), "Multiple '' '' quotes");
-        assert_eq!(escape_sql_string(""), "");
+fn load_geonames_and_cache(location_engine: &mut LocationEngine) -> Result<()> {
+    let [cities, admin1, countries] = GEO_SOURCE_FILES;
+    location_engine.load_geonames(cities, admin1, countries)?;
+    if let Err(e) = location_engine.save_cache(GEO_CACHE_FILE) {
+        warn!("Failed to write {}: {}", GEO_CACHE_FILE, e);
     }
+    Ok(())
+}
 
-    #[test]
-    fn test_db_query_to_sql() {
-        let query = DbQuery {
-            sql: "INSERT INTO table (col1, col2, col3) VALUES (?1, ?2, ?3)".to_string(),
-            params: vec![
-                Value::String("O'Reilly".to_string()),
-                Value::Number(serde_json::Number::from(42)),
-                Value::Bool(true),
-            ],
-        };
-        let sql = query.to_sql();
-        assert_eq!(sql, "INSERT INTO table (col1, col2, col3) VALUES ('O''Reilly', 42, 1)");
+/// Geonames plus metro areas; the flag is false when geonames failed to load.
+fn load_location_engine() -> (LocationEngine, bool) {
+    let mut location_engine = LocationEngine::new();
+    let indexed = std::path::Path::new("cities.index").exists().then(|| {
+        location_engine.load_countries("countryInfo.txt")
+            .and_then(|_| location_engine.load_regions("admin1CodesASCII.txt"))
+            .and_then(|_| location_engine.load_from_index("cities.index"))
+    });
+    if let Some(Err(e)) = &indexed {
+        warn!("Failed to load cities.index: {}. Falling back to {} or the text files.", e, GEO_CACHE_FILE);
+        location_engine = LocationEngine::new();
     }
-    
-    #[test]
-    fn test_db_query_to_sql_order() {
-         let query = DbQuery {
-            sql: "SELECT * FROM t WHERE id = ?2 AND name = ?1".to_string(),
-            params: vec![
-                Value::String("Test".to_string()),
-                Value::Number(serde_json::Number::from(100)),
-            ],
-        };
-        let sql = query.to_sql();
-        assert_eq!(sql, "SELECT * FROM t WHERE id = 100 AND name = 'Test'");
+    let geo_result = if let Some(Ok(())) = indexed {
+        Ok(())
+    } else if LocationEngine::cache_is_fresh(GEO_CACHE_FILE, &GEO_SOURCE_FILES) {
+        match LocationEngine::load_cache(GEO_CACHE_FILE) {
+            Ok(cached) => {
+                location_engine = cached;
+                Ok(())
+            }
+            Err(e) => {
+                warn!("Failed to load {}: {}. Falling back to the text files.", GEO_CACHE_FILE, e);
+                load_geonames_and_cache(&mut location_engine)
+            }
+        }
+    } else {
+        load_geonames_and_cache(&mut location_engine)
+    };
+    if let Err(e) = &geo_result {
+        warn!("Failed to load location data: {}. Location normalization will be limited.", e);
+    }
+    if std::path::Path::new("metro_areas.json").exists() {
+        match crate::location::MetroAreaMap::from_file("metro_areas.json") {
+            Ok(metros) => {
+                info!("Loaded metro areas ({} cities mapped).", metros.len());
+                location_engine.metro_areas = metros;
+            }
+            Err(e) => warn!("Failed to load metro_areas.json: {}", e),
+        }
+    }
+    (location_engine, geo_result.is_ok())
+}
+
+fn load_tag_engine(config: &Config) -> Result<TagEngine> {
+    let engine = match &config.tag_rules_file {
+        Some(path) => {
+            info!("Loading tag rules from {}...", path);
+            TagEngine::from_file(path)?
+        }
+        None => TagEngine::new(),
+    };
+    Ok(engine.with_min_confidence(config.min_tag_confidence).with_cache_capacity(config.tag_cache_size))
+}
+
+fn load_department_normalizer(config: &Config) -> Result<DepartmentNormalizer> {
+    match &config.department_rules_file {
+        Some(path) => {
+            info!("Loading department rules from {}...", path);
+            DepartmentNormalizer::from_file(path)
+        }
+        None => Ok(DepartmentNormalizer::new()),
+    }
+}
+
+/// `zapply validate`: fetches every company's board once and prints which
+/// are dead, slow or no longer parse. Nothing is written to the database.
+async fn validate_mode(config: &Config) -> Result<()> {
+    let companies = load_companies(&config.slugs_files)?;
+    let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+        .timeout(std::time::Duration::from_secs(15))
+        .build()?;
+
+    let pb = ProgressBar::new(companies.len() as u64);
+    let mut checks: Vec<SlugCheck> = stream::iter(&companies)
+        .map(|company| {
+            let client = &client;
+            let pb = &pb;
+            async move {
+                let check = check_company(client, company).await;
+                pb.inc(1);
+                check
+            }
+        })
+        .buffer_unordered(config.concurrency)
+        .collect()
+        .await;
+    pb.finish_and_clear();
+
+    print!("{}", render_validation_report(&mut checks));
+    Ok(())
+}
+
+/// `zapply test-regex --input="..."` or `--title-file=titles.txt`: checks
+/// titles against the keyword regexes without scraping anything.
+fn test_regex_mode(args: &[String], config: &Config) -> Result<()> {
+    let keyword_regex = config.keyword_regex()?;
+    let negative_regex = config.negative_keyword_regex()?;
+    if let Some(path) = args.iter().find_map(|a| a.strip_prefix("--title-file=")) {
+        let content = fs::read_to_string(path).with_context(|| format!("Failed to read {}", path))?;
+        let checks: Vec<TitleCheck> = content.lines()
+            .map(str::trim)
+            .filter(|line| !line.is_empty())
+            .map(|title| TitleCheck::new(title, &keyword_regex, &negative_regex))
+            .collect();
+        print!("{}", render_title_table(&checks));
+        return Ok(());
     }
+    let input = args.iter()
+        .find_map(|a| a.strip_prefix("--input="))
+        .context("Usage: zapply test-regex --input=\"<title>\" or --title-file=<path>")?;
+    print!("{}", render_title_check(&TitleCheck::new(input, &keyword_regex, &negative_regex)));
+    Ok(())
+}
+
+/// `zapply test --company=<slug>`: scrapes one company without touching the
+/// database and prints what came back.
+async fn test_mode(args: &[String], config: &Config) -> Result<()> {
+    let slug = args.iter()
+        .find_map(|a| a.strip_prefix("--company="))
+        .context("Usage: zapply test --company=<slug>")?;
+    let companies = load_companies(&config.slugs_files)?;
+    let mut company = companies.into_iter()
+        .find(|c| c.slug.eq_ignore_ascii_case(slug))
+        .with_context(|| format!("No company with slug {:?} in {}", slug, config.slugs_files.join(", ")))?;
+
+    let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+    if company.ats_type == AtsType::Unknown && company.custom_api_url.is_none() && company.custom_json_path.is_none() {
+        AtsAutoDetector::detect_company(&mut company, &client).await;
+    }
+
+    let keyword_regex = config.keyword_regex()?;
+    let negative_regex = config.negative_keyword_regex()?;
+    let (location_engine, _) = load_location_engine();
+    let now = Utc::now();
+    let cutoffs = Cutoffs {
+        default: now - Duration::days(config.cutoff_days_default),
+        eoi: now - Duration::days(config.cutoff_days_eoi),
+    };
+    // A fresh breaker so a saved open circuit doesn't skip the company
+    let breaker = CircuitBreaker::new(config.circuit_max_failures, config.circuit_reset_after_days);
+
+    let url = match &company.custom_api_url {
+        Some(custom) if company.ats_type == AtsType::Unknown => custom.clone(),
+        _ => company.api_url.clone(),
+    };
+    let result = process_company(
+        &client, &company, &keyword_regex, &negative_regex,
+        Arc::new(load_tag_engine(config)?), Arc::new(EducationDetector::new()), Arc::new(load_department_normalizer(config)?), Arc::new(location_engine),
+        &Metrics::default(), cutoffs, &breaker, &DomainRateLimiter::new(config.max_requests_per_domain), &CountryFilter::default(), config.max_description_chars, Enrichment::from_args(config, args), None,
+    ).await;
+
+    match result {
+        Ok(ScrapedCompany { jobs, .. }) => {
+            print!("{}", render_company_report(&company, &url, &jobs));
+            Ok(())
+        }
+        Err(e) => {
+            print!("{}", render_company_report(&company, &url, &[]));
+            let kind = e.kind();
+            Err(anyhow::Error::new(e).context(format!("{} failed ({})", company.name, kind)))
+        }
+    }
+}
+
+/// One JSON object per event (`timestamp`, `level`, `target`, `message` plus
+/// any event fields), for `LOG_FORMAT=json`.
+fn json_subscriber<W>(filter: EnvFilter, writer: W) -> impl tracing::Subscriber + for<'span> LookupSpan<'span> + Send + Sync
+where
+    W: for<'w> MakeWriter<'w> + Send + Sync + 'static,
+{
+    tracing_subscriber::fmt()
+        .json()
+        .flatten_event(true)
+        .with_env_filter(filter)
+        .with_writer(writer)
+        .finish()
 }
 
 #[tokio::main]
@@ -693,17 +1551,76 @@ async fn main() -> Result<()> {
     let is_verbose = args.iter().any(|a| a == "--log");
     let default_level = if is_verbose { "info" } else { "error" };
 
-    env_logger::init_from_env(env_logger::Env::default().default_filter_or(default_level));
+    // RUST_LOG still overrides the level picked by --log
+    let filter = EnvFilter::try_from_default_env().unwrap_or_else(|_| EnvFilter::new(default_level));
+    let tracer_provider = init_tracer_provider()?;
+    if std::env::var("LOG_FORMAT").is_ok_and(|f| f.eq_ignore_ascii_case("json")) {
+        json_subscriber(filter, std::io::stderr).with(otel_layer(tracer_provider.as_ref())).init();
+    } else {
+        tracing_subscriber::fmt().with_env_filter(filter).with_writer(std::io::stderr).finish()
+            .with(otel_layer(tracer_provider.as_ref()))
+            .init();
+    }
     
     if is_verbose {
         info!("Starting Zapply Job Scraper (Rust)...");
     }
+    if args.iter().any(|a| a == "--dump-tag-rules") {
+        // Starting point for a custom TAG_RULES_FILE
+        print!("{}", TagEngine::new().to_toml()?);
+        return Ok(());
+    }
+    if args.iter().any(|a| a == "--build-index") {
+        LocationEngine::build_index("cities15000.txt", "cities.index")?;
+        return Ok(());
+    }
+
     let is_prod = args.iter().any(|a| a == "--prod");
+    let mut config = Config::load()?;
+    if let Some(files) = args.iter().find_map(|a| a.strip_prefix("--slugs=")) {
+        config.slugs_files = files.split(',').map(str::trim).filter(|f| !f.is_empty()).map(String::from).collect();
+    }
+
+    if args.get(1).map(String::as_str) == Some("test") {
+        return test_mode(&args, &config).await;
+    }
+    if args.get(1).map(String::as_str) == Some("test-regex") || args.iter().any(|a| a == "--test-regex") {
+        return test_regex_mode(&args, &config);
+    }
+    if args.get(1).map(String::as_str) == Some("validate") || args.iter().any(|a| a == "--validate-slugs") {
+        return validate_mode(&config).await;
+    }
 
-    let db: Box<dyn JobDb> = if is_prod {
+    // --output-format prints jobs to stdout once the run finishes
+    let stdout_export = args.iter()
+        .find_map(|a| a.strip_prefix("--output-format="))
+        .map(OutputFormat::parse)
+        .transpose()?
+        .map(JsonStdoutExport::new);
+
+    let sql_output = args.iter().find_map(|a| a.strip_prefix("--sql-output="));
+    let dry_run_db = sql_output.is_some() || args.iter().any(|a| a == "--dry-run-db");
+
+    let db: Box<dyn JobDb> = if let Some(export) = &stdout_export {
+        info!("Mode: {:?} to stdout", export.format);
+        Box::new(export.clone())
+    } else if let Some(path) = args.iter().find_map(|a| a.strip_prefix("--rss=")) {
+        info!("Mode: RSS feed ({})", path);
+        Box::new(RssExport::new(path, &config))
+    } else if let Some(path) = sql_output {
+        info!("Mode: SQL to {}", path);
+        Box::new(DryRunDb::to_file(path)?)
+    } else if dry_run_db {
+        info!("Mode: dry run (printing SQL)");
+        Box::new(DryRunDb::new())
+    } else if args.iter().any(|a| a == "--turso") {
+        info!("Mode: Turso");
+        Box::new(TursoDb::from_env()?)
+    } else if is_prod {
         info!("Mode: PROD (Remote D1)");
         Box::new(RemoteD1 {
             client: reqwest::Client::new(),
+            api_base: D1_API_BASE.to_string(),
             account_id: std::env::var("CLOUDFLARE_ACCOUNT_ID").context("CLOUDFLARE_ACCOUNT_ID not set")?,
             database_id: std::env::var("CLOUDFLARE_DATABASE_ID").context("CLOUDFLARE_DATABASE_ID not set")?,
             api_token: std::env::var("CLOUDFLARE_API_TOKEN").context("CLOUDFLARE_API_TOKEN not set")?,
@@ -715,60 +1632,174 @@ async fn main() -> Result<()> {
         })
     };
 
-    
-    let config = Config::load();
-    let keyword_regex = Regex::new(&config.keywords_regex).context("Invalid Regex")?;
-    let negative_regex = Regex::new(&config.negative_keywords_regex).context("Invalid Negative Regex")?;
+    // --also-export-csv/ndjson write a copy of every batch next to the main backend
+    let mut extra_backends: Vec<Box<dyn JobDb>> = Vec::new();
+    for (flag, format) in [("--also-export-csv=", FileFormat::Csv), ("--also-export-ndjson=", FileFormat::Ndjson)] {
+        for path in args.iter().filter_map(|a| a.strip_prefix(flag)) {
+            info!("Also exporting {:?} to {}", format, path);
+            let export = FileExport::create(path, format).context(format!("Failed to create {}", path))?;
+            extra_backends.push(Box::new(export));
+        }
+    }
+    if let Some(path) = args.iter().find_map(|a| a.strip_prefix("--parquet=")) {
+        info!("Also exporting Parquet to {}", path);
+        extra_backends.push(Box::new(ParquetExport::new(path)));
+    }
+    let db: Box<dyn JobDb> = if extra_backends.is_empty() {
+        db
+    } else {
+        extra_backends.insert(0, db);
+        Box::new(FanOutDb::new(extra_backends))
+    };
+
+    let persists_jobs = db.persists_jobs();
+
+    db.health_check().await
+        .context("Database health check failed; check the wrangler setup or the CLOUDFLARE_*/TURSO_* credentials")?;
+
+    let keyword_regex = config.keyword_regex()?;
+    let negative_regex = config.negative_keyword_regex()?;
 
     info!("Loading company list...");
-    let mut companies: Vec<CompanyEntry> = load_json(&config.slugs_file)
-        .context(format!("Failed to load {}", config.slugs_file))?;
+    let companies = load_companies(&config.slugs_files)?;
+    let mut companies = validate_companies(&companies)?;
+    info!("{} companies after merging", companies.len());
+
+    if let Some(list) = args.iter().find_map(|a| a.strip_prefix("--ats-type=")) {
+        let ats_types = parse_ats_types(list)?;
+        companies.retain(|c| ats_types.contains(&c.ats_type));
+        info!("Filtered to {} companies using {:?}.", companies.len(), ats_types);
+    }
 
     if let Some(limit) = args.iter().find_map(|a| a.strip_prefix("--limit=")).and_then(|s| s.parse().ok()) {
         info!("Limiting search to {} companies.", limit);
         companies.truncate(limit);
     }
 
-    info!("Fetching existing job IDs from database...");
-    let seen_ids = db.get_existing_ids().await?;
+    let client = reqwest::Client::builder()
+        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
+        .timeout(std::time::Duration::from_secs(30))
+        .build()?;
+
+    // Custom boards are deliberately `unknown`; don't try to detect those
+    for company in companies.iter_mut().filter(|c| c.ats_type == AtsType::Unknown && c.custom_api_url.is_none() && c.custom_json_path.is_none()) {
+        AtsAutoDetector::detect_company(company, &client).await;
+        info!("Detected ATS {:?} for {} ({})", company.ats_type, company.name, company.api_url);
+    }
+
+    let now = Utc::now();
+    let cutoffs = match args.iter().find_map(|a| a.strip_prefix("--since=")) {
+        // Cutoffs are exclusive, so step back a second to keep jobs posted at midnight
+        Some(date) => {
+            let since = parse_since(date, now)? - Duration::seconds(1);
+            Cutoffs { default: since, eoi: since }
+        }
+        None => Cutoffs {
+            default: now - Duration::days(config.cutoff_days_default),
+            eoi: now - Duration::days(config.cutoff_days_eoi),
+        },
+    };
+    info!("Effective cutoff: {} (EOI: {})", cutoffs.default.to_rfc3339(), cutoffs.eoi.to_rfc3339());
+
+    let breaker = Arc::new(CircuitBreaker::load(FAILURE_COUNTS_FILE, config.circuit_max_failures, config.circuit_reset_after_days)?);
+
+    let schedule = Arc::new(ScrapeSchedule::load(LAST_SCRAPED_FILE)?);
+    let scheduled = companies.len();
+    companies.retain(|c| schedule.is_due(c, now));
+    if companies.len() < scheduled {
+        info!("Skipping {} companies scraped within their scrape_interval_hours.", scheduled - companies.len());
+    }
+    // Greenhouse boards only return jobs changed since their last run, unless
+    // --force-full. A health report needs whole boards, so it always fetches in full.
+    let health_report = args.iter().any(|a| a == "--health-report");
+    let force_full = health_report || args.iter().any(|a| a == "--force-full");
+    let last_run = Arc::new(LastRuns::load(LAST_RUN_FILE)?);
+
+    let is_resume = args.iter().any(|a| a == "--resume");
+    let checkpoint_path = args.iter()
+        .find_map(|a| a.strip_prefix("--checkpoint="))
+        .map(String::from)
+        .or_else(|| is_resume.then(|| "checkpoint.json".to_string()));
+    let checkpoint = match &checkpoint_path {
+        Some(path) if is_resume && std::path::Path::new(path).exists() => {
+            let checkpoint = Checkpoint::load(path)?;
+            let completed: HashSet<&String> = checkpoint.completed_slugs.iter().collect();
+            companies.retain(|c| !completed.contains(&c.slug));
+            info!("Resuming from {}: skipping {} completed companies.", path, completed.len());
+            checkpoint
+        }
+        _ => Checkpoint::new(),
+    };
+
+    // --stats only reports on what was found; the database is left untouched
+    let stats = args.iter().any(|a| a == "--stats").then(|| Arc::new(Mutex::new(ScrapeStats::default())));
+    let health = health_report.then(|| Arc::new(Mutex::new(HealthRecorder::default())));
+
+    // --no-cache re-scrapes jobs that are already stored instead of skipping them
+    let (known_ids, fingerprints) = if stats.is_some() || args.iter().any(|a| a == "--no-cache") {
+        (HashSet::new(), HashMap::new())
+    } else {
+        info!("Fetching existing job IDs from database...");
+        (db.get_existing_ids().await?, db.get_job_fingerprints().await?)
+    };
     
     let log_file = args.iter()
         .find_map(|a| a.strip_prefix("--log-file="))
         .and_then(|path| fs::File::create(path).ok())
         .map(|f| Arc::new(Mutex::new(f)));
 
-    let mut location_engine = LocationEngine::new();
-    if let Err(e) = location_engine.load_geonames("cities15000.txt", "admin1CodesASCII.txt", "countryInfo.txt") {
-        warn!("Failed to load location data: {}. Location normalization will be limited.", e);
-    } else {
+    let (location_engine, geo_loaded) = load_location_engine();
+    if geo_loaded && stats.is_none() {
         info!("Initializing geo tables in database...");
         db.initialize_geo_tables(&location_engine.countries, &location_engine.regions).await?;
     }
 
-    let tag_engine = Arc::new(TagEngine::new());
+    let tag_engine = Arc::new(load_tag_engine(&config)?);
     let edu_detector = Arc::new(EducationDetector::new());
+    let department_normalizer = Arc::new(load_department_normalizer(&config)?);
     let location_engine = Arc::new(location_engine);
-    
-    let client = reqwest::Client::builder()
-        .user_agent("Mozilla/5.0 (Windows NT 10.0; Win64; x64) AppleWebKit/537.36 (KHTML, like Gecko) Chrome/120.0.0.0 Safari/537.36")
-        .timeout(std::time::Duration::from_secs(30))
-        .build()?;
 
     let total = companies.len();
-    let pb = ProgressBar::new(total as u64);
+    // Keep the terminal clean when stdout is being piped somewhere
+    let pb = if stdout_export.is_some() { ProgressBar::hidden() } else { ProgressBar::new(total as u64) };
     pb.set_style(ProgressStyle::default_bar()
         .template("{spinner:.green} [{elapsed_precise}] [{bar:40.cyan/blue}] {pos}/{len} ({eta}) {msg}")
         .unwrap()
         .progress_chars("#> -"));
 
-    let jobs_count = Arc::new(AtomicUsize::new(0));
-    let failures_count = Arc::new(AtomicUsize::new(0));
-    let inserted_count = Arc::new(AtomicUsize::new(0));
-
-    const BATCH_SIZE: usize = 100;
-    let batch_buffer = Arc::new(Mutex::new(Vec::new()));
-    let seen_ids = Arc::new(Mutex::new(seen_ids));
-    let db = Arc::new(db);
+    let metrics = Arc::new(Metrics::default());
+    let limiter = DomainRateLimiter::new(config.max_requests_per_domain);
+    let include_text_description = config.include_text_description;
+    let max_description_chars = config.max_description_chars;
+    let enrichment = Enrichment::from_args(&config, &args);
+    if !enrichment.enabled {
+        info!("Enrichment disabled (--no-enrich); jobs without a description keep an empty one.");
+    }
+    let country_filter = Arc::new(CountryFilter::new(
+        args.iter().find_map(|a| a.strip_prefix("--filter-countries=").or_else(|| a.strip_prefix("--filter-country="))),
+        args.iter().find_map(|a| a.strip_prefix("--exclude-countries=")),
+    ));
+
+    let job_limit = args.iter()
+        .find_map(|a| a.strip_prefix("--limit-jobs="))
+        .and_then(|s| s.parse().ok())
+        .map(JobLimit::new);
+    // Company tasks block on a full channel instead of piling up jobs in memory
+    let (batch_tx, batch_rx) = mpsc::channel(CHANNEL_CAPACITY);
+    let db: Arc<dyn JobDb> = Arc::from(db);
+    let consumer = tokio::spawn(BatchConsumer {
+        db: db.clone(),
+        client: client.clone(),
+        metrics: metrics.clone(),
+        sizer: BatchSizer::new(),
+        limit: job_limit,
+        known_ids,
+        fingerprints,
+        // A dry run leaves the checkpoint and the other state files alone
+        checkpoint: checkpoint_path.clone().filter(|_| !dry_run_db).map(|path| (path, checkpoint)),
+        last_run: persists_jobs.then(|| (LAST_RUN_FILE.to_string(), last_run.clone())),
+        webhook: config.webhook.clone(),
+    }.run(batch_rx));
 
     let mut stream = stream::iter(companies)
         .map(|company| {
@@ -777,29 +1808,61 @@ async fn main() -> Result<()> {
             let negative_regex = negative_regex.clone();
             let tag_engine = tag_engine.clone();
             let edu_detector = edu_detector.clone();
+            let department_normalizer = department_normalizer.clone();
             let location_engine = location_engine.clone();
             let log_file = log_file.clone();
             let pb = pb.clone();
-            let jobs_count = jobs_count.clone();
-            let failures_count = failures_count.clone();
-            let inserted_count = inserted_count.clone();
-            let batch_buffer = batch_buffer.clone();
-            let seen_ids = seen_ids.clone();
-            let db = db.clone();
+            let metrics = metrics.clone();
+            let batch_tx = batch_tx.clone();
+            let stats = stats.clone();
+            let health = health.clone();
+            let breaker = breaker.clone();
+            let schedule = schedule.clone();
+            let last_run = last_run.clone();
+            let limiter = limiter.clone();
+            let country_filter = country_filter.clone();
 
             async move {
-                let result = process_company(&client, &company, &keyword_regex, &negative_regex, tag_engine, edu_detector, location_engine).await;
+                // Jobs updated while the board is being fetched are picked up next run
+                let started_at = Utc::now();
+                let updated_after = (!force_full && company.ats_type == AtsType::Greenhouse)
+                    .then(|| last_run.updated_after(&company.slug, started_at))
+                    .flatten();
+                let started = std::time::Instant::now();
+                let result = process_company(&client, &company, &keyword_regex, &negative_regex, tag_engine, edu_detector, department_normalizer, location_engine, &metrics, cutoffs, &breaker, &limiter, &country_filter, max_description_chars, enrichment, updated_after).await;
+                if !matches!(result, Err(ScraperError::CircuitOpen { .. })) {
+                    metrics.companies_total.fetch_add(1, Ordering::SeqCst);
+                }
+                if let Some(health) = &health {
+                    health.lock().unwrap().record_company(&company, result.as_ref().map(|r| r.parsed), started.elapsed().as_millis() as u64);
+                }
+                let succeeded = result.is_ok();
                 let jobs = match result {
-                    Ok(j) => {
-                        jobs_count.fetch_add(j.len(), Ordering::SeqCst);
+                    Ok(ScrapedCompany { jobs: mut j, .. }) => {
+                        metrics.add_jobs_found(company.ats_type, j.len());
+                        if include_text_description {
+                            for job in &mut j {
+                                job.text_description = Some(clean_html_to_text(&job.description));
+                            }
+                        }
                         if let Some(ref f) = log_file {
                             let mut f = f.lock().unwrap();
                             writeln!(f, "[SUCCESS] {}: Found {} roles", company.name, j.len()).ok();
                         }
                         j
                     }
+                    Err(e @ ScraperError::CircuitOpen { .. }) => {
+                        warn!(company = %company.name, "{}", e);
+                        if let Some(ref f) = log_file {
+                            let mut f = f.lock().unwrap();
+                            writeln!(f, "[SKIPPED] {}: circuit open", company.name).ok();
+                        }
+                        vec![]
+                    }
                     Err(e) => {
-                        failures_count.fetch_add(1, Ordering::SeqCst);
+                        metrics.companies_failed.fetch_add(1, Ordering::SeqCst);
+                        metrics.record_failure(&e);
+                        warn!(company = %company.name, error_kind = e.kind(), "{}", e);
                         if let Some(ref f) = log_file {
                             let mut f = f.lock().unwrap();
                             writeln!(f, "[ERROR] {}: {:#}", company.name, e).ok();
@@ -808,41 +1871,35 @@ async fn main() -> Result<()> {
                     }
                 };
 
-                // Add to batch buffer
-                let mut buffer = batch_buffer.lock().unwrap();
-                let mut seen_ids_guard = seen_ids.lock().unwrap();
-                
+                if let Some(stats) = &stats {
+                    stats.lock().unwrap().record_company(&company, succeeded, &jobs);
+                    pb.inc(1);
+                    return;
+                }
+
                 for job in jobs {
-                    if seen_ids_guard.insert(job.id.clone()) {
-                        buffer.push(job);
+                    // Only fails if the consumer died, which the final await reports
+                    if batch_tx.send(BatchItem::Job(Box::new(job))).await.is_err() {
+                        break;
                     }
                 }
-                
-                // Check if we need to flush
-                let should_flush = buffer.len() >= BATCH_SIZE;
-                let jobs_to_insert = if should_flush {
-                    std::mem::take(&mut *buffer)
-                } else {
-                    Vec::new()
-                };
-                drop(buffer);
-                drop(seen_ids_guard);
-
-                // Flush batch if needed
-                if !jobs_to_insert.is_empty() {
-                    if let Err(e) = db.insert_jobs(&jobs_to_insert).await {
-                        warn!("Failed to insert batch: {}", e);
-                    } else {
-                        let count = jobs_to_insert.len();
-                        inserted_count.fetch_add(count, Ordering::SeqCst);
+                // Failed companies stay out of the checkpoint and last_run.json,
+                // so a resume or the next incremental fetch retries them
+                if succeeded {
+                    let done = FinishedCompany { slug: company.slug.clone(), started_at, full_fetch: updated_after.is_none() };
+                    batch_tx.send(BatchItem::CompanyDone(done)).await.ok();
+                    if persists_jobs
+                        && let Err(e) = schedule.record_success(&company.slug, started_at, LAST_SCRAPED_FILE)
+                    {
+                        warn!("Failed to save {}: {}", LAST_SCRAPED_FILE, e);
                     }
                 }
 
                 pb.inc(1);
                 pb.set_message(format!("Jobs: {} | Inserted: {} | Failures: {}", 
-                    jobs_count.load(Ordering::SeqCst),
-                    inserted_count.load(Ordering::SeqCst),
-                    failures_count.load(Ordering::SeqCst)
+                    metrics.jobs_found_total(),
+                    metrics.jobs_inserted.load(Ordering::SeqCst),
+                    metrics.companies_failed.load(Ordering::SeqCst)
                 ));
             }
         })
@@ -850,19 +1907,732 @@ async fn main() -> Result<()> {
 
     // Process all companies
     while stream.next().await.is_some() {}
+    drop(stream);
 
-    // Flush remaining jobs
-    let remaining_jobs = {
-        let mut buffer = batch_buffer.lock().unwrap();
-        std::mem::take(&mut *buffer)
-    };
+    // Closing the channel lets the consumer drain it and flush the remainder
+    drop(batch_tx);
+    consumer.await.context("Batch consumer panicked")??;
+    db.finalize().await.context("Failed to finalize exports")?;
 
-    if !remaining_jobs.is_empty() {
-        db.insert_jobs(&remaining_jobs).await?;
-        inserted_count.fetch_add(remaining_jobs.len(), Ordering::SeqCst);
+    let (tag_hits, tag_misses) = tag_engine.cache_stats();
+    if tag_hits + tag_misses > 0 {
+        info!("Tag cache: {} hits, {} misses ({:.0}% hit rate)", tag_hits, tag_misses, 100.0 * tag_hits as f64 / (tag_hits + tag_misses) as f64);
     }
 
-    pb.finish_with_message(format!("Done! Inserted {} jobs.", inserted_count.load(Ordering::SeqCst)));
+    if let Some(profile) = NORMALIZATION_PROFILE.as_ref() {
+        eprint!("{}", profile.lock().unwrap().render());
+    }
+
+    let enrichment_skipped = metrics.enrichment_skipped.load(Ordering::SeqCst);
+    if enrichment_skipped > 0 {
+        info!("Skipped enrichment for {} jobs (--no-enrich)", enrichment_skipped);
+    }
+
+    if let Some(summary) = country_filter.dropped_summary() {
+        info!("Dropped by country filter: {}", summary);
+    }
+
+    if persists_jobs
+        && let Err(e) = breaker.save(FAILURE_COUNTS_FILE)
+    {
+        warn!("Failed to save {}: {}", FAILURE_COUNTS_FILE, e);
+    }
+
+    if let Some(health) = &health {
+        let mut report = health.lock().unwrap().report(Utc::now());
+        if let Some(baseline) = AtsHealthReport::load_baseline(HEALTH_BASELINE_FILE)? {
+            report.compare(&baseline);
+        }
+        for ats in &report.degraded {
+            warn!(ats = %ats, zero_job_rate = report.per_ats[ats].zero_job_rate(), "More companies returned 0 jobs than in {}", HEALTH_BASELINE_FILE);
+        }
+        report.write_to(HEALTH_REPORT_FILE)?;
+        info!("Wrote {}", HEALTH_REPORT_FILE);
+    }
+
+    if let Some(stats) = &stats {
+        pb.finish_and_clear();
+        print!("{}", stats.lock().unwrap().render());
+        return Ok(());
+    }
+
+    if let Some(path) = checkpoint_path.as_ref().filter(|_| !dry_run_db)
+        && std::path::Path::new(path).exists()
+    {
+        fs::remove_file(path).context(format!("Failed to remove checkpoint {}", path))?;
+    }
+
+    pb.finish_with_message(format!("Done! Inserted {} jobs.", metrics.jobs_inserted.load(Ordering::SeqCst)));
+
+    if let Some(export) = &stdout_export {
+        export.write_to(std::io::stdout().lock())?;
+    }
+
+    if args.iter().any(|a| a == "--metrics") {
+        metrics.write_to("metrics.txt")?;
+        info!("Wrote metrics.txt");
+    }
+
+    if let Some(provider) = tracer_provider
+        && let Err(e) = provider.shutdown()
+    {
+        warn!("Failed to flush traces: {}", e);
+    }
 
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RecordingDb {
+        queries: Mutex<Vec<DbQuery>>,
+    }
+
+    #[async_trait::async_trait]
+    impl JobDb for RecordingDb {
+        async fn execute_batch(&self, queries: &[DbQuery]) -> Result<()> {
+            self.queries.lock().unwrap().extend_from_slice(queries);
+            Err(anyhow::anyhow!("D1 API Error ({}): SQLITE_ERROR", batch_context(queries)))
+        }
+        async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+            Ok(HashSet::new())
+        }
+        async fn initialize_geo_tables(&self, _: &HashMap<String, String>, _: &HashMap<String, String>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_normalize_job_dedupes_tags() {
+        let company = CompanyEntry { name: "Acme".to_string(), slug: "acme".to_string(), ..Default::default() };
+        let mut job = AtsType::Lever.parse(&company, &serde_json::json!([
+            {"id": "1", "text": "Python Intern", "hostedUrl": "https://jobs.lever.co/acme/1", "categories": {}}
+        ])).unwrap().remove(0);
+        job.location = "Remote".to_string();
+        job.description = "Write python and Django services.".to_string();
+        job.tags = vec!["remote".to_string(), "PYTHON".to_string(), "Full-time".to_string(), "full-time".to_string()];
+
+        let job = normalize_job(job, &company, &TagEngine::new(), &EducationDetector::new(), &DepartmentNormalizer::new(), &LocationEngine::new(), None);
+        assert_eq!(job.tags, vec!["Django", "Full-time", "Python", "Remote"]);
+    }
+
+    #[test]
+    fn test_normalize_job_degree_not_required() {
+        let company = CompanyEntry { name: "Acme".to_string(), slug: "acme".to_string(), ..Default::default() };
+        let mut job = AtsType::Lever.parse(&company, &serde_json::json!([
+            {"id": "1", "text": "Intern", "hostedUrl": "https://jobs.lever.co/acme/1", "categories": {}}
+        ])).unwrap().remove(0);
+        job.description = "No degree required, just curiosity.".to_string();
+
+        let job = normalize_job(job, &company, &TagEngine::new(), &EducationDetector::new(), &DepartmentNormalizer::new(), &LocationEngine::new(), None);
+        assert!(job.tags.contains(&"Education Not Required".to_string()), "{:?}", job.tags);
+        assert!(!job.tags.contains(&"Education Optional".to_string()));
+    }
+
+    #[test]
+    fn test_default_min_tag_confidence_drops_distance_matches() {
+        let rules = std::env::temp_dir().join(format!("zapply_tag_rules_{}.toml", std::process::id()));
+        std::fs::write(&rules, r#"
+            [[rules]]
+            pattern = '(?i)\bgo\b'
+            tag = "Go"
+            context = '(?i)\blanguage\b'
+            distance = 5
+        "#).unwrap();
+        let mut config = Config::load().unwrap();
+        config.tag_rules_file = Some(rules.to_str().unwrap().to_string());
+        let tag_engine = load_tag_engine(&config).unwrap();
+        std::fs::remove_file(&rules).unwrap();
+
+        let company = CompanyEntry { name: "Acme".to_string(), slug: "acme".to_string(), ..Default::default() };
+        let normalize = |title: &str, description: &str| {
+            let mut job = AtsType::Lever.parse(&company, &serde_json::json!([
+                {"id": "1", "text": title, "hostedUrl": "https://jobs.lever.co/acme/1", "categories": {}}
+            ])).unwrap().remove(0);
+            job.description = description.to_string();
+            normalize_job(job, &company, &tag_engine, &EducationDetector::new(), &DepartmentNormalizer::new(), &LocationEngine::new(), None).tags
+        };
+        // A context-distance match scores 0.5, or 0.75 with the title boost
+        assert!(normalize("Backend Intern", "We write the Go language daily.").is_empty());
+        assert_eq!(normalize("Go language Intern", ""), vec!["Go"]);
+    }
+
+    #[tokio::test]
+    async fn test_insert_jobs_query_context() {
+        let company = CompanyEntry {
+            name: "Stripe".to_string(),
+            ats_type: AtsType::Greenhouse,
+            slug: "stripe".to_string(),
+            api_url: String::new(),
+            domain: None,
+            ..Default::default()
+        };
+        let mut job = AtsType::Greenhouse.parse(&company, &serde_json::json!({"jobs": [
+            {"id": 1, "title": "Software Engineering Intern", "absolute_url": "https://example.com/1"}
+        ]})).unwrap().remove(0);
+        job.tags = vec!["Rust".to_string()];
+
+        let db = RecordingDb { queries: Mutex::new(Vec::new()) };
+        let err = db.insert_jobs(&[job]).await.unwrap_err().to_string();
+        assert!(err.contains("greenhouse:stripe:job_upsert"), "{}", err);
+
+        let queries = db.queries.lock().unwrap();
+        assert!(queries.iter().all(|q| q.context.is_some()));
+        assert!(queries.iter().any(|q| q.context.as_deref() == Some("greenhouse:stripe:job_tags")));
+        let last_seen = queries.last().unwrap();
+        assert!(last_seen.to_sql().starts_with("UPDATE jobs SET last_seen = '"), "{}", last_seen.to_sql());
+        assert!(last_seen.to_sql().ends_with("WHERE id IN ('greenhouse-1')"), "{}", last_seen.to_sql());
+        // The label never ends up in the SQL or the D1 payload
+        assert!(queries.iter().all(|q| !q.to_sql().contains("job_upsert")));
+        assert!(!serde_json::to_string(&queries[0]).unwrap().contains("context"));
+    }
+
+    /// Runs queries against an in-memory copy of `db/schema.sql`.
+    struct SqliteDb {
+        conn: Mutex<rusqlite::Connection>,
+    }
+
+    impl SqliteDb {
+        fn new() -> Self {
+            let conn = rusqlite::Connection::open_in_memory().unwrap();
+            conn.execute_batch("PRAGMA foreign_keys = ON;").unwrap();
+            conn.execute_batch(include_str!("../../db/schema.sql")).unwrap();
+            Self { conn: Mutex::new(conn) }
+        }
+
+        fn rows(&self, sql: &str) -> Vec<String> {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(sql).unwrap();
+            stmt.query_map([], |row| row.get(0)).unwrap().map(|r| r.unwrap()).collect()
+        }
+    }
+
+    #[async_trait::async_trait]
+    impl JobDb for SqliteDb {
+        async fn execute_batch(&self, queries: &[DbQuery]) -> Result<()> {
+            let conn = self.conn.lock().unwrap();
+            for query in queries {
+                conn.execute_batch(&query.to_sql()).with_context(|| query.to_sql())?;
+            }
+            Ok(())
+        }
+        async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare("SELECT id FROM jobs")?;
+            let rows = stmt.query_map([], |row| row.get::<_, String>(0))?;
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        }
+        async fn get_job_fingerprints(&self) -> Result<HashMap<String, String>> {
+            let conn = self.conn.lock().unwrap();
+            let mut stmt = conn.prepare(JOB_FINGERPRINTS_SQL)?;
+            let rows = stmt.query_map([], |row| Ok(fingerprint_row(
+                &row.get::<_, String>(0)?,
+                row.get::<_, Option<String>>(1)?.as_deref(),
+                row.get::<_, Option<String>>(2)?.as_deref(),
+                row.get::<_, Option<String>>(3)?.as_deref(),
+            )))?;
+            Ok(rows.collect::<rusqlite::Result<_>>()?)
+        }
+        async fn initialize_geo_tables(&self, _: &HashMap<String, String>, _: &HashMap<String, String>) -> Result<()> {
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn test_upsert_resolves_slug_and_ats_job_id_conflicts() {
+        let company = CompanyEntry { name: "Stripe".to_string(), slug: "stripe".to_string(), ..Default::default() };
+        let data = serde_json::json!({"jobs": [
+            {"id": 1, "title": "Software Engineering Intern", "absolute_url": "https://example.com/1"}
+        ]});
+        let mut job = AtsType::Greenhouse.parse(&company, &data).unwrap().remove(0);
+        job.tags = vec!["Rust".to_string()];
+        job.benefits = vec!["Health Insurance".to_string()];
+
+        let db = SqliteDb::new();
+        db.insert_jobs(std::slice::from_ref(&job)).await.unwrap();
+        job.title = "Software Engineering Intern (Summer)".to_string();
+        db.insert_jobs(std::slice::from_ref(&job)).await.unwrap();
+        assert_eq!(db.rows("SELECT title FROM jobs"), vec!["Software Engineering Intern (Summer)"]);
+
+        // Same company and ATS id under a new job id takes over the row, and
+        // its old junction rows are replaced rather than merged
+        job.id = "greenhouse-v2-1".to_string();
+        job.tags = vec!["Go".to_string()];
+        job.benefits = vec!["Dental".to_string()];
+        db.insert_jobs(std::slice::from_ref(&job)).await.unwrap();
+        assert_eq!(db.rows("SELECT id FROM jobs"), vec!["greenhouse-v2-1"]);
+        assert_eq!(db.rows("SELECT job_id || ':' || name FROM job_tags"), vec!["greenhouse-v2-1:Go"]);
+        assert_eq!(db.rows("SELECT job_id || ':' || name FROM job_benefits"), vec!["greenhouse-v2-1:Dental"]);
+    }
+
+    #[tokio::test]
+    async fn test_job_history_archives_changed_fields() {
+        let company = CompanyEntry { name: "Stripe".to_string(), slug: "stripe".to_string(), ..Default::default() };
+        let data = serde_json::json!({"jobs": [
+            {"id": 1, "title": "Software Engineering Intern", "absolute_url": "https://example.com/1", "content": "<p>Write Rust.</p>", "location": {"name": "Dublin"}}
+        ]});
+        let mut job = AtsType::Greenhouse.parse(&company, &data).unwrap().remove(0);
+
+        let db = SqliteDb::new();
+        db.insert_jobs(std::slice::from_ref(&job)).await.unwrap();
+        // Neither a new job nor an unchanged one has history
+        db.insert_jobs(std::slice::from_ref(&job)).await.unwrap();
+        assert!(db.rows("SELECT field_name FROM job_history").is_empty());
+
+        job.title = "Software Engineering Intern (Summer)".to_string();
+        job.description = "<p>Write Rust and Go.</p>".to_string();
+        db.insert_jobs(std::slice::from_ref(&job)).await.unwrap();
+        assert_eq!(
+            db.rows("SELECT job_id || '|' || field_name || '|' || old_value || '|' || new_value FROM job_history ORDER BY field_name"),
+            vec![
+                "greenhouse-1|description|<p>Write Rust.</p>|<p>Write Rust and Go.</p>",
+                "greenhouse-1|title|Software Engineering Intern|Software Engineering Intern (Summer)",
+            ]
+        );
+        assert_eq!(db.rows("SELECT title FROM jobs"), vec!["Software Engineering Intern (Summer)"]);
+
+        job.location = "Remote".to_string();
+        db.insert_jobs(std::slice::from_ref(&job)).await.unwrap();
+        assert_eq!(db.rows("SELECT old_value || ' -> ' || new_value FROM job_history WHERE field_name = 'location'"), vec!["Dublin -> Remote"]);
+        assert_eq!(db.rows("SELECT field_name FROM job_history").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn test_consumer_archives_changes_to_stored_jobs() {
+        let company = CompanyEntry { name: "Stripe".to_string(), slug: "stripe".to_string(), ..Default::default() };
+        let data = serde_json::json!({"jobs": [
+            {"id": 1, "title": "Software Engineering Intern", "absolute_url": "https://example.com/1", "content": "<p>Write Rust.</p>"},
+            {"id": 2, "title": "Data Intern", "absolute_url": "https://example.com/2", "content": "<p>Write SQL.</p>"}
+        ]});
+        let mut jobs = AtsType::Greenhouse.parse(&company, &data).unwrap();
+        let db = Arc::new(SqliteDb::new());
+        db.insert_jobs(&jobs).await.unwrap();
+
+        // A normal run: both jobs are already stored, one has a new title
+        jobs[0].title = "Software Engineering Intern (Summer)".to_string();
+        let metrics = Arc::new(Metrics::default());
+        let (tx, rx) = mpsc::channel(8);
+        let consumer = tokio::spawn(BatchConsumer {
+            db: db.clone(),
+            client: reqwest::Client::new(),
+            metrics: metrics.clone(),
+            sizer: BatchSizer::new(),
+            limit: None,
+            known_ids: db.get_existing_ids().await.unwrap(),
+            fingerprints: db.get_job_fingerprints().await.unwrap(),
+            checkpoint: None,
+            last_run: None,
+            webhook: None,
+        }.run(rx));
+        for job in jobs {
+            tx.send(BatchItem::Job(Box::new(job))).await.unwrap();
+        }
+        drop(tx);
+        consumer.await.unwrap().unwrap();
+
+        assert_eq!(
+            db.rows("SELECT job_id || '|' || field_name || '|' || old_value || '|' || new_value FROM job_history"),
+            vec!["greenhouse-1|title|Software Engineering Intern|Software Engineering Intern (Summer)"]
+        );
+        assert_eq!(db.rows("SELECT title FROM jobs ORDER BY id"), vec!["Software Engineering Intern (Summer)", "Data Intern"]);
+        // Updates aren't new jobs
+        assert_eq!(metrics.jobs_inserted.load(Ordering::SeqCst), 0);
+    }
+
+    #[tokio::test]
+    async fn test_health_check_propagates_failures() {
+        use wiremock::matchers::{method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        // The default check runs `SELECT 1` through execute_batch
+        let db = RecordingDb { queries: Mutex::new(Vec::new()) };
+        let err = db.health_check().await.unwrap_err().to_string();
+        assert!(err.contains("health_check"), "{}", err);
+        assert_eq!(db.queries.lock().unwrap()[0].sql, "SELECT 1");
+
+        let server = MockServer::start().await;
+        Mock::given(method("GET"))
+            .and(path("/accounts/acct/d1/database/db"))
+            .respond_with(ResponseTemplate::new(401).set_body_string("Authentication error"))
+            .mount(&server).await;
+        let db = RemoteD1 {
+            client: reqwest::Client::new(),
+            api_base: server.uri(),
+            account_id: "acct".to_string(),
+            database_id: "db".to_string(),
+            api_token: "bad".to_string(),
+        };
+        let err = db.health_check().await.unwrap_err().to_string();
+        assert!(err.contains("401") && err.contains("Authentication error"), "{}", err);
+    }
+
+    #[tokio::test]
+    async fn test_remote_d1_pages_existing_ids() {
+        use wiremock::matchers::{body_partial_json, method, path};
+        use wiremock::{Mock, MockServer, ResponseTemplate};
+
+        let server = MockServer::start().await;
+        let id = |i: usize| format!("lever-{:05}", i);
+        for page in 0..4 {
+            let offset = page * D1_ID_PAGE_SIZE;
+            // Three full pages, then an empty one ends the loop
+            let rows: Vec<Value> = if page < 3 {
+                (offset..offset + D1_ID_PAGE_SIZE).map(|i| serde_json::json!([id(i)])).collect()
+            } else {
+                vec![]
+            };
+            // Each page starts after the last id of the one before
+            let after = if page == 0 { String::new() } else { id(offset - 1) };
+            Mock::given(method("POST"))
+                .and(path("/accounts/acct/d1/database/db/raw"))
+                .and(body_partial_json(serde_json::json!({"params": [after]})))
+                .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({
+                    "result": [{"results": {"columns": ["id"], "rows": rows}, "success": true}]
+                })))
+                .expect(1)
+                .mount(&server).await;
+        }
+
+        let db = RemoteD1 {
+            client: reqwest::Client::new(),
+            api_base: server.uri(),
+            account_id: "acct".to_string(),
+            database_id: "db".to_string(),
+            api_token: "token".to_string(),
+        };
+        let ids = db.get_existing_ids().await.unwrap();
+        assert_eq!(ids.len(), 3 * D1_ID_PAGE_SIZE);
+        assert!(ids.contains(&id(0)) && ids.contains(&id(2999)));
+    }
+
+    #[test]
+    fn test_enrichment_from_args() {
+        let mut config = Config::load().unwrap();
+        config.enrichment_concurrency = 4;
+        let args = |list: &[&str]| list.iter().map(|s| s.to_string()).collect::<Vec<_>>();
+
+        assert_eq!(Enrichment::from_args(&config, &args(&["zapply"])), Enrichment { enabled: true, concurrency: 4 });
+        let flagged = Enrichment::from_args(&config, &args(&["zapply", "--max-enrichment-concurrency=2", "--no-enrich"]));
+        assert_eq!(flagged, Enrichment { enabled: false, concurrency: 2 });
+        assert_eq!(flagged.buffer_size(), 2);
+
+        let unlimited = Enrichment::from_args(&config, &args(&["zapply", "--max-enrichment-concurrency=0"]));
+        assert_eq!(unlimited.buffer_size(), usize::MAX);
+    }
+
+    #[test]
+    fn test_parse_since() {
+        let now = DateTime::parse_from_rfc3339("2025-06-15T12:00:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_since("2025-01-31", now).unwrap().to_rfc3339(), "2025-01-31T00:00:00+00:00");
+        assert_eq!(parse_since("2025-06-15", now).unwrap().to_rfc3339(), "2025-06-15T00:00:00+00:00");
+        assert!(parse_since("2025-06-16", now).is_err());
+        assert!(parse_since("2025-13-01", now).is_err());
+        assert!(parse_since("31/01/2025", now).is_err());
+    }
+
+    #[test]
+    fn test_batch_context() {
+        let query = |ctx: Option<&str>| DbQuery { sql: String::new(), params: vec![], context: ctx.map(String::from) };
+        assert_eq!(batch_context(&[query(None)]), "unlabelled queries");
+        assert_eq!(batch_context(&[query(Some("a")), query(Some("a")), query(None), query(Some("b"))]), "a, b");
+        assert_eq!(batch_context(&[query(Some("batch:job_tags_delete")), query(Some("lever:acme:job_upsert"))]), "lever:acme:job_upsert, batch:job_tags_delete");
+
+        let many: Vec<DbQuery> = (0..8).map(|i| query(Some(&format!("q{}", i)))).collect();
+        assert_eq!(batch_context(&many), "q0, q1, q2, q3, q4 (+3 more)");
+    }
+
+    #[test]
+    fn test_extract_ld_json_description() {
+        let html = r#"<html><head>
+            <script type="application/ld+json">{"@type": "Organization", "name": "Acme"}</script>
+            <script type='application/ld+json' data-rh="true">
+                {"@context": "https://schema.org", "@type": "JobPosting", "title": "Intern",
+                 "description": "&lt;p&gt;Build things&lt;/p&gt;<p>Join us</p>"}
+            </script>
+        </head><body></body></html>"#;
+        assert_eq!(extract_ld_json_description(html).as_deref(), Some("&lt;p&gt;Build things&lt;/p&gt;<p>Join us</p>"));
+
+        assert_eq!(extract_ld_json_description("<html><body>No structured data</body></html>"), None);
+        assert_eq!(extract_ld_json_description(r#"<script type="application/ld+json">{not json}</script>"#), None);
+    }
+
+    #[test]
+    fn test_json_log_output() {
+        #[derive(Clone, Default)]
+        struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+        impl std::io::Write for Buffer {
+            fn write(&mut self, buf: &[u8]) -> std::io::Result<usize> {
+                self.0.lock().unwrap().extend_from_slice(buf);
+                Ok(buf.len())
+            }
+            fn flush(&mut self) -> std::io::Result<()> {
+                Ok(())
+            }
+        }
+
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = json_subscriber(EnvFilter::new("info"), move || writer.clone());
+        tracing::subscriber::with_default(subscriber, || {
+            info!(company = "Acme", ats_type = ?AtsType::Greenhouse, jobs_count = 3, "Parsed jobs");
+            debug!("filtered out");
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 1);
+
+        let event: Value = serde_json::from_str(lines[0]).unwrap();
+        assert!(event["timestamp"].is_string());
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(event["target"], "zapply::tests");
+        assert_eq!(event["message"], "Parsed jobs");
+        assert_eq!(event["company"], "Acme");
+        assert_eq!(event["ats_type"], "Greenhouse");
+        assert_eq!(event["jobs_count"], 3);
+    }
+
+    #[test]
+    fn test_sanitize_sql_param() {
+        assert_eq!(sanitize_sql_param("Normal String"), "Normal String");
+        assert_eq!(sanitize_sql_param("O'Reilly"), "O''Reilly");
+        assert_eq!(sanitize_sql_param("Multiple ' ' quotes"), "Multiple '' '' quotes");
+        assert_eq!(sanitize_sql_param(""), "");
+        assert_eq!(sanitize_sql_param("a\0b"), "ab");
+        // Comment and statement syntax is inert once quoted
+        assert_eq!(sanitize_sql_param("x'; DROP TABLE jobs; -- /* `id`"), "x''; DROP TABLE jobs; -- /* `id`");
+    }
+
+    #[test]
+    fn test_db_query_to_sql() {
+        let query = DbQuery {
+            sql: "INSERT INTO table (col1, col2, col3) VALUES (?1, ?2, ?3)".to_string(),
+            params: vec![
+                Value::String("O'Reilly".to_string()),
+                Value::Number(serde_json::Number::from(42)),
+                Value::Bool(true),
+            ],
+            context: None,
+        };
+        let sql = query.to_sql();
+        assert_eq!(sql, "INSERT INTO table (col1, col2, col3) VALUES ('O''Reilly', 42, 1)");
+    }
+    
+    #[test]
+    fn test_db_query_to_sql_order() {
+         let query = DbQuery {
+            sql: "SELECT * FROM t WHERE id = ?2 AND name = ?1".to_string(),
+            params: vec![
+                Value::String("Test".to_string()),
+                Value::Number(serde_json::Number::from(100)),
+            ],
+            context: None,
+        };
+        let sql = query.to_sql();
+        assert_eq!(sql, "SELECT * FROM t WHERE id = 100 AND name = 'Test'");
+    }
+
+    fn company(slug: &str, ats_type: AtsType, api_url: &str) -> CompanyEntry {
+        CompanyEntry {
+            name: slug.to_string(),
+            ats_type,
+            slug: slug.to_string(),
+            api_url: api_url.to_string(),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_validate_companies_dedupes_slugs() {
+        let companies = vec![
+            company("stripe", AtsType::Greenhouse, "https://boards-api.greenhouse.io/v1/boards/stripe/jobs"),
+            company("spotify", AtsType::Lever, "https://api.lever.co/v0/postings/spotify"),
+            company("stripe", AtsType::Greenhouse, "https://boards-api.greenhouse.io/v1/boards/stripe/jobs"),
+        ];
+        let valid = validate_companies(&companies).unwrap();
+        assert_eq!(valid.len(), 2);
+        assert_eq!(valid[0].ats_type, AtsType::Greenhouse);
+        assert_eq!(valid[1].slug, "spotify");
+
+        // One slug for two different boards would mix their jobs
+        let companies = vec![
+            company("stripe", AtsType::Greenhouse, "https://boards-api.greenhouse.io/v1/boards/stripe/jobs"),
+            company("stripe", AtsType::Lever, "https://api.lever.co/v0/postings/stripe"),
+        ];
+        let err = validate_companies(&companies).unwrap_err().to_string();
+        assert!(err.contains("Slug \"stripe\" is used by both"), "{}", err);
+    }
+
+    #[test]
+    fn test_parse_ats_types() {
+        assert_eq!(parse_ats_types("greenhouse, Ashby").unwrap(), vec![AtsType::Greenhouse, AtsType::Ashby]);
+        assert_eq!(parse_ats_types("SMARTRECRUITERS,").unwrap(), vec![AtsType::SmartRecruiters]);
+        let err = parse_ats_types("lever,workday").unwrap_err().to_string();
+        assert!(err.contains("\"workday\"") && err.contains("available: greenhouse, lever, smartrecruiters"), "{}", err);
+        assert!(parse_ats_types("unknown").is_err());
+    }
+
+    #[test]
+    fn test_validate_companies_prefers_entry_with_domain() {
+        let lever = "https://api.lever.co/v0/postings/stripe";
+        let mut with_domain = company("stripe", AtsType::Lever, lever);
+        with_domain.domain = Some("stripe.com".to_string());
+        let companies = vec![
+            company("stripe", AtsType::Lever, lever),
+            company("spotify", AtsType::Lever, "https://api.lever.co/v0/postings/spotify"),
+            with_domain,
+            company("stripe", AtsType::Lever, lever),
+        ];
+        let valid = validate_companies(&companies).unwrap();
+        assert_eq!(valid.len(), 2);
+        // Keeps the first entry's position in the list
+        assert_eq!(valid[0].slug, "stripe");
+        assert_eq!(valid[0].domain.as_deref(), Some("stripe.com"));
+    }
+
+    #[test]
+    fn test_validate_companies_rejects_bad_urls() {
+        let err = validate_companies(&[company("acme", AtsType::Greenhouse, "boards-api.greenhouse.io/acme")]).unwrap_err();
+        assert!(err.to_string().contains("invalid api_url"), "{}", err);
+
+        let mut custom = company("acme", AtsType::Unknown, "https://acme.com/careers");
+        custom.custom_api_url = Some("not a url".to_string());
+        let err = validate_companies(&[custom]).unwrap_err();
+        assert!(err.to_string().contains("invalid custom_api_url"), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_companies_stages() {
+        let mut seed = company("acme", AtsType::Lever, "https://api.lever.co/v0/postings/acme");
+        seed.stage = Some("series a".to_string());
+        seed.industry = Some("FinTech".to_string());
+        let valid = validate_companies(&[seed.clone()]).unwrap();
+        assert_eq!(valid[0].stage.as_deref(), Some("Series A"));
+        assert_eq!(valid[0].industry.as_deref(), Some("FinTech"));
+
+        seed.stage = Some("Unicorn".to_string());
+        let err = validate_companies(&[seed]).unwrap_err();
+        assert!(err.to_string().contains("unknown stage \"Unicorn\""), "{}", err);
+    }
+
+    #[test]
+    fn test_validate_companies_keeps_unknown_entries() {
+        // Unknown without custom_api_url only warns; auto-detection handles it
+        let valid = validate_companies(&[company("acme", AtsType::Unknown, "https://jobs.lever.co/acme")]).unwrap();
+        assert_eq!(valid.len(), 1);
+    }
+
+    #[test]
+    fn test_company_extra_keywords() {
+        let keyword_regex = Config::load().unwrap().keyword_regex().unwrap();
+        let mut acme = company("acme", AtsType::Greenhouse, "https://boards-api.greenhouse.io/v1/boards/acme/jobs");
+        assert!(company_keyword_regex(&acme).is_none());
+        assert!(!matches_keywords("New Grad SWE", &keyword_regex, None));
+
+        acme.extra_keywords = Some(vec!["New Grad".to_string(), "C++".to_string(), " ".to_string()]);
+        let company_regex = company_keyword_regex(&acme).unwrap();
+        assert!(matches_keywords("New Grad SWE", &keyword_regex, Some(&company_regex)));
+        assert!(matches_keywords("new grad software engineer", &keyword_regex, Some(&company_regex)));
+        // Keywords are literal, not regex syntax
+        assert!(matches_keywords("C++ Developer", &keyword_regex, Some(&company_regex)));
+        assert!(!matches_keywords("Cxx Developer", &keyword_regex, Some(&company_regex)));
+        // The global regex still applies
+        assert!(matches_keywords("Software Engineering Intern", &keyword_regex, Some(&company_regex)));
+        assert!(!matches_keywords("Account Executive", &keyword_regex, Some(&company_regex)));
+    }
+
+    #[test]
+    fn test_board_url_greenhouse_include_content() {
+        let api_url = "https://boards-api.greenhouse.io/v1/boards/acme/jobs";
+        let acme = company("acme", AtsType::Greenhouse, api_url);
+        assert_eq!(board_url(&acme, None), format!("{}?content=true", api_url));
+
+        let without: CompanyEntry = serde_json::from_value(serde_json::json!({
+            "name": "Acme", "type": "greenhouse", "slug": "acme", "api_url": api_url,
+            "ats_options": {"include_content": false},
+        })).unwrap();
+        assert_eq!(board_url(&without, None), api_url);
+
+        // Only Greenhouse looks at the option
+        let lever = company("acme", AtsType::Lever, "https://api.lever.co/v0/postings/acme?mode=json");
+        assert_eq!(board_url(&lever, None), "https://api.lever.co/v0/postings/acme?mode=json");
+    }
+
+    #[test]
+    fn test_board_url_greenhouse_updated_after() {
+        let since: DateTime<Utc> = "2024-01-01T00:00:00Z".parse().unwrap();
+        let acme = company("acme", AtsType::Greenhouse, "https://boards-api.greenhouse.io/v1/boards/acme/jobs");
+        assert_eq!(board_url(&acme, Some(since)), "https://boards-api.greenhouse.io/v1/boards/acme/jobs?content=true&updated_after=2024-01-01T00%3A00%3A00Z");
+        assert_eq!(board_url(&acme, None), "https://boards-api.greenhouse.io/v1/boards/acme/jobs?content=true");
+
+        // Other boards have no incremental endpoint
+        let lever = company("acme", AtsType::Lever, "https://api.lever.co/v0/postings/acme?mode=json");
+        assert_eq!(board_url(&lever, Some(since)), "https://api.lever.co/v0/postings/acme?mode=json");
+    }
+
+    mod sql_properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// True when every quote in a literal's body is doubled.
+        fn quotes_are_escaped(body: &str) -> bool {
+            let mut chars = body.chars();
+            while let Some(c) = chars.next() {
+                if c == '\'' && chars.next() != Some('\'') {
+                    return false;
+                }
+            }
+            true
+        }
+
+        fn string_query(sql: String, params: Vec<String>) -> DbQuery {
+            DbQuery { sql, params: params.into_iter().map(Value::String).collect(), context: None }
+        }
+
+        proptest! {
+            #[test]
+            fn string_params_cannot_close_the_literal(s in any::<String>()) {
+                let sql = string_query("SELECT ?1".to_string(), vec![s]).to_sql();
+                let body = sql.strip_prefix("SELECT '").and_then(|b| b.strip_suffix('\''));
+                prop_assert!(body.is_some_and(quotes_are_escaped), "{}", sql);
+            }
+
+            #[test]
+            fn escaping_round_trips(s in any::<String>()) {
+                prop_assert_eq!(sanitize_sql_param(&s).replace("''", "'"), s.replace('\0', ""));
+            }
+
+            /// SQLite itself must see exactly one statement that yields the input back.
+            #[test]
+            fn sanitized_literals_parse_in_sqlite(
+                s in any::<String>(),
+                injection in proptest::sample::select(vec!["'", "';", "--", "/*", "*/", "`", "\0", "'); DROP TABLE jobs; --"]),
+            ) {
+                let input = format!("{}{}{}", injection, s, injection);
+                let conn = rusqlite::Connection::open_in_memory().unwrap();
+                let sql = string_query("SELECT ?1".to_string(), vec![input.clone()]).to_sql();
+                let mut stmt = conn.prepare(&sql).map_err(|e| TestCaseError::fail(format!("{}: {}", sql, e)))?;
+                let value: String = stmt.query_row([], |row| row.get(0)).unwrap();
+                prop_assert_eq!(value, input.replace('\0', ""));
+            }
+
+            #[test]
+            fn placeholders_substitute_in_any_order(
+                (order, values) in (1usize..=100).prop_flat_map(|n| (
+                    Just((1..=n).collect::<Vec<_>>()).prop_shuffle(),
+                    proptest::collection::vec(any::<String>(), n),
+                ))
+            ) {
+                let sql = order.iter().map(|i| format!("?{}", i)).collect::<Vec<_>>().join(", ");
+                let expected = order.iter()
+                    .map(|i| format!("'{}'", sanitize_sql_param(&values[i - 1])))
+                    .collect::<Vec<_>>()
+                    .join(", ");
+                prop_assert_eq!(string_query(sql, values).to_sql(), expected);
+            }
+        }
+    }
+}
@@ -0,0 +1,197 @@
+use anyhow::Result;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Instant;
+
+use crate::error::ScraperError;
+use crate::models::AtsType;
+use crate::stats::write_table;
+
+/// Run counters, written out in Prometheus text format with `--metrics`.
+#[derive(Default)]
+pub struct Metrics {
+    pub companies_total: AtomicUsize,
+    pub companies_failed: AtomicUsize,
+    pub jobs_inserted: AtomicUsize,
+    pub jobs_deduped: AtomicUsize,
+    pub enrichment_failures: AtomicUsize,
+    /// Jobs `--no-enrich` left without a description.
+    pub enrichment_skipped: AtomicUsize,
+    jobs_found: Mutex<BTreeMap<String, usize>>,
+    failures: Mutex<BTreeMap<&'static str, usize>>,
+}
+
+impl Metrics {
+    pub fn add_jobs_found(&self, ats: AtsType, n: usize) {
+        let label = serde_json::to_string(&ats).unwrap_or_default().trim_matches('"').to_string();
+        *self.jobs_found.lock().unwrap().entry(label).or_insert(0) += n;
+    }
+
+    pub fn record_failure(&self, err: &ScraperError) {
+        *self.failures.lock().unwrap().entry(err.kind()).or_insert(0) += 1;
+    }
+
+    pub fn jobs_found_total(&self) -> usize {
+        self.jobs_found.lock().unwrap().values().sum()
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut counter = |name: &str, help: &str, samples: &[(String, usize)]| {
+            writeln!(out, "# HELP {} {}", name, help).ok();
+            writeln!(out, "# TYPE {} counter", name).ok();
+            for (labels, value) in samples {
+                writeln!(out, "{}{} {}", name, labels, value).ok();
+            }
+        };
+        let load = |c: &AtomicUsize| vec![(String::new(), c.load(Ordering::SeqCst))];
+
+        counter("zapply_companies_total", "Companies processed.", &load(&self.companies_total));
+        counter("zapply_companies_failed_total", "Companies whose board could not be fetched or parsed.", &load(&self.companies_failed));
+        let found: Vec<_> = self.jobs_found.lock().unwrap().iter()
+            .map(|(ats, n)| (format!("{{ats=\"{}\"}}", ats), *n))
+            .collect();
+        counter("zapply_jobs_found_total", "Jobs kept after filtering, by ATS.", &found);
+        counter("zapply_jobs_inserted_total", "Jobs written to the database.", &load(&self.jobs_inserted));
+        counter("zapply_jobs_deduped_total", "Jobs dropped as duplicate listings.", &load(&self.jobs_deduped));
+        counter("zapply_enrichment_failures_total", "Jobs dropped because enrichment failed.", &load(&self.enrichment_failures));
+        counter("zapply_enrichment_skipped_total", "Jobs not enriched because of --no-enrich.", &load(&self.enrichment_skipped));
+        let failures: Vec<_> = self.failures.lock().unwrap().iter()
+            .map(|(kind, n)| (format!("{{kind=\"{}\"}}", kind), *n))
+            .collect();
+        counter("zapply_failures_total", "Failures by error kind.", &failures);
+        out
+    }
+
+    pub fn write_to(&self, path: &str) -> Result<()> {
+        std::fs::write(path, self.render())?;
+        Ok(())
+    }
+}
+
+/// How long one job spent in each `normalize_job` step, with `ZAPPLY_PROFILE=1`.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NormalizationMetrics {
+    pub tag_detection_us: u64,
+    pub edu_detection_us: u64,
+    pub location_resolution_us: u64,
+}
+
+/// Times consecutive steps; does nothing unless profiling is on.
+pub struct StepTimer(Option<Instant>);
+
+impl StepTimer {
+    pub fn start(enabled: bool) -> Self {
+        Self(enabled.then(Instant::now))
+    }
+
+    /// Microseconds since the last lap (or the start), 0 when disabled.
+    pub fn lap(&mut self) -> u64 {
+        let Some(last) = self.0.as_mut() else { return 0 };
+        let now = Instant::now();
+        let elapsed = now.duration_since(*last).as_micros() as u64;
+        *last = now;
+        elapsed
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+struct StepStats {
+    min: u64,
+    max: u64,
+    total: u64,
+}
+
+impl StepStats {
+    fn record(&mut self, us: u64, first: bool) {
+        self.min = if first { us } else { self.min.min(us) };
+        self.max = self.max.max(us);
+        self.total += us;
+    }
+}
+
+/// Min/max/avg of every job's `NormalizationMetrics`, printed at the end of a run.
+#[derive(Debug, Default)]
+pub struct NormalizationProfile {
+    jobs: u64,
+    tag_detection: StepStats,
+    edu_detection: StepStats,
+    location_resolution: StepStats,
+}
+
+impl NormalizationProfile {
+    pub fn record(&mut self, metrics: NormalizationMetrics) {
+        let first = self.jobs == 0;
+        self.tag_detection.record(metrics.tag_detection_us, first);
+        self.edu_detection.record(metrics.edu_detection_us, first);
+        self.location_resolution.record(metrics.location_resolution_us, first);
+        self.jobs += 1;
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let row = |name: &str, stats: &StepStats| vec![
+            name.to_string(),
+            stats.min.to_string(),
+            (stats.total / self.jobs.max(1)).to_string(),
+            stats.max.to_string(),
+            stats.total.to_string(),
+        ];
+        let rows = vec![
+            row("Tag detection", &self.tag_detection),
+            row("Education detection", &self.edu_detection),
+            row("Location resolution", &self.location_resolution),
+        ];
+        write_table(&mut out, &format!("Normalization timings ({} jobs, µs)", self.jobs), &["Step", "Min", "Avg", "Max", "Total"], rows);
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_prometheus() {
+        let metrics = Metrics::default();
+        metrics.companies_total.fetch_add(3, Ordering::SeqCst);
+        metrics.companies_failed.fetch_add(1, Ordering::SeqCst);
+        metrics.jobs_inserted.fetch_add(7, Ordering::SeqCst);
+        metrics.add_jobs_found(AtsType::Greenhouse, 5);
+        metrics.add_jobs_found(AtsType::Lever, 2);
+        metrics.add_jobs_found(AtsType::Greenhouse, 1);
+        metrics.record_failure(&ScraperError::HttpError { status: 404, url: String::new() });
+        metrics.record_failure(&ScraperError::HttpError { status: 500, url: String::new() });
+        metrics.record_failure(&ScraperError::DbError { detail: String::new() });
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE zapply_companies_total counter\nzapply_companies_total 3\n"));
+        assert!(text.contains("zapply_companies_failed_total 1\n"));
+        assert!(text.contains("zapply_jobs_found_total{ats=\"greenhouse\"} 6\n"));
+        assert!(text.contains("zapply_jobs_found_total{ats=\"lever\"} 2\n"));
+        assert!(text.contains("zapply_jobs_inserted_total 7\n"));
+        assert!(text.contains("zapply_jobs_deduped_total 0\n"));
+        assert!(text.contains("zapply_enrichment_failures_total 0\n"));
+        assert!(text.contains("zapply_failures_total{kind=\"http\"} 2\n"));
+        assert!(text.contains("zapply_failures_total{kind=\"db\"} 1\n"));
+        assert_eq!(metrics.jobs_found_total(), 8);
+    }
+
+    #[test]
+    fn test_normalization_profile() {
+        let mut profile = NormalizationProfile::default();
+        profile.record(NormalizationMetrics { tag_detection_us: 300, edu_detection_us: 20, location_resolution_us: 90 });
+        profile.record(NormalizationMetrics { tag_detection_us: 100, edu_detection_us: 40, location_resolution_us: 10 });
+
+        let text = profile.render();
+        assert!(text.contains("Normalization timings (2 jobs, µs)"), "{}", text);
+        let tags = text.lines().find(|l| l.starts_with("Tag detection")).unwrap();
+        assert_eq!(tags.split_whitespace().skip(2).collect::<Vec<_>>(), vec!["100", "200", "300", "400"]);
+        let location = text.lines().find(|l| l.starts_with("Location resolution")).unwrap();
+        assert_eq!(location.split_whitespace().skip(2).collect::<Vec<_>>(), vec!["10", "50", "90", "100"]);
+
+        assert_eq!(StepTimer::start(false).lap(), 0);
+    }
+}
@@ -1,41 +1,109 @@
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::HashMap;
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq, Copy, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum WorkMode {
     Remote,
     Hybrid,
+    /// Remote with no location restriction ("Worldwide", "Global - Remote").
+    Global,
+    #[default]
     InOffice,
 }
 
-impl Default for WorkMode {
-    fn default() -> Self {
-        Self::InOffice
-    }
-}
-
-#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq)]
+/// Seniority implied by a job title; see `tag::detect_seniority`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq, PartialOrd, Ord, Default)]
+#[serde(rename_all = "lowercase")]
+pub enum SeniorityLevel {
+    Intern,
+    EntryLevel,
+    Associate,
+    /// Also the fallback for titles with no seniority marker.
+    #[default]
+    MidLevel,
+    Senior,
+    Staff,
+    Principal,
+    Lead,
+    Director,
+    Executive,
+}
+
+/// Normalized from the ATS's freeform value; see `parsers::normalize_employment_type`.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum EmploymentType {
+    FullTime,
+    PartTime,
+    Contract,
+    Internship,
+    CoOp,
+}
+
+/// Serialized lowercase; see `from_name` for what deserializes.
+#[derive(Debug, Serialize, Clone, Copy, PartialEq, Eq, Default)]
 #[serde(rename_all = "lowercase")]
 pub enum AtsType {
-    #[serde(alias = "Greenhouse")]
     Greenhouse,
-    #[serde(alias = "Lever")]
     Lever,
-    #[serde(alias = "SmartRecruiters")]
     SmartRecruiters,
-    #[serde(alias = "Ashby")]
     Ashby,
-    #[serde(alias = "Workable")]
     Workable,
-    #[serde(alias = "Recruitee")]
     Recruitee,
-    #[serde(alias = "Breezy")]
     Breezy,
-    #[serde(other)]
+    #[default]
     Unknown,
 }
 
+impl AtsType {
+    /// Every type with a parser, i.e. all but `Unknown`.
+    pub const KNOWN: [AtsType; 7] = [
+        Self::Greenhouse, Self::Lever, Self::SmartRecruiters, Self::Ashby,
+        Self::Workable, Self::Recruitee, Self::Breezy,
+    ];
+
+    /// Matches however the name was typed into slugs.json: case, spaces, `_`
+    /// and `-` are ignored ("Smart_Recruiters", "GREEN-HOUSE"). Anything else
+    /// is `Unknown`, so the company goes through auto-detection.
+    pub fn from_name(name: &str) -> Self {
+        let normalized: String = name.chars()
+            .filter(|c| !matches!(c, ' ' | '_' | '-'))
+            .flat_map(char::to_lowercase)
+            .collect();
+        match normalized.as_str() {
+            "greenhouse" => Self::Greenhouse,
+            "lever" => Self::Lever,
+            "smartrecruiters" | "smartrecruiter" => Self::SmartRecruiters,
+            "ashby" | "ashbyhq" => Self::Ashby,
+            "workable" => Self::Workable,
+            "recruitee" => Self::Recruitee,
+            "breezy" | "breezyhr" => Self::Breezy,
+            _ => Self::Unknown,
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for AtsType {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let name = std::borrow::Cow::<str>::deserialize(deserializer)?;
+        Ok(Self::from_name(&name))
+    }
+}
+
+/// Request method for a company's board; everything but Workday-style APIs uses GET.
+#[derive(Debug, Serialize, Deserialize, Clone, Copy, PartialEq, Eq, Default)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum HttpMethod {
+    #[default]
+    #[serde(alias = "get", alias = "Get")]
+    Get,
+    #[serde(alias = "post", alias = "Post")]
+    Post,
+}
+
 #[derive(Debug, Deserialize, Clone, Serialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum FlexibleId {
@@ -43,6 +111,29 @@ pub enum FlexibleId {
     String(String),
 }
 
+impl FlexibleId {
+    /// The id as a number, including numeric strings like `"4012345"`.
+    pub fn as_number(&self) -> Option<i64> {
+        match self {
+            Self::Number(n) => Some(*n),
+            Self::String(s) => s.parse().ok(),
+        }
+    }
+
+    /// The id as text; only a `Number` allocates.
+    pub fn as_string_ref(&self) -> std::borrow::Cow<'_, str> {
+        match self {
+            Self::Number(n) => std::borrow::Cow::Owned(n.to_string()),
+            Self::String(s) => std::borrow::Cow::Borrowed(s),
+        }
+    }
+
+    /// True for Greenhouse-style numeric ids, false for UUIDs and other strings.
+    pub fn is_numeric(&self) -> bool {
+        self.as_number().is_some()
+    }
+}
+
 impl std::fmt::Display for FlexibleId {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         match self {
@@ -52,6 +143,33 @@ impl std::fmt::Display for FlexibleId {
     }
 }
 
+/// Breezy sends a department as a plain name or, on newer boards, as
+/// `{"id": "...", "name": "..."}`.
+#[derive(Debug, Deserialize, Clone, Serialize, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum FlexibleDepartment {
+    Name(String),
+    Object { id: Option<FlexibleId>, name: Option<String> },
+}
+
+impl std::fmt::Display for FlexibleDepartment {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Name(name) | Self::Object { name: Some(name), .. } => write!(f, "{}", name),
+            Self::Object { name: None, .. } => Ok(()),
+        }
+    }
+}
+
+impl From<FlexibleDepartment> for String {
+    fn from(department: FlexibleDepartment) -> Self {
+        match department {
+            FlexibleDepartment::Name(name) | FlexibleDepartment::Object { name: Some(name), .. } => name,
+            FlexibleDepartment::Object { name: None, .. } => String::new(),
+        }
+    }
+}
+
 #[derive(Debug, Deserialize, Clone, Serialize)]
 #[serde(untagged)]
 pub enum AtsDescription {
@@ -69,38 +187,175 @@ impl AtsDescription {
 }
 
 
-#[derive(Debug, Deserialize, Clone)]
+#[derive(Debug, Deserialize, Clone, Default)]
 pub struct CompanyEntry {
     pub name: String,
-    #[serde(rename = "type")]
+    /// Left out for companies whose ATS should be auto-detected.
+    #[serde(rename = "type", default)]
     pub ats_type: AtsType,
     pub slug: String,
     pub api_url: String,
     pub domain: Option<String>,
+    /// Endpoint for a custom (`type: unknown`) job board; falls back to `api_url`.
+    #[serde(default)]
+    pub custom_api_url: Option<String>,
+    /// Dot-separated path to the jobs array in a custom response, e.g. "data.jobs".
+    #[serde(default)]
+    pub custom_json_path: Option<String>,
+    /// Job field -> response key overrides for custom boards, e.g. {"url": "absolute_url"}.
+    #[serde(default)]
+    pub field_map: Option<HashMap<String, String>>,
+    /// Extra title keywords matched alongside KEYWORDS_REGEX, e.g. ["new grad"].
+    #[serde(default)]
+    pub extra_keywords: Option<Vec<String>>,
+    /// Free-form sector, e.g. "FinTech" or "HealthTech".
+    #[serde(default)]
+    pub industry: Option<String>,
+    /// Funding stage; must be one of `KNOWN_STAGES` in main.rs.
+    #[serde(default)]
+    pub stage: Option<String>,
+    /// Minimum hours between successful scrapes, for endpoints that 429 when polled every run.
+    #[serde(default)]
+    pub scrape_interval_hours: Option<u64>,
+    #[serde(default)]
+    pub http_method: Option<HttpMethod>,
+    /// JSON body sent with POST requests. Any `"{cursor}"` in it is replaced
+    /// with the page cursor (`null` on the first page).
+    #[serde(default)]
+    pub request_body: Option<Value>,
+    /// Company logo for the job board; otherwise taken from the ATS response when it has one.
+    #[serde(default)]
+    pub logo_url: Option<String>,
+    /// ATS-specific settings, e.g. `{"include_content": false}` for Greenhouse.
+    #[serde(default)]
+    pub ats_options: Option<HashMap<String, Value>>,
+}
+
+impl CompanyEntry {
+    /// A boolean from `ats_options`; `default` when it's missing or not a bool.
+    pub fn ats_option_bool(&self, key: &str, default: bool) -> bool {
+        self.ats_options.as_ref()
+            .and_then(|options| options.get(key))
+            .and_then(Value::as_bool)
+            .unwrap_or(default)
+    }
+
+    /// `request_body` with `{cursor}` filled in.
+    pub fn request_body_for(&self, cursor: Option<&str>) -> Option<Value> {
+        self.request_body.as_ref().map(|body| interpolate_cursor(body, cursor))
+    }
+}
+
+fn interpolate_cursor(value: &Value, cursor: Option<&str>) -> Value {
+    match value {
+        Value::String(s) if s == "{cursor}" => cursor.map(|c| Value::String(c.to_string())).unwrap_or(Value::Null),
+        Value::String(s) => Value::String(s.replace("{cursor}", cursor.unwrap_or(""))),
+        Value::Array(items) => Value::Array(items.iter().map(|v| interpolate_cursor(v, cursor)).collect()),
+        Value::Object(map) => Value::Object(map.iter().map(|(k, v)| (k.clone(), interpolate_cursor(v, cursor))).collect()),
+        other => other.clone(),
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize, Clone)]
+#[derive(Debug, Serialize, Deserialize, Clone, Default)]
 #[serde(rename_all = "camelCase")]
 pub struct Job {
     pub id: String,
+    /// The ATS's own id, before `id` is prefixed with the ATS name.
+    pub ats_job_id: String,
     pub title: String,
     pub description: String,
+    /// Words in `description` once HTML tags are stripped.
+    pub description_length: usize,
+    /// 0.0-1.0, from `tag::description_quality`.
+    pub description_quality_score: f32,
+    /// `description` as plain text; only filled when INCLUDE_TEXT_DESCRIPTION is set.
+    pub text_description: Option<String>,
+    /// SHA-256 hex of `description`, so the upsert can detect changes cheaply.
+    pub description_hash: String,
     pub company: String,
     pub slug: String,
     pub ats: AtsType,
     pub url: String,
+    /// Direct application form, when the ATS has one separate from `url`.
+    pub apply_url: Option<String>,
+    /// The board API the job was parsed from, for reproducing parser bugs.
+    /// Serialized with the job but not stored in the database.
+    #[serde(default)]
+    pub source_url: String,
     pub company_url: Option<String>,
+    pub company_industry: Option<String>,
+    pub company_stage: Option<String>,
+    pub logo_url: Option<String>,
     pub location: String,
+    /// The ATS's own label next to structured location fields, e.g. Greenhouse's
+    /// "New York City (Remote)"; only read for work mode and timezone hints.
+    #[serde(skip)]
+    pub location_name: Option<String>,
     pub city: Option<String>,
     pub region: Option<String>,
     pub country: Option<String>,
     pub country_code: Option<String>,
-    pub posted: String,
+    pub metro_area: Option<String>,
+    /// IANA zone (or `UTC±HH:MM`) the location or title asks for, e.g. "America/New_York".
+    pub preferred_timezone: Option<String>,
+    /// `None` when the ATS gave no date or one we couldn't parse.
+    #[serde(with = "rfc3339_opt", default)]
+    pub posted: Option<DateTime<Utc>>,
+    #[serde(default)]
+    pub seniority_level: SeniorityLevel,
+    /// `None` when the ATS has no employment type or one we don't recognize.
+    #[serde(default)]
+    pub employment_type: Option<EmploymentType>,
     pub departments: Vec<String>,
+    /// First department mapped to a `normalization::DEPARTMENT_CATEGORIES` value.
+    pub normalized_department: Option<String>,
     pub offices: Vec<String>,
     pub tags: Vec<String>,
     pub degree_levels: Vec<String>,
     pub subject_areas: Vec<String>,
+    /// From the description's benefits section, via `tag::BenefitsExtractor`.
+    pub benefits: Vec<String>,
+}
+
+#[cfg(any(test, feature = "test-util"))]
+impl Job {
+    /// A Lever internship at "Acme" for tests. Other fields come from struct
+    /// update syntax, e.g. `Job { location: ..., ..Job::fixture("lever-1") }`.
+    pub fn fixture(id: &str) -> Self {
+        Self {
+            id: id.to_string(),
+            title: "Intern".to_string(),
+            company: "Acme".to_string(),
+            slug: "acme".to_string(),
+            ats: AtsType::Lever,
+            ..Default::default()
+        }
+    }
+}
+
+/// The format `posted` is stored in.
+pub fn format_date(dt: DateTime<Utc>) -> String {
+    dt.to_rfc3339()
+}
+
+/// Serializes `Option<DateTime<Utc>>` as an RFC 3339 string or null.
+mod rfc3339_opt {
+    use chrono::{DateTime, Utc};
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    pub fn serialize<S: Serializer>(dt: &Option<DateTime<Utc>>, serializer: S) -> Result<S::Ok, S::Error> {
+        match dt {
+            Some(dt) => serializer.serialize_str(&super::format_date(*dt)),
+            None => serializer.serialize_none(),
+        }
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<DateTime<Utc>>, D::Error> {
+        Option::<String>::deserialize(deserializer)?
+            .filter(|s| !s.is_empty())
+            .map(|s| DateTime::parse_from_rfc3339(&s).map(|dt| dt.with_timezone(&Utc)).map_err(serde::de::Error::custom))
+            .transpose()
+    }
 }
 
 // --- Specialized Response Structs ---
@@ -151,13 +406,45 @@ pub struct RawGreenhouseNameItem {
 pub struct LeverJob {
     pub id: String,
     pub text: String,
+    #[serde(alias = "hostedUrl")]
     pub hosted_url: String,
+    /// Direct application form; `hostedUrl` is the listing page.
+    #[serde(rename = "applyUrl")]
+    pub apply_url: Option<String>,
     pub description: Option<String>,
+    /// Extra HTML shown after the lists, often benefits or equity details.
+    pub additional: Option<String>,
+    #[serde(default)]
+    pub lists: Vec<LeverList>,
     pub categories: LeverCategories,
     #[serde(rename = "createdAt")]
     pub created_at: Option<u64>,
 }
 
+/// A titled bullet list, e.g. "Requirements".
+#[derive(Deserialize)]
+pub struct LeverList {
+    pub text: String,
+    pub content: LeverListContent,
+}
+
+/// The postings API sends `<li>` markup; some boards send plain items instead.
+#[derive(Deserialize)]
+#[serde(untagged)]
+pub enum LeverListContent {
+    Html(String),
+    Items(Vec<String>),
+}
+
+impl LeverListContent {
+    pub fn to_html(&self) -> String {
+        match self {
+            Self::Html(html) => html.clone(),
+            Self::Items(items) => items.iter().map(|item| format!("<li>{}</li>", item)).collect(),
+        }
+    }
+}
+
 #[derive(Deserialize)]
 pub struct LeverCategories {
     pub location: Option<String>,
@@ -177,12 +464,21 @@ pub struct SmartRecruitersResponse {
 pub struct SmartRecruitersJob {
     pub id: String,
     pub name: String,
+    pub company: Option<SmartRecruitersCompany>,
     pub released_date: Option<String>,
     pub location: SmartRecruitersLocation,
     pub department: Option<SmartRecruitersLabel>,
     pub type_of_employment: Option<SmartRecruitersIdLabel>,
     pub custom_field: Option<Vec<SmartRecruitersCustomField>>,
     pub posting_url: Option<String>,
+    pub apply_url: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct SmartRecruitersCompany {
+    pub identifier: Option<String>,
+    pub name: Option<String>,
+    pub logo: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -192,6 +488,8 @@ pub struct SmartRecruitersLocation {
     pub region: Option<String>,
     pub country: Option<String>,
     pub full_location: Option<String>,
+    pub remote: Option<bool>,
+    pub hybrid: Option<bool>,
 }
 
 #[derive(Deserialize)]
@@ -214,6 +512,15 @@ pub struct SmartRecruitersCustomField {
 #[derive(Deserialize)]
 pub struct AshbyResponse {
     pub jobs: Vec<AshbyJob>,
+    #[serde(rename = "jobBoard")]
+    pub job_board: Option<AshbyJobBoard>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AshbyJobBoard {
+    pub title: Option<String>,
+    pub logo: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -222,10 +529,25 @@ pub struct AshbyJob {
     pub id: String,
     pub title: String,
     pub job_url: String,
+    pub apply_url: Option<String>,
     pub location: Option<Value>, // Changed from Option<String>
     pub published_at: Option<String>,
     pub department: Option<String>,
     pub description_html: Option<AtsDescription>,
+    pub is_remote: Option<bool>,
+    /// "FullTime", "PartTime", "Intern", "Contract" or "Temporary".
+    pub employment_type: Option<String>,
+    /// Top-level on v1 boards; v2 nests it under `compensation`.
+    pub compensation_tier_summary: Option<String>,
+    pub compensation: Option<AshbyCompensation>,
+    /// v2 only: other cities the job hires in, as `{"location": "London, UK"}`.
+    pub secondary_locations: Option<Vec<Value>>,
+}
+
+#[derive(Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AshbyCompensation {
+    pub compensation_tier_summary: Option<String>,
 }
 
 #[derive(Deserialize)]
@@ -264,7 +586,10 @@ pub struct WorkableJob {
     pub shortcode: String,
     pub title: String,
     pub city: Option<String>,
+    pub state: Option<String>,
     pub country: Option<String>,
+    pub remote: Option<bool>,
+    pub application_url: Option<String>,
     pub created_at: Option<String>,
     pub description: Option<String>,
     pub requirements: Option<String>,
@@ -290,6 +615,7 @@ pub struct RecruiteeJob {
     pub careers_url: String,
     pub description: Option<String>,
     pub location: Option<String>,
+    pub country: Option<String>,
     pub created_at: Option<String>,
     pub department: Option<String>,
 }
@@ -315,7 +641,9 @@ pub struct BreezyJob {
     #[serde(rename = "type")]
     pub employment_type: Option<BreezyType>,
     pub location: Option<BreezyLocation>,
-    pub department: Option<String>,
+    pub department: Option<FlexibleDepartment>,
+    /// A sub-group of `department` on some boards.
+    pub team: Option<String>,
     pub salary: Option<String>,
 }
 
@@ -344,3 +672,56 @@ pub struct BreezyLdJson {
 }
 
 
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_flexible_id_helpers() {
+        let ids: Vec<FlexibleId> = serde_json::from_str(r#"[4012345, "4012345", "3f9c2a1e-8b7d-4c6e-9f0a-1b2c3d4e5f60", ""]"#).unwrap();
+        assert_eq!(ids.iter().map(FlexibleId::as_number).collect::<Vec<_>>(), vec![Some(4012345), Some(4012345), None, None]);
+        assert_eq!(ids.iter().map(FlexibleId::is_numeric).collect::<Vec<_>>(), vec![true, true, false, false]);
+        assert_eq!(ids[0].as_string_ref(), "4012345");
+        assert_eq!(ids[2].as_string_ref(), "3f9c2a1e-8b7d-4c6e-9f0a-1b2c3d4e5f60");
+        assert!(matches!(ids[1].as_string_ref(), std::borrow::Cow::Borrowed(_)));
+        assert_eq!(ids[0].to_string(), ids[1].as_string_ref());
+    }
+
+    #[test]
+    fn test_ats_type_name_variants() {
+        for ats in AtsType::KNOWN {
+            let name = serde_json::to_value(ats).unwrap().as_str().unwrap().to_string();
+            let (head, tail) = name.split_at(name.len() / 2);
+            let variants = [
+                name.clone(),
+                name.to_uppercase(),
+                format!("{}{}", head.to_uppercase(), tail),
+                format!("{}{}", head, tail.to_uppercase()),
+                format!("{}_{}", head, tail),
+                format!("{}-{}", head.to_uppercase(), tail),
+                format!("{} {}", head, tail),
+                format!(" {} ", name),
+                name.chars().enumerate().map(|(i, c)| if i % 2 == 0 { c.to_ascii_uppercase() } else { c }).collect(),
+                format!("{:?}", ats),
+            ];
+            for variant in variants {
+                let parsed: AtsType = serde_json::from_value(Value::String(variant.clone())).unwrap();
+                assert_eq!(parsed, ats, "{:?}", variant);
+            }
+        }
+
+        for (misspelling, expected) in [
+            ("smart_recruiters", AtsType::SmartRecruiters),
+            ("smartrecruiter", AtsType::SmartRecruiters),
+            ("Smart Recruiter", AtsType::SmartRecruiters),
+            ("green_house", AtsType::Greenhouse),
+            ("workday", AtsType::Unknown),
+            ("", AtsType::Unknown),
+        ] {
+            assert_eq!(AtsType::from_name(misspelling), expected, "{:?}", misspelling);
+        }
+        // Still serialized in the stored spelling
+        assert_eq!(serde_json::to_string(&AtsType::SmartRecruiters).unwrap(), "\"smartrecruiters\"");
+    }
+}
@@ -0,0 +1,146 @@
+use anyhow::{Context, Result, bail};
+use regex::{Regex, RegexBuilder};
+use serde::Deserialize;
+
+/// Every value `DepartmentNormalizer` can produce.
+pub const DEPARTMENT_CATEGORIES: [&str; 11] = [
+    "engineering", "product", "design", "data", "marketing", "sales",
+    "finance", "hr", "legal", "operations", "other",
+];
+
+/// Checked in order, so the more specific groups come first: "Data Engineering"
+/// is data, "Product Design" is design, "Product Engineering" is engineering.
+const DEFAULT_RULES: [(&str, &str); 10] = [
+    (r"\bdata\b|\banalytics\b|\bmachine learning\b|\bml\b|\bai\b|\bbusiness intelligence\b", "data"),
+    (r"\bdesign\b|\bux\b|\bui\b|\bcreative\b|\buser research\b", "design"),
+    (r"\bhr\b|\bhuman resources\b|\bpeople\b|\btalent\b|\brecruit(ing|ment)\b", "hr"),
+    (r"\blegal\b|\bcompliance\b|\bcounsel\b|\bprivacy\b", "legal"),
+    (r"\bfinance\b|\bfinancial\b|\baccounting\b|\btreasury\b|\btax\b|\bfp&a\b", "finance"),
+    (r"\bmarketing\b|\bbrand\b|\bcommunications\b|\bgrowth\b|\bcontent\b", "marketing"),
+    (r"\bsales\b|\bbusiness development\b|\baccount management\b|\bpartnerships\b|\bcustomer success\b", "sales"),
+    (r"\bengineering\b|\bsoftware\b|\br&d\b|\bresearch (and|&) development\b|\btechnology\b|\bit\b|\binfrastructure\b|\bsecurity\b|\bplatform\b", "engineering"),
+    (r"\bproduct\b|\bprogram management\b", "product"),
+    (r"\boperations\b|\bops\b|\bsupport\b|\bcustomer service\b|\bsupply chain\b|\blogistics\b|\bfacilities\b", "operations"),
+];
+
+#[derive(Deserialize)]
+struct DepartmentRulesFile {
+    rules: Vec<DepartmentRuleDef>,
+}
+
+#[derive(Deserialize)]
+struct DepartmentRuleDef {
+    pattern: String,
+    category: String,
+}
+
+/// Maps the ATS's department names onto `DEPARTMENT_CATEGORIES`; anything no
+/// rule matches is "other".
+pub struct DepartmentNormalizer {
+    rules: Vec<(Regex, &'static str)>,
+}
+
+impl Default for DepartmentNormalizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl DepartmentNormalizer {
+    pub fn new() -> Self {
+        let rules = DEFAULT_RULES.iter()
+            .map(|(pattern, category)| (build(pattern).expect("Invalid department pattern"), *category))
+            .collect();
+        Self { rules }
+    }
+
+    /// Replaces the built-in rules, keeping their first-match-wins order:
+    ///
+    /// ```toml
+    /// [[rules]]
+    /// pattern = "\\bquant(itative)?\\b"
+    /// category = "data"
+    /// ```
+    pub fn from_file(path: &str) -> Result<Self> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read department rules file: {}", path))?;
+        Self::from_toml(&content)
+            .with_context(|| format!("Failed to load department rules from: {}", path))
+    }
+
+    pub fn from_toml(content: &str) -> Result<Self> {
+        let file: DepartmentRulesFile = toml::from_str(content)?;
+        let mut rules = Vec::with_capacity(file.rules.len());
+        for def in file.rules {
+            let Some(category) = DEPARTMENT_CATEGORIES.iter().find(|c| **c == def.category) else {
+                bail!("Unknown department category '{}' (expected one of {})", def.category, DEPARTMENT_CATEGORIES.join(", "));
+            };
+            let regex = build(&def.pattern).with_context(|| format!("Invalid pattern for category '{}'", def.category))?;
+            rules.push((regex, *category));
+        }
+        Ok(Self { rules })
+    }
+
+    /// `None` for a blank name, otherwise the first matching category.
+    pub fn normalize(&self, department: &str) -> Option<&'static str> {
+        let department = department.trim();
+        if department.is_empty() {
+            return None;
+        }
+        let category = self.rules.iter()
+            .find(|(regex, _)| regex.is_match(department))
+            .map_or("other", |(_, category)| *category);
+        Some(category)
+    }
+}
+
+fn build(pattern: &str) -> Result<Regex, regex::Error> {
+    RegexBuilder::new(pattern).case_insensitive(true).build()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_department_mapping() {
+        let normalizer = DepartmentNormalizer::new();
+        let cases = [
+            ("Engineering", "engineering"),
+            ("Software Engineering", "engineering"),
+            ("Product Engineering", "engineering"),
+            ("R&D", "engineering"),
+            ("Data Engineering", "data"),
+            ("Product Management", "product"),
+            ("Product Design", "design"),
+            ("Product Marketing", "marketing"),
+            ("Sales Engineering", "sales"),
+            ("People Operations", "hr"),
+            ("Finance & Accounting", "finance"),
+            ("Legal", "legal"),
+            ("Customer Support", "operations"),
+            ("Clinical Trials", "other"),
+        ];
+        for (raw, expected) in cases {
+            assert_eq!(normalizer.normalize(raw), Some(expected), "{}", raw);
+        }
+        assert_eq!(normalizer.normalize("  "), None);
+    }
+
+    #[test]
+    fn test_department_rules_from_toml() {
+        let normalizer = DepartmentNormalizer::from_toml(r#"
+            [[rules]]
+            pattern = "\\bquant(itative)?\\b"
+            category = "data"
+        "#).unwrap();
+        assert_eq!(normalizer.normalize("Quantitative Research"), Some("data"));
+        // Only the file's rules apply
+        assert_eq!(normalizer.normalize("Engineering"), Some("other"));
+
+        let Err(err) = DepartmentNormalizer::from_toml("[[rules]]\npattern = \"x\"\ncategory = \"research\"") else {
+            panic!("Unknown category accepted");
+        };
+        assert!(err.to_string().contains("Unknown department category 'research'"), "{}", err);
+    }
+}
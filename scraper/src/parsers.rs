@@ -1,8 +1,10 @@
 use serde_json::Value;
 use crate::models::*;
-use chrono::{DateTime, Utc, TimeZone};
-use log::debug;
-use ammonia;
+use crate::location::normalize_country_display;
+use chrono::{DateTime, Duration, NaiveDate, NaiveDateTime, TimeDelta, Utc, TimeZone};
+use once_cell::sync::Lazy;
+use regex::Regex;
+use tracing::debug;
 use anyhow::{Result, Context};
 
 // --- Parsing Trait ---
@@ -10,19 +12,124 @@ use anyhow::{Result, Context};
 pub trait AtsParser {
     fn parse(&self, company: &CompanyEntry, data: &Value) -> Result<Vec<Job>>;
     fn estimate_raw_item_count(&self, data: &Value) -> usize;
+    /// Checks the response has the fields `parse` relies on, so schema drift
+    /// shows up as a warning rather than an empty board or a parse error.
+    fn validate_schema(&self, data: &Value) -> Vec<SchemaWarning>;
 }
 
-fn normalize_date(date_str: &str) -> String {
-    if date_str.is_empty() { return String::new(); }
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Severity {
+    /// `parse` fails or drops jobs without it.
+    Critical,
+    /// Jobs parse but lose data, e.g. no location or posted date.
+    Warning,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SchemaWarning {
+    pub field: String,
+    pub message: String,
+    pub severity: Severity,
+}
+
+/// Where an ATS keeps its job list and which keys each job needs. `a|b`
+/// accepts either key, for fields parsed through a serde alias.
+struct JobSchema {
+    /// `None` for a top-level array.
+    list: Option<&'static str>,
+    required: &'static [&'static str],
+    expected: &'static [&'static str],
+}
+
+impl JobSchema {
+    fn validate(&self, data: &Value) -> Vec<SchemaWarning> {
+        let list = match self.list {
+            Some(key) => data.get(key),
+            None => Some(data),
+        };
+        let Some(items) = list.and_then(Value::as_array) else {
+            let field = self.list.unwrap_or("(root)");
+            return vec![SchemaWarning {
+                field: field.to_string(),
+                message: format!("expected an array of jobs, found {}", json_type(list.unwrap_or(&Value::Null))),
+                severity: Severity::Critical,
+            }];
+        };
+
+        let fields = self.required.iter().map(|f| (f, Severity::Critical))
+            .chain(self.expected.iter().map(|f| (f, Severity::Warning)));
+        fields.filter_map(|(field, severity)| {
+            let missing = items.iter()
+                .filter(|item| field.split('|').all(|key| item.get(key).is_none_or(Value::is_null)))
+                .count();
+            (missing > 0).then(|| SchemaWarning {
+                field: field.to_string(),
+                message: format!("missing on {} of {} jobs", missing, items.len()),
+                severity,
+            })
+        }).collect()
+    }
+}
+
+fn json_type(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "nothing",
+        Value::Bool(_) => "a boolean",
+        Value::Number(_) => "a number",
+        Value::String(_) => "a string",
+        Value::Array(_) => "an array",
+        Value::Object(_) => "an object",
+    }
+}
+
+static RELATIVE_DATE_REGEX: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)^(?:posted\s+)?(?:(\d+)\s+(day|week|month|year)s?\s+ago|(today|yesterday))$").unwrap()
+});
+
+/// "2 days ago", "Posted today", "3 weeks ago" relative to `now`.
+/// Months and years are approximated as 30 and 365 days.
+fn parse_relative_date(date_str: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    let caps = RELATIVE_DATE_REGEX.captures(date_str.trim())?;
+    if let Some(word) = caps.get(3) {
+        let days = if word.as_str().eq_ignore_ascii_case("yesterday") { 1 } else { 0 };
+        return Some(now - Duration::days(days));
+    }
+    let n: i64 = caps[1].parse().ok()?;
+    let unit_days = match caps[2].to_lowercase().as_str() {
+        "day" => 1,
+        "week" => 7,
+        "month" => 30,
+        _ => 365,
+    };
+    // "300000 years ago" is out of range for chrono rather than a date
+    now.checked_sub_signed(TimeDelta::try_days(n.checked_mul(unit_days)?)?)
+}
+
+fn normalize_date(date_str: &str) -> Option<DateTime<Utc>> {
+    normalize_date_at(date_str, Utc::now())
+}
+
+fn normalize_date_at(date_str: &str, now: DateTime<Utc>) -> Option<DateTime<Utc>> {
+    if date_str.is_empty() { return None; }
     
     // Try to parse as ISO 8601 (e.g., 2024-01-01T12:00:00Z)
     if let Ok(dt) = DateTime::parse_from_rfc3339(date_str) {
-        return dt.with_timezone(&Utc).to_rfc3339();
+        return Some(dt.with_timezone(&Utc));
     }
 
     // Try RFC 2822 (e.g., Mon, 02 Jan 2006 15:04:05 -0700)
     if let Ok(dt) = DateTime::parse_from_rfc2822(date_str) {
-        return dt.with_timezone(&Utc).to_rfc3339();
+        return Some(dt.with_timezone(&Utc));
+    }
+
+    // Recruitee (e.g., 2025-02-07 09:15:00 UTC)
+    if let Ok(dt) = NaiveDateTime::parse_from_str(date_str, "%Y-%m-%d %H:%M:%S UTC") {
+        return Some(dt.and_utc());
+    }
+
+    // Date only, as midnight UTC (e.g., Workable's 2025-02-01)
+    if let Ok(date) = NaiveDate::parse_from_str(date_str, "%Y-%m-%d") {
+        return date.and_hms_opt(0, 0, 0).map(|dt| dt.and_utc());
     }
     
     // Try to parse as Unix timestamp (seconds or milliseconds)
@@ -32,15 +139,64 @@ fn normalize_date(date_str: &str) -> String {
         } else {
             Utc.timestamp_opt(ts, 0).single()
         };
-        if let Some(dt) = dt {
-            return dt.to_rfc3339();
+        if dt.is_some() {
+            return dt;
         }
     }
 
-    date_str.to_string()
+    let dt = parse_relative_date(date_str, now);
+    if dt.is_none() {
+        debug!("Unrecognized date format: {:?}", date_str);
+    }
+    dt
 }
 
-pub(crate) fn clean_html(html: &str) -> String {
+/// Follows a dot-separated path ("data.jobs", "jobs.0.items"); numeric
+/// segments index into arrays. An empty path returns `data` itself.
+fn json_path<'a>(data: &'a Value, path: &str) -> Option<&'a Value> {
+    path.split('.')
+        .filter(|seg| !seg.is_empty())
+        .try_fold(data, |value, seg| match value {
+            Value::Array(items) => seg.parse::<usize>().ok().and_then(|i| items.get(i)),
+            _ => value.get(seg),
+        })
+}
+
+/// "City, Region, Country" from whichever parts the ATS filled in.
+fn join_location(parts: &[Option<&str>]) -> String {
+    parts.iter().flatten().map(|p| p.trim()).filter(|p| !p.is_empty()).collect::<Vec<_>>().join(", ")
+}
+
+/// Keeps an ATS apply link only when it adds something over the listing URL.
+fn distinct_apply_url(apply_url: Option<String>, url: &str) -> Option<String> {
+    apply_url.filter(|a| !a.is_empty() && a != url)
+}
+
+/// Ashby's PascalCase employment types, spelled the way other boards tag them.
+fn ashby_employment_type(raw: &str) -> &str {
+    match raw {
+        "FullTime" => "Full-time",
+        "PartTime" => "Part-time",
+        "Intern" => "Internship",
+        other => other,
+    }
+}
+
+/// Maps freeform values like "Full-time", "Part Time" or "Contractor" onto
+/// `EmploymentType`, ignoring case, spaces and punctuation.
+pub fn normalize_employment_type(raw: &str) -> Option<EmploymentType> {
+    let key: String = raw.chars().filter(|c| c.is_alphanumeric()).flat_map(char::to_lowercase).collect();
+    match key.as_str() {
+        "fulltime" | "permanent" => Some(EmploymentType::FullTime),
+        "parttime" => Some(EmploymentType::PartTime),
+        "contract" | "contractor" | "temporary" | "freelance" => Some(EmploymentType::Contract),
+        "internship" | "intern" => Some(EmploymentType::Internship),
+        "coop" => Some(EmploymentType::CoOp),
+        _ => None,
+    }
+}
+
+pub fn clean_html(html: &str) -> String {
     if html.is_empty() { return String::new(); }
     
     // Decode common entities if it looks double-escaped
@@ -58,6 +214,82 @@ pub(crate) fn clean_html(html: &str) -> String {
     ammonia::clean(&decoded)
 }
 
+/// Shortens sanitized HTML to at most `max_chars` characters of markup. Cuts
+/// at the last sentence end, else the last word boundary with "..." appended
+/// (either only if it keeps at least half), and never inside a tag or entity.
+/// Tags left open are closed again, which can add a few characters past the
+/// limit. `None` when the HTML already fits.
+pub fn truncate_html(html: &str, max_chars: usize) -> Option<String> {
+    if html.chars().count() <= max_chars {
+        return None;
+    }
+
+    let (mut in_tag, mut in_entity) = (false, false);
+    // Cut points as (byte index, chars before it)
+    let (mut sentence_end, mut word_end, mut safe_end) = (None, None, 0);
+    let mut prev = ' ';
+    for (count, (i, c)) in html.char_indices().enumerate() {
+        if count >= max_chars {
+            break;
+        }
+        let outside = !in_tag && !in_entity;
+        if outside && (c.is_whitespace() || c == '<') {
+            if matches!(prev, '.' | '!' | '?') {
+                sentence_end = Some((i, count));
+            }
+            word_end = Some((i, count));
+        }
+        if outside {
+            safe_end = i;
+        }
+        match c {
+            '<' => in_tag = true,
+            '>' if in_tag => in_tag = false,
+            '&' if !in_tag => in_entity = true,
+            ';' if in_entity => in_entity = false,
+            _ => {}
+        }
+        prev = c;
+    }
+
+    let keeps_half = |&(_, count): &(usize, usize)| count >= max_chars / 2;
+    let truncated = match (sentence_end.filter(keeps_half), word_end.filter(keeps_half)) {
+        (Some((cut, _)), _) => html[..cut].trim_end().to_string(),
+        (None, Some((cut, _))) => format!("{}...", html[..cut].trim_end()),
+        (None, None) => format!("{}...", html[..safe_end].trim_end()),
+    };
+    // ammonia closes whatever the cut left open
+    Some(ammonia::clean(&truncated))
+}
+
+static HTML_HEADING: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<h[23][^>]*>(.*?)</h[23]\s*>").unwrap());
+static HTML_LINE_BREAK: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)<br\s*/?>|</?p(\s[^>]*)?>|</?[uo]l(\s[^>]*)?>|</?div(\s[^>]*)?>|</h[1-6]\s*>").unwrap());
+static HTML_LIST_ITEM: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)<li(\s[^>]*)?>").unwrap());
+static HTML_ANY_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+static BLANK_LINES: Lazy<Regex> = Lazy::new(|| Regex::new(r"\n{3,}").unwrap());
+
+/// Plain text for search indexing and NLP: `<h2>`/`<h3>` become "## Heading",
+/// list items become "- item", and `<br>`, `<p>` and block ends become line
+/// breaks. Everything else is stripped and runs of blank lines collapse to one.
+pub fn clean_html_to_text(html: &str) -> String {
+    // Sanitize first so scripts and styles are gone and entities are consistent
+    let html = clean_html(html);
+    let text = HTML_HEADING.replace_all(&html, "\n\n## $1\n\n");
+    let text = HTML_LIST_ITEM.replace_all(&text, "\n- ");
+    let text = HTML_LINE_BREAK.replace_all(&text, "\n");
+    let text = HTML_ANY_TAG.replace_all(&text, "");
+    let text = text
+        .replace("&nbsp;", " ")
+        .replace("&lt;", "<")
+        .replace("&gt;", ">")
+        .replace("&quot;", "\"")
+        .replace("&#39;", "'")
+        .replace("&amp;", "&");
+
+    let lines: Vec<&str> = text.lines().map(str::trim).collect();
+    BLANK_LINES.replace_all(&lines.join("\n"), "\n\n").trim().to_string()
+}
+
 impl AtsParser for AtsType {
     fn parse(&self, company: &CompanyEntry, data: &Value) -> Result<Vec<Job>> {
         match self {
@@ -68,7 +300,7 @@ impl AtsParser for AtsType {
             AtsType::Workable => self.parse_workable(company, data),
             AtsType::Recruitee => self.parse_recruitee(company, data),
             AtsType::Breezy => self.parse_breezy(company, data),
-            _ => Ok(vec![]),
+            AtsType::Unknown => self.parse_custom(company, data),
         }
     }
 
@@ -79,6 +311,50 @@ impl AtsParser for AtsType {
             _ => 0,
         }
     }
+
+    fn validate_schema(&self, data: &Value) -> Vec<SchemaWarning> {
+        let schema = match self {
+            // Boards return `{"jobs": [...]}`, single-job endpoints a bare array
+            AtsType::Greenhouse => JobSchema {
+                list: if data.is_array() { None } else { Some("jobs") },
+                required: &["id", "title", "absolute_url|url"],
+                expected: &["location", "updated_at|posted"],
+            },
+            AtsType::Lever => JobSchema {
+                list: None,
+                required: &["id", "text", "hostedUrl|hosted_url", "categories"],
+                expected: &["createdAt"],
+            },
+            AtsType::SmartRecruiters => JobSchema {
+                list: Some("content"),
+                required: &["id", "name", "location"],
+                expected: &["releasedDate"],
+            },
+            AtsType::Ashby => JobSchema {
+                list: Some("jobs"),
+                required: &["id", "title", "jobUrl"],
+                expected: &["location", "publishedAt"],
+            },
+            AtsType::Workable => JobSchema {
+                list: Some("jobs"),
+                required: &["shortcode", "title"],
+                expected: &["created_at"],
+            },
+            AtsType::Recruitee => JobSchema {
+                list: Some("offers"),
+                required: &["id", "title", "careers_url"],
+                expected: &["location", "created_at"],
+            },
+            AtsType::Breezy => JobSchema {
+                list: None,
+                required: &["id", "name"],
+                expected: &["location", "published_date"],
+            },
+            // The shape comes from the company's `custom_json_path` and `field_map`
+            AtsType::Unknown => return Vec::new(),
+        };
+        schema.validate(data)
+    }
 }
 
 impl AtsType {
@@ -86,24 +362,44 @@ impl AtsType {
         let ats_str = serde_json::to_string(self).unwrap_or_default().trim_matches('"').to_lowercase();
         Job {
             id: format!("{}-{}", ats_str, id),
+            ats_job_id: id,
             title,
             description: String::new(),
+            description_length: 0,
+            description_quality_score: 0.0,
+            text_description: None,
+            description_hash: String::new(),
             company: company.name.clone(),
             slug: company.slug.clone(),
             ats: *self,
             url,
+            apply_url: None,
+            source_url: match &company.custom_api_url {
+                Some(custom) if company.ats_type == AtsType::Unknown => custom.clone(),
+                _ => company.api_url.clone(),
+            },
             company_url: company.domain.clone(),
+            company_industry: None,
+            company_stage: None,
+            logo_url: company.logo_url.clone(),
             location: String::new(),
+            location_name: None,
             city: None,
             region: None,
             country: None,
             country_code: None,
-            posted: String::new(),
+            metro_area: None,
+            preferred_timezone: None,
+            posted: None,
+            seniority_level: Default::default(),
+            employment_type: None,
             departments: vec![],
+            normalized_department: None,
             offices: vec![],
             tags: vec![],
             degree_levels: vec![],
             subject_areas: vec![],
+            benefits: vec![],
         }
     }
 
@@ -128,7 +424,7 @@ impl AtsType {
         };
 
         Ok(raw_jobs.into_iter().map(|rj| {
-            let is_edu_optional = self.is_greenhouse_education_optional(&rj);
+            let edu_tag = self.greenhouse_education_tag(&rj);
             let mut job = self.new_job(company, rj.id.to_string(), rj.title, rj.url);
             
             job.description = rj.description.as_ref().map(|d| clean_html(d.as_str())).unwrap_or_default();
@@ -138,15 +434,26 @@ impl AtsType {
             job.location = match &rj.location {
                 Some(Value::String(s)) => s.clone(),
                 Some(Value::Object(map)) => {
-                    map.get("name").and_then(|v| v.as_str()).map(String::from)
-                        .or_else(|| map.get("city").and_then(|v| v.as_str()).map(String::from)) // Fallback to city
-                        .unwrap_or_else(|| "Unknown".to_string())
+                    let field = |key: &str| map.get(key).and_then(|v| v.as_str()).map(str::trim).filter(|v| !v.is_empty());
+                    let (city, state, country) = (field("city"), field("state"), field("country"));
+                    // The structured form skips `LocationEngine::resolve` when all three are there
+                    job.city = city.map(String::from);
+                    job.region = state.map(String::from);
+                    job.country = country.map(normalize_country_display);
+                    job.country_code = country.filter(|c| c.len() == 2).map(str::to_uppercase);
+                    job.location_name = field("name").map(String::from);
+                    let joined = join_location(&[city, state, job.country.as_deref()]);
+                    if !joined.is_empty() {
+                        joined
+                    } else {
+                        field("name").unwrap_or("Unknown").to_string()
+                    }
                 },
                 _ => String::new(),
             };
 
-            if is_edu_optional {
-                job.tags.push("Education Optional".to_string());
+            if let Some(tag) = edu_tag {
+                job.tags.push(tag.to_string());
             }
 
             job.departments = rj.departments.into_iter().filter_map(|d| d.name).collect();
@@ -166,27 +473,50 @@ impl AtsType {
         }
     }
 
-    fn is_greenhouse_education_optional(&self, rj: &RawGreenhouseJob) -> bool {
-        const EDU_OPTIONAL: &str = "education_optional";
+    /// The structured requirement, from `education` or an "Education" metadata field.
+    fn greenhouse_education_value<'a>(&self, rj: &'a RawGreenhouseJob) -> Option<&'a str> {
         const EDU_FIELD: &str = "Education";
-        
-        let is_optional = |v: &str| v == EDU_OPTIONAL;
-
-        rj.education.as_ref().map_or(false, |e| match e {
-            GreenhouseEducation::Object { value } => is_optional(value),
-            GreenhouseEducation::String(s) => is_optional(s),
-        }) || rj.metadata.as_ref().map_or(false, |m| {
-            m.iter().any(|item| {
+
+        let direct = rj.education.as_ref().map(|e| match e {
+            GreenhouseEducation::Object { value } => value.as_str(),
+            GreenhouseEducation::String(s) => s.as_str(),
+        });
+        direct.or_else(|| {
+            rj.metadata.as_ref()?.iter().find_map(|item| {
                 let name = item.name.as_deref().or(item.label.as_deref());
-                if name == Some(EDU_FIELD) {
-                    return item.value.as_str().map_or(false, is_optional) ||
-                           item.value.get("value").and_then(|v| v.as_str()).map_or(false, is_optional);
+                if name != Some(EDU_FIELD) {
+                    return None;
                 }
-                false
+                item.value.as_str().or_else(|| item.value.get("value").and_then(|v| v.as_str()))
             })
         })
     }
 
+    fn is_greenhouse_education_optional(&self, rj: &RawGreenhouseJob) -> bool {
+        self.greenhouse_education_value(rj) == Some("education_optional")
+    }
+
+    fn is_greenhouse_education_required(&self, rj: &RawGreenhouseJob) -> bool {
+        self.greenhouse_education_value(rj) == Some("education_required")
+    }
+
+    fn is_greenhouse_education_none(&self, rj: &RawGreenhouseJob) -> bool {
+        self.greenhouse_education_value(rj) == Some("education_none")
+    }
+
+    /// At most one of the three education tags; they come from a single value.
+    fn greenhouse_education_tag(&self, rj: &RawGreenhouseJob) -> Option<&'static str> {
+        if self.is_greenhouse_education_optional(rj) {
+            Some("Education Optional")
+        } else if self.is_greenhouse_education_required(rj) {
+            Some("Degree Required")
+        } else if self.is_greenhouse_education_none(rj) {
+            Some("Education Not Required")
+        } else {
+            None
+        }
+    }
+
     fn parse_lever(&self, company: &CompanyEntry, data: &Value) -> Result<Vec<Job>> {
         let items: Vec<LeverJob> = match serde_json::from_value(data.clone()) {
             Ok(j) => j,
@@ -195,7 +525,16 @@ impl AtsType {
 
         Ok(items.into_iter().map(|j| {
             let mut job = self.new_job(company, j.id, j.text, j.hosted_url);
-            job.description = clean_html(&j.description.unwrap_or_default());
+            job.apply_url = distinct_apply_url(j.apply_url, &job.url);
+            let mut desc = j.description.unwrap_or_default();
+            for list in &j.lists {
+                desc.push_str(&format!("<h3>{}</h3><ul>{}</ul>", list.text, list.content.to_html()));
+            }
+            if let Some(additional) = j.additional.filter(|a| !a.trim().is_empty()) {
+                desc.push_str("<hr>");
+                desc.push_str(&additional);
+            }
+            job.description = clean_html(&desc);
             job.location = j.categories.location.unwrap_or_default();
             job.posted = normalize_date(&j.created_at.map(|c| c.to_string()).unwrap_or_default());
             
@@ -203,6 +542,8 @@ impl AtsType {
             if !dept.is_empty() { job.departments.push(dept); }
 
             if let Some(commitment) = j.categories.commitment {
+                job.employment_type = normalize_employment_type(&commitment);
+                // The raw value stays a tag for existing filters
                 if !commitment.is_empty() { job.tags.push(commitment); }
             }
 
@@ -216,19 +557,28 @@ impl AtsType {
         Ok(resp.content.into_iter().map(|j| {
             let url = j.posting_url.unwrap_or_else(|| format!("https://jobs.smartrecruiters.com/{}/{}", company.slug, j.id));
             let mut job = self.new_job(company, j.id.clone(), j.name, url);
+            job.apply_url = distinct_apply_url(j.apply_url, &job.url);
+            job.logo_url = job.logo_url.or(j.company.and_then(|c| c.logo).filter(|l| !l.is_empty()));
             
             // Build location string
             let loc = &j.location;
-            let mut loc_parts = Vec::new();
-            if let Some(city) = &loc.city { if !city.is_empty() { loc_parts.push(city.as_str()); } }
-            if let Some(region) = &loc.region { if !region.is_empty() { loc_parts.push(region.as_str()); } }
-            if let Some(country) = &loc.country { if !country.is_empty() { loc_parts.push(country.as_str()); } }
+            // Usually a lowercase ISO code ("nz")
+            job.country = loc.country.as_deref().filter(|c| !c.is_empty()).map(normalize_country_display);
+            let joined = join_location(&[loc.city.as_deref(), loc.region.as_deref(), job.country.as_deref()]);
             
-            job.location = if loc_parts.is_empty() {
+            job.location = if joined.is_empty() && loc.remote == Some(true) {
+                "Remote".to_string()
+            } else if joined.is_empty() {
                 loc.full_location.clone().unwrap_or_default()
             } else {
-                loc_parts.join(", ")
+                joined
             };
+            if loc.remote == Some(true) {
+                job.tags.push("Remote".to_string());
+            }
+            if loc.hybrid == Some(true) {
+                job.tags.push("Hybrid".to_string());
+            }
             
             job.posted = normalize_date(&j.released_date.unwrap_or_default());
             
@@ -261,8 +611,11 @@ impl AtsType {
             Ok(r) => r,
             Err(e) => return Err(anyhow::anyhow!("Ashby parsing failed for {}: {}", company.name, e)),
         };
+        let board_logo = resp.job_board.and_then(|b| b.logo).filter(|l| !l.is_empty());
         Ok(resp.jobs.into_iter().map(|j| {
             let mut job = self.new_job(company, j.id, j.title, j.job_url);
+            job.logo_url = job.logo_url.or_else(|| board_logo.clone());
+            job.apply_url = distinct_apply_url(j.apply_url, &job.url);
             job.location = match &j.location {
                  Some(Value::String(s)) => s.clone(),
                  Some(Value::Object(map)) => {
@@ -282,6 +635,30 @@ impl AtsType {
             if let Some(dept) = j.department {
                 job.departments.push(dept);
             }
+
+            // Extra cities go to `offices`, which normalize_job resolves like Greenhouse's
+            for secondary in j.secondary_locations.iter().flatten() {
+                let name = match secondary {
+                    Value::String(s) => Some(s.as_str()),
+                    Value::Object(map) => map.get("location").or_else(|| map.get("name")).and_then(Value::as_str),
+                    _ => None,
+                };
+                if let Some(name) = name.map(str::trim).filter(|n| !n.is_empty() && *n != job.location) {
+                    job.offices.push(name.to_string());
+                }
+            }
+
+            if j.is_remote == Some(true) {
+                job.tags.push("Remote".to_string());
+            }
+            if let Some(emp_type) = j.employment_type.as_deref().map(ashby_employment_type) {
+                job.tags.push(emp_type.to_string());
+            }
+            let compensation = j.compensation.and_then(|c| c.compensation_tier_summary)
+                .or(j.compensation_tier_summary);
+            if let Some(salary) = compensation {
+                if !salary.is_empty() { job.tags.push(format!("Salary: {}", salary)); }
+            }
             job
         }).collect())
     }
@@ -292,7 +669,21 @@ impl AtsType {
         Ok(resp.jobs.into_iter().map(|j| {
             let url = format!("https://apply.workable.com/{}/j/{}/", company.slug, j.shortcode);
             let mut job = self.new_job(company, j.shortcode.clone(), j.title, url);
-            job.location = format!("{}, {}", j.city.unwrap_or_default(), j.country.unwrap_or_default());
+            job.apply_url = distinct_apply_url(j.application_url, &job.url);
+
+            job.country = j.country.as_deref().filter(|c| !c.is_empty()).map(normalize_country_display);
+            let mut loc_parts = Vec::new();
+            if let Some(city) = &j.city { if !city.is_empty() { loc_parts.push(city.as_str()); } }
+            if let Some(state) = &j.state { if !state.is_empty() { loc_parts.push(state.as_str()); } }
+            if let Some(country) = &job.country { loc_parts.push(country.as_str()); }
+            job.location = loc_parts.join(", ");
+
+            if j.remote == Some(true) {
+                if job.location.is_empty() {
+                    job.location = "Remote".to_string();
+                }
+                job.tags.push("Remote".to_string());
+            }
             job.posted = normalize_date(&j.created_at.unwrap_or_default());
             
             // Build description from v2 API fields
@@ -322,6 +713,7 @@ impl AtsType {
             let mut job = self.new_job(company, j.id.to_string(), j.title, j.careers_url);
             job.description = clean_html(&j.description.unwrap_or_default());
             job.location = j.location.unwrap_or_default();
+            job.country = j.country.as_deref().filter(|c| !c.is_empty()).map(normalize_country_display);
             job.posted = normalize_date(&j.created_at.unwrap_or_default());
             if let Some(dept) = j.department {
                 job.departments.push(dept);
@@ -330,6 +722,45 @@ impl AtsType {
         }).collect())
     }
 
+    fn parse_custom(&self, company: &CompanyEntry, data: &Value) -> Result<Vec<Job>> {
+        let path = company.custom_json_path.as_deref().unwrap_or("");
+        let items = json_path(data, path)
+            .and_then(|v| v.as_array())
+            .context(format!("Custom board for {}: no array at '{}'", company.name, path))?;
+
+        // Job field -> key in the response item, overridable per company
+        let key = |field: &'static str| -> &str {
+            company.field_map.as_ref()
+                .and_then(|m| m.get(field))
+                .map(String::as_str)
+                .unwrap_or(field)
+        };
+        let text = |item: &Value, field: &'static str| -> Option<String> {
+            match json_path(item, key(field))? {
+                Value::String(s) if !s.is_empty() => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }
+        };
+
+        Ok(items.iter().filter_map(|item| {
+            let (Some(id), Some(title), Some(url)) = (text(item, "id"), text(item, "title"), text(item, "url")) else {
+                debug!("Skipping custom job for {}: missing id, title or url", company.name);
+                return None;
+            };
+            // Namespace by slug since every custom board shares the "unknown" prefix
+            let mut job = self.new_job(company, format!("{}-{}", company.slug, id), title, url);
+            job.ats_job_id = id;
+            job.description = clean_html(&text(item, "description").unwrap_or_default());
+            job.location = text(item, "location").unwrap_or_default();
+            job.posted = normalize_date(&text(item, "posted").unwrap_or_default());
+            if let Some(dept) = text(item, "department") {
+                job.departments.push(dept);
+            }
+            Some(job)
+        }).collect())
+    }
+
     fn parse_breezy(&self, company: &CompanyEntry, data: &Value) -> Result<Vec<Job>> {
         let items: Vec<BreezyJob> = serde_json::from_value(data.clone())
             .context(format!("Breezy parsing failed for {}", company.name))?;
@@ -339,11 +770,10 @@ impl AtsType {
             
             // Build location string
             if let Some(loc) = &j.location {
+                job.country = loc.country.as_ref().and_then(|c| c.name.as_deref()).filter(|c| !c.is_empty()).map(normalize_country_display);
                 let mut loc_parts = Vec::new();
                 if let Some(name) = &loc.name { if !name.is_empty() { loc_parts.push(name.as_str()); } }
-                if let Some(country) = &loc.country.as_ref().and_then(|c| c.name.as_ref()) {
-                    if !country.is_empty() { loc_parts.push(country.as_str()); }
-                }
+                if let Some(country) = &job.country { loc_parts.push(country.as_str()); }
                 job.location = loc_parts.join(", ");
 
                 // Tag remote
@@ -359,8 +789,10 @@ impl AtsType {
 
             job.posted = normalize_date(&j.published_date.unwrap_or_default());
             
-            if let Some(dept) = j.department {
-                if !dept.is_empty() { job.departments.push(dept); }
+            // Department first so it's the one `normalized_department` is taken from
+            let dept = j.department.map(|d| d.to_string());
+            for name in [dept, j.team].into_iter().flatten() {
+                if !name.is_empty() && !job.departments.contains(&name) { job.departments.push(name); }
             }
 
             if let Some(emp_type) = j.employment_type.and_then(|t| t.name) {
@@ -379,6 +811,151 @@ impl AtsType {
 mod tests {
     use super::*;
     use serde_json::json;
+    use std::collections::HashMap;
+
+    #[test]
+    fn test_clean_html_to_text() {
+        assert_eq!(clean_html_to_text(""), "");
+        assert_eq!(clean_html_to_text("<p>One</p><p>Two</p>"), "One\n\nTwo");
+        assert_eq!(clean_html_to_text("Line one<br>Line two<br/>Line three"), "Line one\nLine two\nLine three");
+        assert_eq!(clean_html_to_text("<h2>About us</h2><p>We build.</p><h3 class=\"x\">Perks</h3>"), "## About us\n\nWe build.\n\n## Perks");
+        assert_eq!(clean_html_to_text("<ul><li>Rust</li><li>Go <b>(bonus)</b></li></ul>"), "- Rust\n- Go (bonus)");
+        // Entities are decoded and script contents dropped
+        assert_eq!(clean_html_to_text("<p>R&amp;D &quot;team&quot;</p><script>alert(1)</script>"), "R&D \"team\"");
+        // Blank lines collapse to one
+        assert_eq!(clean_html_to_text("<p>A</p><p></p><p>&nbsp;</p><div><p>B</p></div>"), "A\n\nB");
+    }
+
+    #[test]
+    fn test_truncate_html() {
+        assert_eq!(truncate_html("<p>Short.</p>", 100), None);
+        // Word boundary, and the open paragraph is closed again
+        assert_eq!(truncate_html("<p>Build reliable systems today</p>", 24).as_deref(), Some("<p>Build reliable...</p>"));
+        // A sentence end that keeps at least half the text wins over a later
+        // word, and needs no ellipsis
+        assert_eq!(
+            truncate_html("<p>First sentence here. Second sentence is longer</p>", 40).as_deref(),
+            Some("<p>First sentence here.</p>")
+        );
+        // Half is measured in characters, not bytes
+        assert_eq!(truncate_html("<p>éééééé xxxxxxxxxxxxxxxxxxxx</p>", 20).as_deref(), Some("<p>éééééé xxxxxxxxx...</p>"));
+        // Never inside a tag or an entity
+        let cut = truncate_html("<ul><li>Rust</li><li><a href=\"https://example.com/very/long\">Docs</a></li></ul>", 40).unwrap();
+        assert_eq!(cut, "<ul><li>Rust</li><li>...</li></ul>");
+        let cut = truncate_html("<p>R&amp;D&amp;Ops&amp;More</p>", 12).unwrap();
+        assert_eq!(cut, "<p>R&amp;D...</p>");
+    }
+
+    #[test]
+    fn test_json_path() {
+        let data = json!({"jobs": [{"items": [1, 2]}], "meta": {"count": 2}});
+        assert_eq!(json_path(&data, "jobs.0.items"), Some(&json!([1, 2])));
+        assert_eq!(json_path(&data, "meta.count"), Some(&json!(2)));
+        assert_eq!(json_path(&data, ""), Some(&data));
+        assert_eq!(json_path(&data, "jobs.1"), None);
+        assert_eq!(json_path(&data, "missing.path"), None);
+    }
+
+    #[test]
+    fn test_parse_custom() {
+        let company = CompanyEntry {
+            name: "Niche Co".to_string(),
+            ats_type: AtsType::Unknown,
+            slug: "nicheco".to_string(),
+            custom_json_path: Some("data.0.openings".to_string()),
+            field_map: Some(HashMap::from([
+                ("url".to_string(), "links.apply".to_string()),
+                ("posted".to_string(), "created".to_string()),
+            ])),
+            ..Default::default()
+        };
+
+        let data = json!({
+            "data": [{
+                "openings": [
+                    {"id": 17, "title": "Data Intern", "links": {"apply": "https://niche.co/jobs/17"},
+                     "location": "Berlin, Germany", "created": "2025-03-01T00:00:00Z", "description": "<p>Hi</p>"},
+                    {"id": 18, "title": "No Link Intern"}
+                ]
+            }]
+        });
+
+        let jobs = AtsType::Unknown.parse(&company, &data).unwrap();
+        assert_eq!(jobs.len(), 1);
+        let job = &jobs[0];
+        assert_eq!(job.id, "unknown-nicheco-17");
+        assert_eq!(job.ats_job_id, "17");
+        assert_eq!(job.title, "Data Intern");
+        assert_eq!(job.url, "https://niche.co/jobs/17");
+        assert_eq!(job.location, "Berlin, Germany");
+        assert_eq!(job.posted.map(format_date).as_deref(), Some("2025-03-01T00:00:00+00:00"));
+        assert_eq!(job.description, "<p>Hi</p>");
+
+        let wrong_path = CompanyEntry { custom_json_path: Some("jobs".to_string()), ..company };
+        assert!(AtsType::Unknown.parse(&wrong_path, &data).is_err());
+    }
+
+    #[test]
+    fn test_parse_greenhouse_location_forms() {
+        let company = CompanyEntry { name: "Acme".to_string(), slug: "acme".to_string(), ..Default::default() };
+        let data = json!({"jobs": [
+            {"id": 1, "title": "A", "absolute_url": "https://x/1", "location": {"name": "New York City"}},
+            {"id": 2, "title": "B", "absolute_url": "https://x/2",
+             "location": {"name": "New York City (Remote)", "city": "New York", "state": "NY", "country": "us"}},
+            {"id": 3, "title": "C", "absolute_url": "https://x/3",
+             "location": {"name": "Europe", "city": "", "country": "Germany"}},
+            {"id": 4, "title": "D", "absolute_url": "https://x/4", "location": "Remote"}
+        ]});
+
+        let jobs = AtsType::Greenhouse.parse(&company, &data).unwrap();
+        assert_eq!(jobs[0].location, "New York City");
+        assert!(jobs[0].city.is_none() && jobs[0].country_code.is_none());
+
+        assert_eq!(jobs[1].location, "New York, NY, United States");
+        assert_eq!(jobs[1].city.as_deref(), Some("New York"));
+        assert_eq!(jobs[1].region.as_deref(), Some("NY"));
+        assert_eq!(jobs[1].country.as_deref(), Some("United States"));
+        assert_eq!(jobs[1].country_code.as_deref(), Some("US"));
+        // Kept for the work mode `resolve_structured` reads from it
+        assert_eq!(jobs[1].location_name.as_deref(), Some("New York City (Remote)"));
+
+        // A country name is kept for display but isn't a code
+        assert_eq!(jobs[2].location, "Germany");
+        assert_eq!(jobs[2].country.as_deref(), Some("Germany"));
+        assert!(jobs[2].city.is_none() && jobs[2].country_code.is_none());
+
+        assert_eq!(jobs[3].location, "Remote");
+    }
+
+    #[test]
+    fn test_greenhouse_education_tags() {
+        let company = CompanyEntry { name: "Acme".to_string(), slug: "acme".to_string(), ..Default::default() };
+        let data = json!({"jobs": [
+            {"id": 1, "title": "A", "absolute_url": "https://x/1", "education": "education_optional"},
+            {"id": 2, "title": "B", "absolute_url": "https://x/2", "education": {"value": "education_required"}},
+            {"id": 3, "title": "C", "absolute_url": "https://x/3",
+             "metadata": [{"name": "Education", "value": "education_none"}]},
+            {"id": 4, "title": "D", "absolute_url": "https://x/4",
+             "metadata": [{"label": "Education", "value": {"value": "education_required"}}]},
+            {"id": 5, "title": "E", "absolute_url": "https://x/5",
+             "metadata": [{"name": "Team", "value": "education_optional"}]},
+            // The top-level field wins over metadata, so the tags never combine
+            {"id": 6, "title": "F", "absolute_url": "https://x/6", "education": "education_none",
+             "metadata": [{"name": "Education", "value": "education_optional"}]}
+        ]});
+
+        let edu_tags: Vec<Vec<String>> = AtsType::Greenhouse.parse(&company, &data).unwrap().into_iter()
+            .map(|j| j.tags)
+            .collect();
+        assert_eq!(edu_tags, vec![
+            vec!["Education Optional".to_string()],
+            vec!["Degree Required".to_string()],
+            vec!["Education Not Required".to_string()],
+            vec!["Degree Required".to_string()],
+            vec![],
+            vec!["Education Not Required".to_string()],
+        ]);
+    }
 
     #[test]
     fn test_parse_smartrecruiters() {
@@ -388,6 +965,7 @@ mod tests {
             slug: "airnewzealand".to_string(),
             api_url: "https://api.smartrecruiters.com/v1/companies/airnewzealand/postings".to_string(),
             domain: Some("airnewzealand.com".to_string()),
+            ..Default::default()
         };
 
         let data = json!({
@@ -405,6 +983,7 @@ mod tests {
                         "fullLocation": "Auckland, Auckland, New Zealand"
                     },
                     "typeOfEmployment": { "label": "Full-time" },
+                    "applyUrl": "https://jobs.smartrecruiters.com/airnewzealand/6000000000788236/apply",
                     "customField": [
                         {
                             "fieldId": "6663765cd273aa35722c76da",
@@ -412,16 +991,33 @@ mod tests {
                             "valueLabel": "Auckland Airport - Campus (AKL35K)"
                         }
                     ]
+                },
+                {
+                    "id": "6000000000790001",
+                    "name": "Data Analyst Intern",
+                    "releasedDate": "2026-01-09T08:00:00.000Z",
+                    "location": { "city": "", "country": "", "remote": true, "hybrid": false, "fullLocation": "" }
+                },
+                {
+                    "id": "6000000000790002",
+                    "name": "Graduate Engineer",
+                    "location": { "city": "Wellington", "country": "nz", "remote": false, "hybrid": true }
                 }
             ]
         });
 
         let jobs = AtsType::SmartRecruiters.parse(&company, &data).unwrap();
-        assert_eq!(jobs.len(), 1);
+        assert_eq!(jobs.len(), 3);
+        assert_eq!(jobs[1].location, "Remote");
+        assert_eq!(jobs[1].tags, vec!["Remote"]);
+        assert_eq!(jobs[2].location, "Wellington, New Zealand");
+        assert_eq!(jobs[2].tags, vec!["Hybrid"]);
         let job = &jobs[0];
         assert_eq!(job.title, "Senior Software Engineer (iOS)");
-        assert_eq!(job.location, "Auckland, Auckland, nz");
+        assert_eq!(job.location, "Auckland, Auckland, New Zealand");
+        assert_eq!(job.country.as_deref(), Some("New Zealand"));
         assert_eq!(job.url, "https://jobs.smartrecruiters.com/airnewzealand/6000000000788236");
+        assert_eq!(job.apply_url.as_deref(), Some("https://jobs.smartrecruiters.com/airnewzealand/6000000000788236/apply"));
         assert!(job.tags.contains(&"Full-time".to_string()));
         assert!(job.tags.contains(&"Auckland Airport - Campus (AKL35K)".to_string()));
     }
@@ -434,6 +1030,7 @@ mod tests {
             slug: "cal-com".to_string(),
             api_url: "https://cal-com.breezy.hr/json".to_string(),
             domain: Some("cal.com".to_string()),
+            ..Default::default()
         };
 
         let data = json!([
@@ -464,4 +1061,266 @@ mod tests {
         assert!(job.tags.contains(&"Fully remote, no location restrictions".to_string()));
         assert!(job.tags.contains(&"Salary: $60k".to_string()));
     }
+
+    #[test]
+    fn test_parse_breezy_department_forms() {
+        let company = CompanyEntry { name: "Acme".to_string(), slug: "acme".to_string(), ..Default::default() };
+        let data = json!([
+            { "id": "a", "name": "Intern", "department": "Engineering" },
+            { "id": "b", "name": "Intern", "department": { "id": "d1", "name": "Engineering" }, "team": "Platform" },
+            { "id": "c", "name": "Intern", "department": { "id": 42, "name": null }, "team": "Design" },
+            { "id": "d", "name": "Intern", "department": "Sales", "team": "Sales" },
+        ]);
+        let jobs = AtsType::Breezy.parse(&company, &data).unwrap();
+        let departments: Vec<Vec<&str>> = jobs.iter().map(|j| j.departments.iter().map(String::as_str).collect()).collect();
+        assert_eq!(departments, vec![vec!["Engineering"], vec!["Engineering", "Platform"], vec!["Design"], vec!["Sales"]]);
+
+        let object: FlexibleDepartment = serde_json::from_value(json!({"id": "d1", "name": "Data"})).unwrap();
+        assert_eq!(String::from(object), "Data");
+    }
+
+    #[test]
+    fn test_normalize_relative_dates() {
+        let now = Utc.with_ymd_and_hms(2025, 3, 15, 12, 0, 0).unwrap();
+        let normalized = |s: &str| normalize_date_at(s, now).map(format_date);
+        assert_eq!(normalized("2 days ago").as_deref(), Some("2025-03-13T12:00:00+00:00"));
+        assert_eq!(normalized("Posted today").as_deref(), Some("2025-03-15T12:00:00+00:00"));
+        assert_eq!(normalized("Posted Yesterday").as_deref(), Some("2025-03-14T12:00:00+00:00"));
+        assert_eq!(normalized("1 week ago").as_deref(), Some("2025-03-08T12:00:00+00:00"));
+        assert_eq!(normalized("3 weeks ago").as_deref(), Some("2025-02-22T12:00:00+00:00"));
+        assert_eq!(normalized("1 month ago").as_deref(), Some("2025-02-13T12:00:00+00:00"));
+        assert_eq!(normalized("Posted 1 year ago").as_deref(), Some("2024-03-15T12:00:00+00:00"));
+        assert_eq!(normalized("300000 years ago"), None);
+        assert_eq!(normalized("99999999999999 weeks ago"), None);
+        // Absolute formats are unaffected by `now`
+        assert_eq!(normalized("2024-01-01T12:00:00Z").as_deref(), Some("2024-01-01T12:00:00+00:00"));
+    }
+
+    #[test]
+    fn test_normalize_date_ats_formats() {
+        assert_eq!(normalize_date("2025-02-01").map(format_date).as_deref(), Some("2025-02-01T00:00:00+00:00"));
+        assert_eq!(normalize_date("2025-02-07 09:15:00 UTC").map(format_date).as_deref(), Some("2025-02-07T09:15:00+00:00"));
+        assert_eq!(normalize_date("2025-02-30"), None);
+    }
+
+    #[test]
+    fn test_normalize_date_unparseable() {
+        assert_eq!(normalize_date(""), None);
+        assert_eq!(normalize_date("sometime soon"), None);
+        assert_eq!(normalize_date("1700000000").map(format_date).as_deref(), Some("2023-11-14T22:13:20+00:00"));
+    }
+
+    #[test]
+    fn test_parse_workable_location() {
+        let company = CompanyEntry {
+            name: "Hugging Face".to_string(),
+            ats_type: AtsType::Workable,
+            slug: "huggingface".to_string(),
+            ..Default::default()
+        };
+        let data = json!({
+            "jobs": [
+                {"shortcode": "A1", "title": "ML Intern", "city": "Paris", "state": "Île-de-France", "country": "France"},
+                {"shortcode": "B2", "title": "Research Intern", "city": "San Francisco", "country": null},
+                {"shortcode": "C3", "title": "Data Intern", "city": "", "country": null, "remote": true},
+                {"shortcode": "D4", "title": "Ops Intern"}
+            ]
+        });
+
+        let jobs = AtsType::Workable.parse(&company, &data).unwrap();
+        assert_eq!(jobs[0].location, "Paris, Île-de-France, France");
+        assert_eq!(jobs[0].url, "https://apply.workable.com/huggingface/j/A1/");
+        assert_eq!(jobs[1].location, "San Francisco");
+        assert_eq!(jobs[2].location, "Remote");
+        assert_eq!(jobs[2].tags, vec!["Remote".to_string()]);
+        assert_eq!(jobs[3].location, "");
+        assert!(jobs[3].tags.is_empty());
+    }
+
+    #[test]
+    fn test_parse_ashby_compensation_fallback() {
+        let company = CompanyEntry {
+            name: "Linear".to_string(),
+            ats_type: AtsType::Ashby,
+            slug: "linear".to_string(),
+            ..Default::default()
+        };
+        let data = json!({
+            "apiVersion": "1",
+            "jobs": [
+                {"id": "a", "title": "Intern", "jobUrl": "https://jobs.ashbyhq.com/linear/a",
+                 "employmentType": "Contract", "compensationTierSummary": "$40/hr"},
+                {"id": "b", "title": "Graduate", "jobUrl": "https://jobs.ashbyhq.com/linear/b", "isRemote": true,
+                 "compensationTierSummary": "stale", "compensation": {"compensationTierSummary": "$120K"}}
+            ]
+        });
+
+        let jobs = AtsType::Ashby.parse(&company, &data).unwrap();
+        assert_eq!(jobs[0].tags, vec!["Contract".to_string(), "Salary: $40/hr".to_string()]);
+        // The nested v2 summary wins over the top-level one
+        assert_eq!(jobs[1].tags, vec!["Remote".to_string(), "Salary: $120K".to_string()]);
+    }
+
+    #[test]
+    fn test_validate_schema() {
+        let critical = |ats: AtsType, data: Value| -> Vec<String> {
+            ats.validate_schema(&data).into_iter()
+                .filter(|w| w.severity == Severity::Critical)
+                .map(|w| format!("{}: {}", w.field, w.message))
+                .collect()
+        };
+        let cases = [
+            (AtsType::Greenhouse, json!({"jobs": [{"id": 1, "title": "Intern", "url": "u"}, {"id": 2, "title": "Intern"}]}), vec!["absolute_url|url: missing on 1 of 2 jobs"]),
+            (AtsType::Greenhouse, json!({"error": "not found"}), vec!["jobs: expected an array of jobs, found nothing"]),
+            (AtsType::Lever, json!({"ok": false, "error": "Document not found"}), vec!["(root): expected an array of jobs, found an object"]),
+            (AtsType::Lever, json!([{"id": "a", "text": "Intern", "hostedUrl": "u"}]), vec!["categories: missing on 1 of 1 jobs"]),
+            (AtsType::SmartRecruiters, json!({"content": [{"id": "1", "title": "Intern", "location": {}}]}), vec!["name: missing on 1 of 1 jobs"]),
+            (AtsType::Ashby, json!({"jobPostings": []}), vec!["jobs: expected an array of jobs, found nothing"]),
+            (AtsType::Ashby, json!({"jobs": [{"id": "a", "title": "Intern", "jobUrl": null}]}), vec!["jobUrl: missing on 1 of 1 jobs"]),
+            (AtsType::Workable, json!({"jobs": [{"id": "a", "title": "Intern"}]}), vec!["shortcode: missing on 1 of 1 jobs"]),
+            (AtsType::Recruitee, json!({"offers": "none"}), vec!["offers: expected an array of jobs, found a string"]),
+            (AtsType::Breezy, json!([{"id": "a", "title": "Intern"}]), vec!["name: missing on 1 of 1 jobs"]),
+        ];
+        for (ats, data, expected) in cases {
+            assert_eq!(critical(ats, data.clone()), expected, "{:?} {}", ats, data);
+        }
+
+        // Missing optional fields only warn, and an empty board is fine
+        let warnings = AtsType::Ashby.validate_schema(&json!({"jobs": [{"id": "a", "title": "Intern", "jobUrl": "u", "location": "Remote"}]}));
+        assert_eq!(warnings, vec![SchemaWarning { field: "publishedAt".to_string(), message: "missing on 1 of 1 jobs".to_string(), severity: Severity::Warning }]);
+        assert!(AtsType::Workable.validate_schema(&json!({"jobs": []})).is_empty());
+        assert!(AtsType::Unknown.validate_schema(&json!({"anything": 1})).is_empty());
+    }
+
+    #[test]
+    fn test_logo_url_sources() {
+        let company = CompanyEntry { name: "Acme".to_string(), slug: "acme".to_string(), ..Default::default() };
+        let smartrecruiters = json!({"content": [
+            {"id": "1", "name": "Intern", "location": {}, "company": {"identifier": "Acme", "logo": "https://cdn.example/acme.png"}},
+            {"id": "2", "name": "Graduate", "location": {}, "company": {"identifier": "Acme"}}
+        ]});
+        let jobs = AtsType::SmartRecruiters.parse(&company, &smartrecruiters).unwrap();
+        assert_eq!(jobs[0].logo_url.as_deref(), Some("https://cdn.example/acme.png"));
+        assert!(jobs[1].logo_url.is_none());
+
+        let ashby = json!({"jobs": [{"id": "a", "title": "Intern", "jobUrl": "https://jobs.ashbyhq.com/acme/a"}],
+                           "jobBoard": {"title": "Acme", "logo": "https://cdn.example/board.png"}});
+        let jobs = AtsType::Ashby.parse(&company, &ashby).unwrap();
+        assert_eq!(jobs[0].logo_url.as_deref(), Some("https://cdn.example/board.png"));
+
+        // The slugs.json entry wins over the ATS
+        let company = CompanyEntry { logo_url: Some("https://acme.com/logo.svg".to_string()), ..company };
+        let jobs = AtsType::Ashby.parse(&company, &ashby).unwrap();
+        assert_eq!(jobs[0].logo_url.as_deref(), Some("https://acme.com/logo.svg"));
+    }
+
+    #[test]
+    fn test_parse_ashby_secondary_locations() {
+        let company = CompanyEntry {
+            name: "Linear".to_string(),
+            ats_type: AtsType::Ashby,
+            slug: "linear".to_string(),
+            ..Default::default()
+        };
+        let data = json!({
+            "jobs": [
+                {"id": "a", "title": "Intern", "jobUrl": "https://jobs.ashbyhq.com/linear/a",
+                 "location": "San Francisco, CA",
+                 "secondaryLocations": [{"location": "New York, NY"}, {"location": "London, UK"}, {"location": "San Francisco, CA"}, "Remote"]},
+                {"id": "b", "title": "Graduate", "jobUrl": "https://jobs.ashbyhq.com/linear/b", "location": "Berlin"}
+            ]
+        });
+
+        let jobs = AtsType::Ashby.parse(&company, &data).unwrap();
+        assert_eq!(jobs[0].location, "San Francisco, CA");
+        // The primary location isn't repeated
+        assert_eq!(jobs[0].offices, vec!["New York, NY".to_string(), "London, UK".to_string(), "Remote".to_string()]);
+        assert!(jobs[1].offices.is_empty());
+    }
+
+    #[test]
+    fn test_parse_lever_lists_and_additional() {
+        let company = CompanyEntry {
+            name: "Acme".to_string(),
+            ats_type: AtsType::Lever,
+            slug: "acme".to_string(),
+            ..Default::default()
+        };
+        let data = json!([
+            {
+                "id": "a", "text": "Intern", "hostedUrl": "https://jobs.lever.co/acme/a", "categories": {},
+                "description": "<div>About the role</div>",
+                "lists": [
+                    {"text": "Requirements", "content": "<li>Rust</li><li>SQL</li>"},
+                    {"text": "Nice to have", "content": ["Go", "Kubernetes"]}
+                ],
+                "additional": "<div>Equity and <b>401k</b><script>x()</script></div>"
+            },
+            {"id": "b", "text": "Intern", "hostedUrl": "https://jobs.lever.co/acme/b", "categories": {}, "description": "<p>Plain</p>", "additional": " "}
+        ]);
+
+        let jobs = AtsType::Lever.parse(&company, &data).unwrap();
+        assert_eq!(
+            jobs[0].description,
+            "<div>About the role</div><h3>Requirements</h3><ul><li>Rust</li><li>SQL</li></ul>\
+             <h3>Nice to have</h3><ul><li>Go</li><li>Kubernetes</li></ul><hr><div>Equity and <b>401k</b></div>"
+        );
+        // Nothing to append
+        assert_eq!(jobs[1].description, "<p>Plain</p>");
+    }
+
+    #[test]
+    fn test_parse_lever_commitment() {
+        let company = CompanyEntry {
+            name: "Acme".to_string(),
+            ats_type: AtsType::Lever,
+            slug: "acme".to_string(),
+            ..Default::default()
+        };
+        let cases = [
+            ("Full-time", Some(EmploymentType::FullTime)),
+            ("Part-time", Some(EmploymentType::PartTime)),
+            ("Part Time", Some(EmploymentType::PartTime)),
+            ("Contract", Some(EmploymentType::Contract)),
+            ("Contractor", Some(EmploymentType::Contract)),
+            ("Internship", Some(EmploymentType::Internship)),
+            ("Co-op", Some(EmploymentType::CoOp)),
+            ("FULL TIME", Some(EmploymentType::FullTime)),
+            ("Seasonal", None),
+        ];
+        for (commitment, expected) in cases {
+            let data = json!([{"id": "a", "text": "Intern", "hostedUrl": "https://jobs.lever.co/acme/a", "categories": {"commitment": commitment}}]);
+            let jobs = AtsType::Lever.parse(&company, &data).unwrap();
+            assert_eq!(jobs[0].employment_type, expected, "{}", commitment);
+            // The raw value is still tagged
+            assert!(jobs[0].tags.contains(&commitment.to_string()), "{}", commitment);
+        }
+    }
+
+    #[test]
+    fn test_apply_url_differs_from_url() {
+        let company = CompanyEntry {
+            name: "Acme".to_string(),
+            ats_type: AtsType::Lever,
+            slug: "acme".to_string(),
+            ..Default::default()
+        };
+        let data = json!([
+            {"id": "a", "text": "Intern", "hostedUrl": "https://jobs.lever.co/acme/a", "applyUrl": "https://jobs.lever.co/acme/a/apply", "categories": {}},
+            {"id": "b", "text": "Intern", "hostedUrl": "https://jobs.lever.co/acme/b", "applyUrl": "https://jobs.lever.co/acme/b", "categories": {}},
+            {"id": "c", "text": "Intern", "hostedUrl": "https://jobs.lever.co/acme/c", "categories": {}}
+        ]);
+
+        let jobs = AtsType::Lever.parse(&company, &data).unwrap();
+        assert_eq!(jobs[0].apply_url.as_deref(), Some("https://jobs.lever.co/acme/a/apply"));
+        assert_ne!(jobs[0].apply_url.as_deref(), Some(jobs[0].url.as_str()));
+        // An apply link that is just the listing again isn't kept
+        assert_eq!(jobs[1].apply_url, None);
+        assert_eq!(jobs[2].apply_url, None);
+
+        let data = json!({"jobs": [{"shortcode": "A1", "title": "ML Intern", "application_url": "https://apply.workable.com/j/A1/apply"}]});
+        let jobs = AtsType::Workable.parse(&CompanyEntry { ats_type: AtsType::Workable, ..company }, &data).unwrap();
+        assert_ne!(jobs[0].apply_url.as_deref(), Some(jobs[0].url.as_str()));
+        assert_eq!(jobs[0].apply_url.as_deref(), Some("https://apply.workable.com/j/A1/apply"));
+    }
 }
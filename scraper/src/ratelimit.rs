@@ -0,0 +1,73 @@
+use dashmap::DashMap;
+use std::sync::Arc;
+use tokio::sync::{OwnedSemaphorePermit, Semaphore};
+
+/// Caps concurrent requests per host, so 25 scrapers don't all hit
+/// boards.greenhouse.io at once. Clones share the same limits.
+#[derive(Clone)]
+pub struct DomainRateLimiter {
+    semaphores: Arc<DashMap<String, Arc<Semaphore>>>,
+    max_per_domain: usize,
+}
+
+impl DomainRateLimiter {
+    pub fn new(max_per_domain: usize) -> Self {
+        Self {
+            semaphores: Arc::new(DashMap::new()),
+            // Zero would block every request forever
+            max_per_domain: max_per_domain.max(1),
+        }
+    }
+
+    /// Waits for a slot on `url`'s host; the slot is released when the permit drops.
+    /// URLs without a host aren't limited.
+    pub async fn acquire(&self, url: &str) -> Option<OwnedSemaphorePermit> {
+        let host = domain_of(url)?;
+        let semaphore = self.semaphores
+            .entry(host)
+            .or_insert_with(|| Arc::new(Semaphore::new(self.max_per_domain)))
+            .clone();
+        // The semaphore is never closed, so this can't fail
+        semaphore.acquire_owned().await.ok()
+    }
+}
+
+/// Lowercase FQDN of `url`, e.g. "boards-api.greenhouse.io".
+pub fn domain_of(url: &str) -> Option<String> {
+    reqwest::Url::parse(url).ok()?.host_str().map(|h| h.to_lowercase())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+    use tokio::time::timeout;
+
+    #[test]
+    fn test_domain_of() {
+        assert_eq!(domain_of("https://boards-api.greenhouse.io/v1/boards/x/jobs").as_deref(), Some("boards-api.greenhouse.io"));
+        assert_eq!(domain_of("https://API.Lever.co:443/v0/postings/x").as_deref(), Some("api.lever.co"));
+        assert_eq!(domain_of("not a url"), None);
+    }
+
+    #[tokio::test]
+    async fn test_limiter_blocks_excess_requests_per_domain() {
+        let limiter = DomainRateLimiter::new(2);
+        let first = limiter.acquire("https://api.lever.co/v0/postings/a").await.unwrap();
+        let _second = limiter.acquire("https://api.lever.co/v0/postings/b").await.unwrap();
+
+        // A third request to the same host waits...
+        let waiting = timeout(Duration::from_millis(50), limiter.acquire("https://api.lever.co/v0/postings/c")).await;
+        assert!(waiting.is_err());
+        // ...while other hosts are unaffected
+        let other = timeout(Duration::from_millis(50), limiter.acquire("https://boards-api.greenhouse.io/v1")).await;
+        assert!(other.unwrap().is_some());
+
+        let clone = limiter.clone();
+        let third = tokio::spawn(async move { clone.acquire("https://api.lever.co/v0/postings/c").await.is_some() });
+        tokio::time::sleep(Duration::from_millis(20)).await;
+        assert!(!third.is_finished());
+        drop(first);
+        assert!(timeout(Duration::from_secs(1), third).await.unwrap().unwrap());
+    }
+}
@@ -0,0 +1,108 @@
+use regex::Regex;
+use std::fmt::Write;
+
+use crate::stats::write_table;
+
+/// How one title fares against the keyword regexes in `zapply test-regex`.
+pub struct TitleCheck {
+    pub title: String,
+    /// Matched spans of `keywords_regex`, in order.
+    pub keyword_matches: Vec<(usize, usize)>,
+    pub negative_matches: Vec<(usize, usize)>,
+}
+
+impl TitleCheck {
+    pub fn new(title: &str, keyword_regex: &Regex, negative_regex: &Regex) -> Self {
+        let spans = |regex: &Regex| regex.find_iter(title).map(|m| (m.start(), m.end())).collect();
+        Self {
+            title: title.to_string(),
+            keyword_matches: spans(keyword_regex),
+            negative_matches: spans(negative_regex),
+        }
+    }
+
+    /// The same rule `scrape_company` applies before a job is kept.
+    pub fn would_include(&self) -> bool {
+        !self.keyword_matches.is_empty() && self.negative_matches.is_empty()
+    }
+}
+
+/// `title` with each span wrapped in brackets: "[Senior] Data Scientist".
+fn highlight(title: &str, spans: &[(usize, usize)]) -> String {
+    let mut out = String::new();
+    let mut last = 0;
+    for (start, end) in spans {
+        out.push_str(&title[last..*start]);
+        write!(out, "[{}]", &title[*start..*end]).ok();
+        last = *end;
+    }
+    out.push_str(&title[last..]);
+    out
+}
+
+/// One title with its matches highlighted.
+pub fn render_title_check(check: &TitleCheck) -> String {
+    let show = |spans: &[(usize, usize)]| if spans.is_empty() { "no match".to_string() } else { highlight(&check.title, spans) };
+    let mut out = String::new();
+    writeln!(out, "Input:                   {}", check.title).ok();
+    writeln!(out, "keywords_regex:          {}", show(&check.keyword_matches)).ok();
+    writeln!(out, "negative_keywords_regex: {}", show(&check.negative_matches)).ok();
+    writeln!(out, "Would include:           {}", check.would_include()).ok();
+    out
+}
+
+/// A table of titles, e.g. from `--title-file`, and how many would be kept.
+pub fn render_title_table(checks: &[TitleCheck]) -> String {
+    let mut out = String::new();
+    let included = checks.iter().filter(|c| c.would_include()).count();
+    writeln!(out, "{} of {} titles would be included", included, checks.len()).ok();
+    let rows = checks.iter().map(|c| vec![
+        c.title.clone(),
+        (!c.keyword_matches.is_empty()).to_string(),
+        (!c.negative_matches.is_empty()).to_string(),
+        c.would_include().to_string(),
+    ]).collect();
+    write_table(&mut out, "Titles", &["Title", "keyword_match", "negative_match", "would_include"], rows);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn regexes() -> (Regex, Regex) {
+        (Regex::new(r"(?i)\b(intern|graduate)\b").unwrap(), Regex::new(r"(?i)\b(senior|lead)\b").unwrap())
+    }
+
+    #[test]
+    fn test_render_title_check() {
+        let (keywords, negative) = regexes();
+        let check = TitleCheck::new("Senior Data Scientist", &keywords, &negative);
+        assert!(!check.would_include());
+        let text = render_title_check(&check);
+        assert!(text.contains("keywords_regex:          no match\n"), "{}", text);
+        assert!(text.contains("negative_keywords_regex: [Senior] Data Scientist\n"), "{}", text);
+        assert!(text.ends_with("Would include:           false\n"), "{}", text);
+
+        let check = TitleCheck::new("Graduate Engineer (Intern)", &keywords, &negative);
+        assert!(check.would_include());
+        assert!(render_title_check(&check).contains("keywords_regex:          [Graduate] Engineer ([Intern])\n"));
+    }
+
+    #[test]
+    fn test_render_title_table() {
+        let (keywords, negative) = regexes();
+        let checks: Vec<TitleCheck> = ["Software Intern", "Lead Intern", "Data Scientist"].iter()
+            .map(|t| TitleCheck::new(t, &keywords, &negative))
+            .collect();
+        let text = render_title_table(&checks);
+        assert!(text.starts_with("1 of 3 titles would be included\n"), "{}", text);
+        let row = |title: &str| -> Vec<String> {
+            let line = text.lines().find(|l| l.starts_with(title)).unwrap();
+            line[title.len()..].split_whitespace().map(String::from).collect()
+        };
+        assert_eq!(row("Software Intern"), vec!["true", "false", "true"]);
+        assert_eq!(row("Lead Intern"), vec!["true", "true", "false"]);
+        assert_eq!(row("Data Scientist"), vec!["false", "false", "false"]);
+    }
+}
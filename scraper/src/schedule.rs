@@ -0,0 +1,95 @@
+use anyhow::{Context, Result};
+use chrono::{DateTime, Duration, Utc};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Mutex;
+
+use crate::models::CompanyEntry;
+
+/// Last successful scrape per slug, persisted in `last_scraped.json` so
+/// companies with a `scrape_interval_hours` aren't polled on every run.
+pub struct ScrapeSchedule {
+    last_scraped: Mutex<HashMap<String, DateTime<Utc>>>,
+}
+
+impl ScrapeSchedule {
+    pub fn new() -> Self {
+        Self { last_scraped: Mutex::new(HashMap::new()) }
+    }
+
+    /// A missing file means nothing has been scraped yet.
+    pub fn load(path: &str) -> Result<Self> {
+        let schedule = Self::new();
+        if std::path::Path::new(path).exists() {
+            let content = fs::read_to_string(path)?;
+            let last_scraped = serde_json::from_str(&content).context(format!("Invalid {}", path))?;
+            *schedule.last_scraped.lock().unwrap() = last_scraped;
+        }
+        Ok(schedule)
+    }
+
+    /// False while the company's interval hasn't passed since its last success.
+    pub fn is_due(&self, company: &CompanyEntry, now: DateTime<Utc>) -> bool {
+        let Some(hours) = company.scrape_interval_hours else { return true; };
+        match self.last_scraped.lock().unwrap().get(&company.slug) {
+            Some(last) => now - *last >= Duration::hours(hours as i64),
+            None => true,
+        }
+    }
+
+    /// Records the success and rewrites `path` straight away, so a run that
+    /// dies halfway still remembers the companies it finished.
+    pub fn record_success(&self, slug: &str, now: DateTime<Utc>, path: &str) -> Result<()> {
+        let mut last_scraped = self.last_scraped.lock().unwrap();
+        last_scraped.insert(slug.to_string(), now);
+        // Written under the lock so concurrent tasks can't interleave renames
+        save(&last_scraped, path)
+    }
+}
+
+/// Writes to a temp file and renames it over `path`.
+fn save(timestamps: &HashMap<String, DateTime<Utc>>, path: &str) -> Result<()> {
+    let tmp_path = format!("{}.tmp", path);
+    fs::write(&tmp_path, serde_json::to_string_pretty(timestamps)?)?;
+    fs::rename(&tmp_path, path)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn company(slug: &str, scrape_interval_hours: Option<u64>) -> CompanyEntry {
+        CompanyEntry {
+            name: slug.to_string(),
+            slug: slug.to_string(),
+            scrape_interval_hours,
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn test_schedule_skips_recent_companies() {
+        let path = std::env::temp_dir().join(format!("zapply_last_scraped_{}.json", std::process::id()));
+        let path = path.to_str().unwrap();
+        let now = Utc::now();
+
+        let schedule = ScrapeSchedule::new();
+        let workday = company("workday", Some(6));
+        assert!(schedule.is_due(&workday, now));
+
+        schedule.record_success("workday", now - Duration::hours(2), path).unwrap();
+        schedule.record_success("lever", now - Duration::hours(2), path).unwrap();
+        assert!(!schedule.is_due(&workday, now));
+        assert!(schedule.is_due(&workday, now + Duration::hours(4)));
+        // Without an interval a company is scraped every run
+        assert!(schedule.is_due(&company("lever", None), now));
+
+        let loaded = ScrapeSchedule::load(path).unwrap();
+        assert!(!loaded.is_due(&workday, now));
+        assert!(!std::path::Path::new(&format!("{}.tmp", path)).exists());
+
+        fs::remove_file(path).unwrap();
+        assert!(ScrapeSchedule::load(path).unwrap().is_due(&workday, now));
+    }
+}
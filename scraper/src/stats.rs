@@ -0,0 +1,237 @@
+use std::collections::{BTreeMap, HashMap};
+use std::fmt::Write;
+
+use crate::models::{CompanyEntry, Job};
+
+#[derive(Default)]
+struct AtsStats {
+    companies: usize,
+    succeeded: usize,
+    jobs: usize,
+}
+
+/// Aggregates for `--stats`, used to tune keyword regexes and spot parser regressions.
+#[derive(Default)]
+pub struct ScrapeStats {
+    per_ats: BTreeMap<String, AtsStats>,
+    company_jobs: HashMap<String, usize>,
+    tag_counts: HashMap<String, usize>,
+    country_counts: HashMap<String, usize>,
+    /// Keyed by the serialized `EmploymentType`, or "None" when the ATS gave none.
+    employment_counts: HashMap<String, usize>,
+    description_lengths: Vec<usize>,
+}
+
+impl ScrapeStats {
+    pub fn record_company(&mut self, company: &CompanyEntry, succeeded: bool, jobs: &[Job]) {
+        let ats = serde_json::to_string(&company.ats_type).unwrap_or_default().trim_matches('"').to_string();
+        let entry = self.per_ats.entry(ats).or_default();
+        entry.companies += 1;
+        entry.jobs += jobs.len();
+        if succeeded {
+            entry.succeeded += 1;
+        }
+        if !jobs.is_empty() {
+            *self.company_jobs.entry(company.name.clone()).or_insert(0) += jobs.len();
+        }
+
+        for job in jobs {
+            for tag in &job.tags {
+                *self.tag_counts.entry(tag.clone()).or_insert(0) += 1;
+            }
+            let country = job.country.clone().unwrap_or_else(|| "Unknown".to_string());
+            *self.country_counts.entry(country).or_insert(0) += 1;
+            let employment = match job.employment_type {
+                Some(kind) => serde_json::to_string(&kind).unwrap_or_default().trim_matches('"').to_string(),
+                None => "None".to_string(),
+            };
+            *self.employment_counts.entry(employment).or_insert(0) += 1;
+            self.description_lengths.push(job.description.chars().count());
+        }
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let companies: usize = self.per_ats.values().map(|a| a.companies).sum();
+        let succeeded: usize = self.per_ats.values().map(|a| a.succeeded).sum();
+        let jobs = self.description_lengths.len();
+
+        writeln!(out, "Companies scraped: {} ({} succeeded)", companies, succeeded).ok();
+        writeln!(out, "Jobs found: {}", jobs).ok();
+        let mut lengths = self.description_lengths.clone();
+        lengths.sort_unstable();
+        writeln!(out, "Description length: median {} / p95 {} chars", percentile(&lengths, 50), percentile(&lengths, 95)).ok();
+
+        let ats_rows = self.per_ats.iter().map(|(ats, a)| vec![
+            ats.clone(),
+            a.companies.to_string(),
+            format!("{:.1}%", a.succeeded as f64 * 100.0 / a.companies.max(1) as f64),
+            a.jobs.to_string(),
+        ]).collect();
+        write_table(&mut out, "Per ATS", &["ATS", "Companies", "Success", "Jobs"], ats_rows);
+        write_table(&mut out, "Top companies", &["Company", "Jobs"], top_rows(&self.company_jobs, 10));
+        write_table(&mut out, "Top tags", &["Tag", "Jobs"], top_rows(&self.tag_counts, 20));
+        write_table(&mut out, "Top countries", &["Country", "Jobs"], top_rows(&self.country_counts, 10));
+        write_table(&mut out, "Jobs per employment type", &["Employment type", "Jobs"], top_rows(&self.employment_counts, self.employment_counts.len()));
+        out
+    }
+}
+
+/// Summary for `zapply test --company=<slug>`.
+pub fn render_company_report(company: &CompanyEntry, url: &str, jobs: &[Job]) -> String {
+    let mut out = String::new();
+    let ats = serde_json::to_string(&company.ats_type).unwrap_or_default();
+    writeln!(out, "Company: {} ({})", company.name, company.slug).ok();
+    writeln!(out, "ATS: {}", ats.trim_matches('"')).ok();
+    writeln!(out, "API URL: {}", url).ok();
+    writeln!(out, "Jobs parsed: {}", jobs.len()).ok();
+
+    if !jobs.is_empty() {
+        writeln!(out, "\nFirst jobs").ok();
+        for job in jobs.iter().take(5) {
+            let location = if job.location.is_empty() { "no location" } else { job.location.as_str() };
+            writeln!(out, "  - {} ({})", job.title, location).ok();
+        }
+    }
+
+    let warnings = [
+        (jobs.iter().filter(|j| j.posted.is_none()).count(), "without a posted date"),
+        (jobs.iter().filter(|j| j.location.is_empty()).count(), "without a location"),
+        (jobs.iter().filter(|j| j.description.trim().is_empty()).count(), "without a description"),
+    ];
+    let warnings: Vec<_> = warnings.iter().filter(|(n, _)| *n > 0).collect();
+    if !warnings.is_empty() {
+        writeln!(out, "\nWarnings").ok();
+        for (n, what) in warnings {
+            writeln!(out, "  - {} job(s) {}", n, what).ok();
+        }
+    }
+
+    let mut tag_counts = HashMap::new();
+    for tag in jobs.iter().flat_map(|j| &j.tags) {
+        *tag_counts.entry(tag.clone()).or_insert(0) += 1;
+    }
+    if !tag_counts.is_empty() {
+        write_table(&mut out, "Tags", &["Tag", "Jobs"], top_rows(&tag_counts, 20));
+    }
+    out
+}
+
+/// Nearest-rank percentile of an already sorted slice.
+pub fn percentile(sorted: &[usize], pct: usize) -> usize {
+    if sorted.is_empty() {
+        return 0;
+    }
+    let rank = (pct * sorted.len()).div_ceil(100).max(1);
+    sorted[rank - 1]
+}
+
+fn top_rows(counts: &HashMap<String, usize>, n: usize) -> Vec<Vec<String>> {
+    let mut entries: Vec<_> = counts.iter().collect();
+    // Ties broken by name so output is stable between runs
+    entries.sort_by(|a, b| b.1.cmp(a.1).then_with(|| a.0.cmp(b.0)));
+    entries.into_iter()
+        .take(n)
+        .map(|(name, count)| vec![name.clone(), count.to_string()])
+        .collect()
+}
+
+pub fn write_table(out: &mut String, title: &str, headers: &[&str], rows: Vec<Vec<String>>) {
+    let mut widths: Vec<usize> = headers.iter().map(|h| h.chars().count()).collect();
+    for row in &rows {
+        for (w, cell) in widths.iter_mut().zip(row) {
+            *w = (*w).max(cell.chars().count());
+        }
+    }
+
+    writeln!(out, "\n{}", title).ok();
+    let header: Vec<String> = headers.iter().zip(&widths).map(|(h, w)| format!("{:<w$}", h, w = w)).collect();
+    writeln!(out, "{}", header.join("  ").trim_end()).ok();
+    writeln!(out, "{}", widths.iter().map(|w| "-".repeat(*w)).collect::<Vec<_>>().join("  ")).ok();
+    for row in rows {
+        // First column is a label, the rest are numbers
+        let cells: Vec<String> = row.iter().zip(&widths).enumerate()
+            .map(|(i, (cell, w))| if i == 0 { format!("{:<w$}", cell, w = w) } else { format!("{:>w$}", cell, w = w) })
+            .collect();
+        writeln!(out, "{}", cells.join("  ").trim_end()).ok();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::{AtsType, EmploymentType};
+
+    fn company(name: &str, ats_type: AtsType) -> CompanyEntry {
+        CompanyEntry {
+            name: name.to_string(),
+            ats_type,
+            slug: name.to_lowercase(),
+            api_url: String::new(),
+            domain: None,
+            ..Default::default()
+        }
+    }
+
+    fn job(tags: &[&str], country: Option<&str>, description: &str) -> Job {
+        Job {
+            description: description.to_string(),
+            ats: AtsType::Greenhouse,
+            country: country.map(String::from),
+            tags: tags.iter().map(|t| t.to_string()).collect(),
+            ..Job::fixture("greenhouse-1")
+        }
+    }
+
+    #[test]
+    fn test_percentile() {
+        let sorted: Vec<usize> = (1..=100).collect();
+        assert_eq!(percentile(&sorted, 50), 50);
+        assert_eq!(percentile(&sorted, 95), 95);
+        assert_eq!(percentile(&[7], 95), 7);
+        assert_eq!(percentile(&[], 50), 0);
+    }
+
+    #[test]
+    fn test_render_stats() {
+        let mut stats = ScrapeStats::default();
+        let mut canva = [
+            job(&["Rust", "Remote"], Some("Australia"), "aaaa"),
+            job(&["Rust"], Some("Australia"), "aa"),
+        ];
+        canva[0].employment_type = Some(EmploymentType::Internship);
+        canva[1].employment_type = Some(EmploymentType::FullTime);
+        stats.record_company(&company("Canva", AtsType::Greenhouse), true, &canva);
+        stats.record_company(&company("Stripe", AtsType::Greenhouse), false, &[]);
+        stats.record_company(&company("Spotify", AtsType::Lever), true, &[job(&["Python"], None, "aaaaaa")]);
+
+        let out = stats.render();
+        assert!(out.contains("Companies scraped: 3 (2 succeeded)"));
+        assert!(out.contains("Jobs found: 3"));
+        assert!(out.contains("median 4 / p95 6 chars"));
+        assert!(out.contains("greenhouse          2    50.0%     2"));
+        assert!(out.contains("lever               1   100.0%     1"));
+        assert!(out.contains("Canva       2\nSpotify     1\n"));
+        assert!(out.contains("Rust       2\nPython     1\nRemote     1\n"));
+        assert!(out.contains("Australia     2\nUnknown       1\n"));
+        assert!(out.contains("Jobs per employment type\nEmployment type  Jobs\n---------------  ----\nNone                1\nfull-time           1\ninternship          1\n"), "{}", out);
+        assert!(!out.contains("Stripe"));
+    }
+
+    #[test]
+    fn test_company_report() {
+        let mut jobs = vec![
+            job(&["Rust", "Remote"], Some("Germany"), "Build things"),
+            job(&["Rust"], None, ""),
+        ];
+        jobs[0].title = "Backend Intern".to_string();
+        jobs[0].location = "Berlin, Germany".to_string();
+        jobs[1].title = "Graduate Engineer".to_string();
+
+        let report = render_company_report(&company("Acme", AtsType::Lever), "https://api.lever.co/v0/postings/acme", &jobs);
+        assert!(report.starts_with("Company: Acme (acme)\nATS: lever\nAPI URL: https://api.lever.co/v0/postings/acme\nJobs parsed: 2\n"));
+        assert!(report.contains("  - Backend Intern (Berlin, Germany)\n  - Graduate Engineer (no location)\n"));
+        assert!(report.contains("  - 2 job(s) without a posted date\n  - 1 job(s) without a location\n  - 1 job(s) without a description\n"));
+        assert!(report.contains("Rust       2\nRemote     1"), "{}", report);
+    }
+}
@@ -1,11 +1,32 @@
-use regex::RegexSet;
-
+use regex::{Regex, RegexSet};
+use serde::{Deserialize, Serialize};
+use anyhow::{Context, Result};
+use fnv::FnvHasher;
+use lru::LruCache;
+use once_cell::sync::Lazy;
+use rayon::prelude::*;
+use std::hash::Hasher;
+use std::num::NonZeroUsize;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+use crate::models::SeniorityLevel;
 
 pub struct TagEngine {
     regex_set: RegexSet,
     rules: Vec<TagRule>,
+    /// Matches below this (after the title boost) are dropped by `normalize_job`.
+    min_confidence: f32,
+    /// `detect_tags_scored` results keyed by the FNV hash of the text, since
+    /// a company's jobs often share the same boilerplate. `None` when disabled.
+    cache: Option<Mutex<LruCache<u64, Vec<TagMatch>>>>,
+    cache_hits: AtomicUsize,
+    cache_misses: AtomicUsize,
 }
 
+/// Texts remembered by `TagEngine` unless `with_cache_capacity` says otherwise.
+pub const DEFAULT_TAG_CACHE_CAPACITY: usize = 1000;
+
 struct TagRule {
     regex: regex::Regex,
     tag: &'static str,
@@ -17,6 +38,67 @@ struct TagRule {
     forbidden_max_distance: Option<usize>,
 }
 
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TagMatch {
+    pub tag: &'static str,
+    pub confidence: f32,
+}
+
+const CONFIDENCE_EXACT: f32 = 1.0;
+const CONFIDENCE_CONTEXT: f32 = 0.8;
+const CONFIDENCE_DISTANCE: f32 = 0.5;
+/// Title mentions are a stronger signal than passing mentions in the description.
+const TITLE_CONFIDENCE_BOOST: f32 = 1.5;
+
+/// Where the text passed to `detect_tags_with_source` came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TagSource {
+    Title,
+    Description,
+}
+
+impl TagRule {
+    fn confidence(&self) -> f32 {
+        match (&self.context, self.max_word_distance) {
+            (Some(_), Some(_)) => CONFIDENCE_DISTANCE,
+            (Some(_), None) => CONFIDENCE_CONTEXT,
+            // Passing a forbidden-context check counts as satisfied context
+            (None, _) if self.forbidden_context.is_some() => CONFIDENCE_CONTEXT,
+            (None, _) => CONFIDENCE_EXACT,
+        }
+    }
+}
+
+/// On-disk form of a `TagRule`. Empty strings and zero distances mean "unset".
+#[derive(Debug, Serialize, Deserialize)]
+struct TagRuleDef {
+    pattern: String,
+    tag: String,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    context: String,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    distance: usize,
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    forbidden: String,
+    #[serde(default, skip_serializing_if = "is_zero")]
+    forbidden_distance: usize,
+}
+
+fn is_zero(n: &usize) -> bool {
+    *n == 0
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct TagRulesFile {
+    rules: Vec<TagRuleDef>,
+}
+
+impl Default for TagEngine {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl TagEngine {
     pub fn new() -> Self {
         let mut patterns = Vec::new();
@@ -423,7 +505,6 @@ impl TagEngine {
 
         // General & Benefits
         simple!(r"(?i)\blgbtq(\+|\b)", "LGBTQ+ Friendly");
-        simple!(r"(?i)\bpaid (internship|role|position)\b", "Paid");
         simple!(r"(?i)\bvisa sponsorship\b", "Visa Sponsorship");
         simple!(r"(?i)\bremote\b", "Remote");
         simple!(r"(?i)\bhybrid\b", "Hybrid");
@@ -438,16 +519,151 @@ impl TagEngine {
         simple!(r"(?i)\bcloud architect\b", "Cloud Architect");
         simple!(r"(?i)\bsecurity engineer\b|\bcybersecurity engineer\b", "Security Engineer");
 
-        // Smart Tags
-        strict_dist!(r"\$|£|€|¥|₹|USD|GBP|EUR", "Paid", r"(?i)\b(salary|wages?|rate|annum|hour|pay|remuneration|compensation|stipend)\b", 10);
-
         let regex_set = RegexSet::new(patterns).expect("Failed to create RegexSet");
 
-        Self { regex_set, rules }
+        Self::build(regex_set, rules)
+    }
+
+    /// Loads rules from a TOML file instead of the built-in defaults:
+    ///
+    /// ```toml
+    /// [[rules]]
+    /// pattern = "(?i)\\bgo\\b"
+    /// tag = "Go"
+    /// context = "(?i)\\blanguage\\b"
+    /// distance = 5
+    /// ```
+    pub fn from_file(path: &str) -> Result<TagEngine> {
+        let content = std::fs::read_to_string(path)
+            .with_context(|| format!("Failed to read tag rules file: {}", path))?;
+        Self::from_toml(&content)
+            .with_context(|| format!("Failed to load tag rules from: {}", path))
+    }
+
+    pub fn from_toml(content: &str) -> Result<TagEngine> {
+        let file: TagRulesFile = toml::from_str(content)?;
+
+        let build = |pattern: &str| regex::RegexBuilder::new(pattern).case_insensitive(true).build();
+        let optional = |s: usize| if s == 0 { None } else { Some(s) };
+
+        let mut patterns = Vec::with_capacity(file.rules.len());
+        let mut rules = Vec::with_capacity(file.rules.len());
+        for def in file.rules {
+            let regex = build(&def.pattern).with_context(|| format!("Invalid pattern for tag '{}'", def.tag))?;
+            let context = if def.context.is_empty() { None } else {
+                Some(build(&def.context).with_context(|| format!("Invalid context for tag '{}'", def.tag))?)
+            };
+            let forbidden_context = if def.forbidden.is_empty() { None } else {
+                Some(build(&def.forbidden).with_context(|| format!("Invalid forbidden context for tag '{}'", def.tag))?)
+            };
+
+            patterns.push(def.pattern);
+            rules.push(TagRule {
+                regex,
+                // Rules live for the whole run; leaking keeps `detect_tags` returning `&'static str`
+                tag: Box::leak(def.tag.into_boxed_str()),
+                context,
+                max_word_distance: optional(def.distance),
+                forbidden_context,
+                forbidden_max_distance: optional(def.forbidden_distance),
+            });
+        }
+
+        // Case-insensitive like the rules themselves, or the prefilter drops their matches
+        let regex_set = regex::RegexSetBuilder::new(patterns)
+            .case_insensitive(true)
+            .build()
+            .context("Failed to create RegexSet")?;
+        Ok(Self::build(regex_set, rules))
+    }
+
+    /// Serializes the current rules in the format read by `from_file`.
+    pub fn to_toml(&self) -> Result<String> {
+        let rules = self.rules.iter().map(|rule| TagRuleDef {
+            pattern: rule.regex.as_str().to_string(),
+            tag: rule.tag.to_string(),
+            context: rule.context.as_ref().map(|r| r.as_str().to_string()).unwrap_or_default(),
+            distance: rule.max_word_distance.unwrap_or(0),
+            forbidden: rule.forbidden_context.as_ref().map(|r| r.as_str().to_string()).unwrap_or_default(),
+            forbidden_distance: rule.forbidden_max_distance.unwrap_or(0),
+        }).collect();
+        Ok(toml::to_string(&TagRulesFile { rules })?)
+    }
+
+    fn build(regex_set: RegexSet, rules: Vec<TagRule>) -> Self {
+        Self {
+            regex_set,
+            rules,
+            min_confidence: 0.0,
+            cache: None,
+            cache_hits: AtomicUsize::new(0),
+            cache_misses: AtomicUsize::new(0),
+        }
+        .with_cache_capacity(DEFAULT_TAG_CACHE_CAPACITY)
+    }
+
+    /// Remembers the results for up to `capacity` distinct texts; 0 turns the cache off.
+    pub fn with_cache_capacity(mut self, capacity: usize) -> Self {
+        self.cache = NonZeroUsize::new(capacity).map(|c| Mutex::new(LruCache::new(c)));
+        self
+    }
+
+    /// Cache hits and misses so far; both stay 0 with the cache off.
+    pub fn cache_stats(&self) -> (usize, usize) {
+        (self.cache_hits.load(Ordering::Relaxed), self.cache_misses.load(Ordering::Relaxed))
+    }
+
+    /// Sets the threshold returned by `min_confidence`; everything is kept by default.
+    pub fn with_min_confidence(mut self, min_confidence: f32) -> Self {
+        self.min_confidence = min_confidence;
+        self
+    }
+
+    pub fn min_confidence(&self) -> f32 {
+        self.min_confidence
+    }
+
+    /// `detect_tags_scored` with title matches boosted by `TITLE_CONFIDENCE_BOOST`, capped at 1.0.
+    pub fn detect_tags_with_source(&self, text: &str, source: TagSource) -> Vec<TagMatch> {
+        let mut matches = self.detect_tags_scored(text);
+        if source == TagSource::Title {
+            for m in &mut matches {
+                m.confidence = (m.confidence * TITLE_CONFIDENCE_BOOST).min(CONFIDENCE_EXACT);
+            }
+        }
+        matches
     }
 
     pub fn detect_tags(&self, text: &str) -> Vec<&'static str> {
+        self.detect_tags_scored(text).into_iter().map(|m| m.tag).collect()
+    }
 
+    /// `detect_tags` for each text, spread across rayon's thread pool. Worth it
+    /// for thousands of texts; for a handful the sequential call is cheaper.
+    pub fn detect_tags_batch(&self, texts: &[&str]) -> Vec<Vec<&'static str>> {
+        texts.par_iter().map(|text| self.detect_tags(text)).collect()
+    }
+
+    /// Like `detect_tags`, but each tag carries a confidence based on how the
+    /// rule matched: exact keyword, keyword plus context, or keyword near context.
+    pub fn detect_tags_scored(&self, text: &str) -> Vec<TagMatch> {
+        let Some(cache) = &self.cache else { return self.match_rules(text) };
+        let mut hasher = FnvHasher::default();
+        hasher.write(text.as_bytes());
+        let key = hasher.finish();
+
+        if let Some(hit) = cache.lock().unwrap().get(&key) {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return hit.clone();
+        }
+        // Matched without the lock so other threads aren't held up
+        self.cache_misses.fetch_add(1, Ordering::Relaxed);
+        let matches = self.match_rules(text);
+        cache.lock().unwrap().put(key, matches.clone());
+        matches
+    }
+
+    fn match_rules(&self, text: &str) -> Vec<TagMatch> {
         let matches = self.regex_set.matches(text);
         
         matches.into_iter()
@@ -478,7 +694,7 @@ impl TagEngine {
                     }
                 }
                 
-                Some(rule.tag)
+                Some(TagMatch { tag: rule.tag, confidence: rule.confidence() })
             })
             .collect()
     }
@@ -517,18 +733,380 @@ fn count_words(s: &str) -> usize {
     count
 }
 
+// === Tag Hierarchy ===
+
+/// Parent tags implied by a detected tag (e.g. a React job needs JavaScript).
+pub struct TagHierarchy;
+
+impl TagHierarchy {
+    pub fn implies(tag: &str) -> &'static [&'static str] {
+        match tag {
+            "React" | "Vue" | "Svelte" | "Node.js" => &["JavaScript"],
+            "Angular" => &["TypeScript"],
+            "Next.js" => &["React"],
+            "Nuxt" => &["Vue"],
+            "Django" | "Flask" | "FastAPI" | "Pandas" | "NumPy" | "PyTorch" => &["Python"],
+            "Ruby on Rails" => &["Ruby"],
+            "Laravel" => &["PHP"],
+            "Spring" => &["Java"],
+            "PostgreSQL" | "SQL Server" => &["SQL"],
+            _ => &[],
+        }
+    }
+
+    /// Adds every (transitively) implied tag that isn't already present.
+    pub fn expand_implied(tags: &mut Vec<String>) {
+        let mut i = 0;
+        while i < tags.len() {
+            for &parent in Self::implies(&tags[i]) {
+                if !tags.iter().any(|t| t == parent) {
+                    tags.push(parent.to_string());
+                }
+            }
+            i += 1;
+        }
+    }
+}
+
+// === Paid Status Detection ===
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PaidStatus {
+    Paid,
+    Unpaid,
+    /// Both paid and unpaid signals were found.
+    Unknown,
+}
+
+impl PaidStatus {
+    pub fn tag(&self) -> Option<&'static str> {
+        match self {
+            Self::Paid => Some("Paid"),
+            Self::Unpaid => Some("Unpaid"),
+            Self::Unknown => None,
+        }
+    }
+}
+
+pub struct PaidStatusDetector {
+    unpaid_regex: regex::Regex,
+    paid_regex: regex::Regex,
+}
+
+impl Default for PaidStatusDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PaidStatusDetector {
+    pub fn new() -> Self {
+        let unpaid_regex = regex::RegexBuilder::new(
+            r"\bunpaid\b|\bnot paid\b|\bno (compensation|pay|salary|stipend)\b|\b(for )?academic credit only\b|\bvolunteer (role|position|opportunity|internship)\b|\bis a volunteer\b"
+        )
+        .case_insensitive(true)
+        .build()
+        .expect("Invalid unpaid regex");
+
+        let paid_regex = regex::RegexBuilder::new(
+            r"\bpaid (internship|role|position|opportunity)\b|\bcompetitive (salary|pay|compensation)\b|\bstipend\b|\b(salary|compensation|remuneration|hourly (rate|wage))\b|[$£€¥₹]\s?\d|\b(USD|GBP|EUR|AUD|CAD|NZD)\s?\d"
+        )
+        .case_insensitive(true)
+        .build()
+        .expect("Invalid paid regex");
+
+        Self { unpaid_regex, paid_regex }
+    }
+
+    /// `None` when the posting says nothing about pay.
+    pub fn detect(&self, title: &str, description: &str) -> Option<PaidStatus> {
+        let text = format!("{} {}", title, description);
+        let unpaid = self.unpaid_regex.is_match(&text);
+        // "No compensation" would otherwise also count as a paid signal
+        let remaining = self.unpaid_regex.replace_all(&text, " ");
+        let paid = self.paid_regex.is_match(&remaining);
+
+        match (paid, unpaid) {
+            (true, true) => Some(PaidStatus::Unknown),
+            (true, false) => Some(PaidStatus::Paid),
+            (false, true) => Some(PaidStatus::Unpaid),
+            (false, false) => None,
+        }
+    }
+}
+
+// === Application Requirements Detection ===
+
+/// What a posting asks applicants to send, e.g. "Cover Letter Required".
+/// Each item only counts next to a request ("please include", "submit",
+/// "required"), so passing mentions like "our portfolio companies" don't tag.
+pub struct ApplicationRequirementsDetector {
+    rules: Vec<(Regex, &'static str)>,
+    negation: Regex,
+}
+
+const REQUIREMENT_ITEMS: [(&str, &str); 5] = [
+    (r"cover (?:letter|note)s?", "Cover Letter Required"),
+    (r"portfolio(?: link| url| website| site)?", "Portfolio Required"),
+    (r"github(?: profile| account| link| url| username)?", "GitHub Required"),
+    (r"writing samples?", "Writing Sample Required"),
+    (r"references", "References Required"),
+];
+
+impl Default for ApplicationRequirementsDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ApplicationRequirementsDetector {
+    pub fn new() -> Self {
+        let before = r"\b(?:required|requires?|must (?:include|submit|provide|attach|send)|please (?:include|submit|attach|provide|send|share)|(?:submit|attach|include|provide|send|upload) (?:a|an|your|two|three)|along with|together with)\b";
+        let after = r"\b(?:is |are )?(?:required|mandatory|must be (?:included|submitted|attached|provided))\b";
+        let rules = REQUIREMENT_ITEMS.iter().map(|(item, tag)| {
+            // Stays within one sentence so "required" elsewhere doesn't count
+            let pattern = format!(r"(?i){before}[^.!?\n]{{0,60}}?\b{item}\b|\b{item}\b[^.!?\n]{{0,30}}?{after}");
+            (Regex::new(&pattern).expect("Invalid application requirement regex"), *tag)
+        }).collect();
+        let negation = Regex::new(r"(?i)\b(?:no|not|optional|unnecessary|welcome)\b|n't\b").unwrap();
+        Self { rules, negation }
+    }
+
+    pub fn detect(&self, description: &str) -> Vec<&'static str> {
+        let text = HTML_TAG.replace_all(description, " ");
+        self.rules.iter()
+            .filter(|(regex, _)| regex.find_iter(&text).any(|m| {
+                // "No cover letter required" negates from just before the match
+                let preceding = text[..m.start()].split_whitespace().last().unwrap_or("");
+                !self.negation.is_match(m.as_str())
+                    && !matches!(preceding.to_ascii_lowercase().as_str(), "no" | "not" | "without")
+            }))
+            .map(|(_, tag)| *tag)
+            .collect()
+    }
+}
+
+// === Benefits Extraction ===
+
+/// Canonical names for the benefits most postings list, checked in order.
+const BENEFIT_RULES: [(&str, &str); 9] = [
+    (r"\b(health|medical|dental|vision)\b.{0,30}\b(insurance|coverage|cover|care|plan|benefits)\b|\bhealth ?care\b", "Health Insurance"),
+    (r"\b401\s?\(?k\)?", "401(k)"),
+    (r"\bretirement (plan|savings|matching|contributions?)\b|\bpension\b|\bsuperannuation\b|\bkiwisaver\b", "Retirement Plan"),
+    (r"\bstock options?\b|\bequity\b|\brsus?\b|\besop\b|\bemployee stock\b|\bshare options?\b", "Stock Options"),
+    (r"\b(unlimited|flexible|uncapped)\b.{0,20}\b(pto|vacation|holidays?|time off|leave|annual leave)\b|\bunlimited pto\b", "Flexible PTO"),
+    (r"\bremote\b|\bwork from (home|anywhere)\b|\bwfh\b|\bhybrid\b", "Remote Work"),
+    (r"\bgym\b|\bfitness\b.{0,20}\b(membership|stipend|allowance|reimbursement)\b|\bwellness (stipend|allowance)\b", "Gym Membership"),
+    (r"\b(parental|maternity|paternity) leave\b", "Parental Leave"),
+    (r"\b(learning|education|training|development|conference) (budget|stipend|allowance)\b", "Learning Budget"),
+];
+
+/// A heading or bold line that opens a benefits section: "Benefits",
+/// "Perks & Benefits", "What we offer", "What you'll get".
+static BENEFITS_HEADING: Lazy<Regex> = Lazy::new(|| {
+    let heading = r"(?:our |the )?(?:perks (?:&amp;|&|and) )?(?:benefits|perks)(?: (?:&amp;|&|and) (?:perks|benefits))?|what (?:we|we'll|we will) offer(?: you)?|what you(?:'ll| will) get";
+    // Either its own heading or bold element, or a line of plain text
+    Regex::new(&format!(r"(?im)<(?:h[1-6]|strong|b|p)\b[^>]*>\s*(?:{heading})\s*:?\s*</(?:h[1-6]|strong|b|p)>|^\s*(?:{heading})\s*:?\s*$")).unwrap()
+});
+static NEXT_HEADING: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)<h[1-6][\s>]").unwrap());
+static LIST_ITEM: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?is)<li\b[^>]*>(.*?)</li>").unwrap());
+
+/// Longer list items are sentences about the company rather than a benefit.
+const MAX_BENEFIT_CHARS: usize = 80;
+
+/// Pulls the benefits list out of a description: the items under a
+/// "Benefits" or "What we offer" heading, with common ones mapped to a
+/// canonical name so "Comprehensive medical, dental & vision insurance" and
+/// "Health coverage" both become "Health Insurance".
+pub struct BenefitsExtractor {
+    rules: Vec<(Regex, &'static str)>,
+}
+
+impl Default for BenefitsExtractor {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl BenefitsExtractor {
+    pub fn new() -> Self {
+        let rules = BENEFIT_RULES.iter()
+            .map(|(pattern, name)| (Regex::new(&format!("(?i){}", pattern)).expect("Invalid benefit regex"), *name))
+            .collect();
+        Self { rules }
+    }
+
+    /// Empty when the description has no benefits section.
+    pub fn extract(&self, description: &str) -> Vec<String> {
+        let Some(heading) = BENEFITS_HEADING.find(description) else {
+            return Vec::new();
+        };
+        let rest = &description[heading.end()..];
+        let section = &rest[..NEXT_HEADING.find(rest).map_or(rest.len(), |m| m.start())];
+
+        let mut items: Vec<String> = LIST_ITEM.captures_iter(section)
+            .map(|caps| clean_item(&caps[1]))
+            .collect();
+        if items.is_empty() {
+            // Plain-text lists: one benefit per line or bullet
+            let lines = section.replace("<br>", "\n").replace("</p>", "\n");
+            let text = HTML_TAG.replace_all(&lines, " ");
+            items = text.split(['\n', '•'])
+                .map(|line| clean_item(line.trim().trim_start_matches(['-', '*']).trim()))
+                .collect();
+        }
+
+        let mut benefits: Vec<String> = Vec::new();
+        for item in items {
+            let benefit = match self.rules.iter().find(|(regex, _)| regex.is_match(&item)) {
+                Some((_, name)) => name.to_string(),
+                None if !item.is_empty() && item.chars().count() <= MAX_BENEFIT_CHARS => item,
+                None => continue,
+            };
+            if !benefits.contains(&benefit) {
+                benefits.push(benefit);
+            }
+        }
+        benefits
+    }
+}
+
+fn clean_item(html: &str) -> String {
+    let text = HTML_TAG.replace_all(html, " ").replace("&amp;", "&").replace("&nbsp;", " ");
+    let text = text.split_whitespace().collect::<Vec<_>>().join(" ");
+    text.trim_end_matches(['.', ';', ',']).to_string()
+}
+
+// === Seniority Detection ===
+
+/// Title keywords, most specific first: "Senior Associate" is senior and
+/// "Lead Generation Intern" is still an intern.
+static SENIORITY_KEYWORDS: Lazy<Vec<(Regex, SeniorityLevel)>> = Lazy::new(|| {
+    [
+        (r"\b(intern|internship|co-?op|apprentice(ship)?|placement|working student|summer (analyst|associate))\b", SeniorityLevel::Intern),
+        (r"\b(chief|c[etfoi]o|vp|svp|evp|vice president|president)\b", SeniorityLevel::Executive),
+        (r"\b(director|head of)\b", SeniorityLevel::Director),
+        (r"\b(principal|distinguished)\b", SeniorityLevel::Principal),
+        (r"\bstaff\b", SeniorityLevel::Staff),
+        (r"\b(lead|tech lead|team lead)\b", SeniorityLevel::Lead),
+        (r"\b(senior|sr|snr)\b", SeniorityLevel::Senior),
+    ].into_iter()
+        .map(|(pattern, level)| (Regex::new(&format!("(?i){}", pattern)).unwrap(), level))
+        .collect()
+});
+
+/// Keywords weaker than a level code: "Associate Engineer L4" is mid-level.
+static JUNIOR_KEYWORDS: Lazy<Vec<(Regex, SeniorityLevel)>> = Lazy::new(|| {
+    [
+        (r"\bassociate\b", SeniorityLevel::Associate),
+        (r"\b(junior|jr|entry[-\s]level|new[-\s]grad(uate)?|graduate|grad|early[-\s]career|trainee)\b", SeniorityLevel::EntryLevel),
+        (r"\b(mid[-\s]level|intermediate)\b", SeniorityLevel::MidLevel),
+    ].into_iter()
+        .map(|(pattern, level)| (Regex::new(&format!("(?i){}", pattern)).unwrap(), level))
+        .collect()
+});
+
+/// Company ladder codes: "L3" (Google), "IC4"/"E5" (Meta), "P2", "Level 5".
+static LEVEL_CODE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\b(l|ic|e|p|level\s?)-?(\d{1,2})\b").unwrap());
+/// Trailing roman numerals: "Software Engineer II". Case-sensitive so the word "I" doesn't count.
+static ROMAN_LEVEL: Lazy<Regex> = Lazy::new(|| Regex::new(r"\s(I{1,3}|IV)(\s*[-,(/|]|$)").unwrap());
+
+fn ladder_level(prefix: &str, n: u32) -> SeniorityLevel {
+    // P-ladders start a step higher than L/IC/E ladders, which begin at 3 for new grads
+    let n = if prefix.eq_ignore_ascii_case("p") { n + 2 } else { n };
+    match n {
+        0..=3 => SeniorityLevel::EntryLevel,
+        4 => SeniorityLevel::MidLevel,
+        5 => SeniorityLevel::Senior,
+        6 | 7 => SeniorityLevel::Staff,
+        _ => SeniorityLevel::Principal,
+    }
+}
+
+/// Seniority implied by a job title. Titles with no marker are `MidLevel`.
+pub fn detect_seniority(title: &str) -> SeniorityLevel {
+    if let Some((_, level)) = SENIORITY_KEYWORDS.iter().find(|(re, _)| re.is_match(title)) {
+        return *level;
+    }
+    if let Some(caps) = LEVEL_CODE.captures(title) {
+        if let Ok(n) = caps[2].parse() {
+            return ladder_level(caps[1].trim(), n);
+        }
+    }
+    if let Some((_, level)) = JUNIOR_KEYWORDS.iter().find(|(re, _)| re.is_match(title)) {
+        return *level;
+    }
+    match ROMAN_LEVEL.captures(title).and_then(|c| c.get(1)).map(|m| m.as_str()) {
+        Some("I") => SeniorityLevel::EntryLevel,
+        Some("III") => SeniorityLevel::Senior,
+        Some("IV") => SeniorityLevel::Staff,
+        _ => SeniorityLevel::MidLevel,
+    }
+}
+
+// === Description Quality ===
+
+const QUALITY_MIN_WORDS: usize = 50;
+const QUALITY_FULL_WORDS: usize = 500;
+
+static HTML_TAG: Lazy<Regex> = Lazy::new(|| Regex::new(r"<[^>]*>").unwrap());
+static SECTION_HEADING: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)<h[23][\s>]").unwrap());
+static REQUIREMENTS_SECTION: Lazy<Regex> = Lazy::new(|| {
+    Regex::new(r"(?i)\b(requirements|qualifications|what you('ll| will)? (need|bring)|about you|who you are|you have|must have)\b").unwrap()
+});
+static EEO_BOILERPLATE: Lazy<Regex> = Lazy::new(|| Regex::new(r"(?i)\bequal opportunity employer\b").unwrap());
+
+/// Word count and a 0.0-1.0 score for a cleaned HTML description.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DescriptionQuality {
+    pub word_count: usize,
+    pub score: f32,
+}
+
+/// Half the score is length (nothing under 50 words, full marks over 500),
+/// the rest is a section heading, a requirements section, and not being
+/// just an equal-opportunity statement.
+pub fn description_quality(html: &str) -> DescriptionQuality {
+    let text = HTML_TAG.replace_all(html, " ");
+    let word_count = text.split_whitespace().count();
+
+    let length = if word_count < QUALITY_MIN_WORDS {
+        0.0
+    } else {
+        ((word_count - QUALITY_MIN_WORDS) as f32 / (QUALITY_FULL_WORDS - QUALITY_MIN_WORDS) as f32).min(1.0)
+    };
+    let heading = SECTION_HEADING.is_match(html);
+    let requirements = REQUIREMENTS_SECTION.is_match(&text);
+    // An EEO line in a real description is fine; a short one that is mostly EEO isn't
+    let boilerplate_only = EEO_BOILERPLATE.is_match(&text) && word_count < QUALITY_MIN_WORDS * 2;
+
+    let score = 0.5 * length
+        + if heading { 0.2 } else { 0.0 }
+        + if requirements { 0.2 } else { 0.0 }
+        + if boilerplate_only || word_count == 0 { 0.0 } else { 0.1 };
+    DescriptionQuality { word_count, score }
+}
+
 // === Education Detection ===
 
 #[derive(Debug, Clone, Default, PartialEq, Eq)]
 pub struct EducationInfo {
     pub degree_levels: Vec<String>,
     pub subject_areas: Vec<String>,
+    /// Explicitly states a degree is required (not merely preferred).
+    pub degree_required: bool,
+    /// Explicitly states no degree is needed ("no degree required").
+    pub degree_not_required: bool,
 }
 
 pub struct EducationDetector {
     regex_set: regex::RegexSet,
     rules: Vec<EducationRule>,
     context_regex: regex::Regex,
+    required_regex: regex::Regex,
+    /// Words between a degree and "required" that undo the requirement.
+    hedge_regex: regex::Regex,
+    not_required_regex: regex::Regex,
 }
 
 struct EducationRule {
@@ -541,6 +1119,12 @@ enum EducationKind {
     Subject,
 }
 
+impl Default for EducationDetector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl EducationDetector {
     pub fn new() -> Self {
         let mut patterns = Vec::new();
@@ -649,19 +1233,54 @@ impl EducationDetector {
         .build()
         .expect("Invalid context regex");
 
+        let required_regex = regex::RegexBuilder::new(
+            r"\b(degree|bachelor'?s?|master'?s?|ph\.?d\.?)\b(?P<gap>[^.\n]{0,60})\b(required|mandatory)\b|\brequires? an? (\w+'?s? )?degree\b|\bmust (have|hold) an? (\w+'?s? )?degree\b"
+        )
+        .case_insensitive(true)
+        .build()
+        .expect("Invalid degree required regex");
+
+        let hedge_regex = regex::RegexBuilder::new(r"\b(not|preferred|nice to have)\b")
+            .case_insensitive(true)
+            .build()
+            .expect("Invalid degree hedge regex");
+
+        let not_required_regex = regex::RegexBuilder::new(
+            r"\bno (college |university |formal )?degree (is )?(required|necessary|needed)\b|\bdegree (is )?not (required|necessary|needed)\b|\b(we )?do(n'?t| not) require an? (\w+'?s? )?degree\b|\beducation level (is )?not important\b"
+        )
+        .case_insensitive(true)
+        .build()
+        .expect("Invalid degree not required regex");
+
         Self {
             regex_set,
             rules,
             context_regex,
+            required_regex,
+            hedge_regex,
+            not_required_regex,
         }
     }
 
+    /// "Degree ... required", unless the words in between hedge it, as in
+    /// "degree preferred, not required".
+    fn requires_degree(&self, text: &str) -> bool {
+        self.required_regex.captures_iter(text)
+            .any(|c| c.name("gap").is_none_or(|gap| !self.hedge_regex.is_match(gap.as_str())))
+    }
+
     pub fn detect(&self, text: &str) -> EducationInfo {
+        // "No degree required" also matches the required pattern, so it wins
+        let degree_not_required = self.not_required_regex.is_match(text);
+        let mut info = EducationInfo {
+            degree_not_required,
+            degree_required: !degree_not_required && self.requires_degree(text),
+            ..Default::default()
+        };
+
         if !self.context_regex.is_match(text) {
-            return EducationInfo::default();
+            return info;
         }
-
-        let mut info = EducationInfo::default();
         let matches = self.regex_set.matches(text);
 
         for index in matches {
@@ -741,7 +1360,6 @@ mod tests {
         let tags = engine.detect_tags(text);
         let tags_set: HashSet<_> = tags.iter().cloned().collect();
 
-        assert!(tags_set.contains("Paid"));
         assert!(tags_set.contains("LGBTQ+ Friendly"));
         assert!(tags_set.contains("Visa Sponsorship"));
         assert!(tags_set.contains("Remote"));
@@ -825,16 +1443,215 @@ mod tests {
             forbidden_max_distance: Some(1),
         });
         
-        let engine = TagEngine {
-            regex_set: RegexSet::new(patterns).unwrap(),
-            rules,
-        };
+        let engine = TagEngine::build(RegexSet::new(patterns).unwrap(), rules);
         
         assert!(engine.detect_tags("I know Java well.").contains(&"Java"));
         // "Java Script"
         assert!(!engine.detect_tags("I know Java Script.").contains(&"Java"));
     }
 
+    #[test]
+    fn test_detect_tags_batch_matches_sequential() {
+        let engine = TagEngine::new();
+        let texts = ["Rust Intern", "", "Python and React developer, hybrid in London", "Remote data internship"];
+        let batch = engine.detect_tags_batch(&texts);
+        let sequential: Vec<_> = texts.iter().map(|t| engine.detect_tags(t)).collect();
+        assert_eq!(batch, sequential);
+        assert!(engine.detect_tags_batch(&[]).is_empty());
+    }
+
+    #[test]
+    fn test_tag_cache_hits_repeated_text() {
+        let engine = TagEngine::new().with_cache_capacity(2);
+        let boilerplate = "We use Rust and Python on AWS.";
+        let first = engine.detect_tags_scored(boilerplate);
+        assert_eq!(engine.detect_tags_scored(boilerplate), first);
+        assert_eq!(engine.cache_stats(), (1, 1));
+
+        // The least recently used text is evicted once the cache is full
+        engine.detect_tags("Go developer");
+        engine.detect_tags("React intern");
+        engine.detect_tags(boilerplate);
+        assert_eq!(engine.cache_stats(), (1, 4));
+
+        let uncached = TagEngine::new().with_cache_capacity(0);
+        assert_eq!(uncached.detect_tags_scored(boilerplate), first);
+        assert_eq!(uncached.cache_stats(), (0, 0));
+    }
+
+    #[test]
+    fn test_toml_round_trip() {
+        let engine = TagEngine::new();
+        let toml = engine.to_toml().unwrap();
+        let loaded = TagEngine::from_toml(&toml).unwrap();
+
+        assert_eq!(loaded.rules.len(), engine.rules.len());
+        for (a, b) in engine.rules.iter().zip(&loaded.rules) {
+            assert_eq!(a.regex.as_str(), b.regex.as_str());
+            assert_eq!(a.tag, b.tag);
+            assert_eq!(a.context.as_ref().map(|r| r.as_str()), b.context.as_ref().map(|r| r.as_str()));
+            assert_eq!(a.max_word_distance, b.max_word_distance);
+            assert_eq!(a.forbidden_context.as_ref().map(|r| r.as_str()), b.forbidden_context.as_ref().map(|r| r.as_str()));
+            assert_eq!(a.forbidden_max_distance, b.forbidden_max_distance);
+        }
+
+        let text = "Rust developer with Go programming language experience, SEO optimization, and a salary of $90k.";
+        assert_eq!(engine.detect_tags(text), loaded.detect_tags(text));
+    }
+
+    #[test]
+    fn test_from_toml_custom_rules() {
+        let engine = TagEngine::from_toml(r#"
+            [[rules]]
+            pattern = "(?i)\\brust\\b"
+            tag = "Rust"
+
+            [[rules]]
+            pattern = "(?i)\\bgo\\b"
+            tag = "Go"
+            context = "(?i)\\blanguage\\b"
+            distance = 5
+
+            [[rules]]
+            pattern = "(?i)\\bjava\\b"
+            tag = "Java"
+            forbidden = "(?i)\\bscript\\b"
+            forbidden_distance = 1
+        "#).unwrap();
+
+        assert_eq!(engine.detect_tags("Rust and Python"), vec!["Rust"]);
+        assert!(engine.detect_tags("The Go programming language").contains(&"Go"));
+        assert!(!engine.detect_tags("We go fast").contains(&"Go"));
+        assert!(engine.detect_tags("I know Java well.").contains(&"Java"));
+        assert!(!engine.detect_tags("I know Java Script.").contains(&"Java"));
+
+        assert!(TagEngine::from_toml("[[rules]]\npattern = \"(\"\ntag = \"Broken\"").is_err());
+
+        // Patterns without an inline (?i) still match any case
+        let engine = TagEngine::from_toml(r#"
+            [[rules]]
+            pattern = "\\brust\\b"
+            tag = "Rust"
+        "#).unwrap();
+        assert_eq!(engine.detect_tags("Experience with Rust required"), vec!["Rust"]);
+    }
+
+    #[test]
+    fn test_detect_tags_scored() {
+        let engine = TagEngine::from_toml(r#"
+            [[rules]]
+            pattern = '(?i)\brust\b'
+            tag = "Rust"
+
+            [[rules]]
+            pattern = '(?i)\bgo\b'
+            tag = "Go"
+            context = '(?i)\blanguage\b'
+            distance = 5
+
+            [[rules]]
+            pattern = '(?i)\bswift\b'
+            tag = "Swift"
+            context = '(?i)\bios\b'
+
+            [[rules]]
+            pattern = '(?i)\bjava\b'
+            tag = "Java"
+            forbidden = '(?i)\bscript\b'
+            forbidden_distance = 1
+        "#).unwrap();
+
+        let scored = engine.detect_tags_scored("Rust and the Go language, Swift for iOS, plus Java.");
+        let score = |tag: &str| scored.iter().find(|m| m.tag == tag).map(|m| m.confidence);
+
+        assert_eq!(score("Rust"), Some(1.0));
+        assert_eq!(score("Swift"), Some(0.8));
+        assert_eq!(score("Java"), Some(0.8));
+        assert_eq!(score("Go"), Some(0.5));
+    }
+
+    #[test]
+    fn test_detect_tags_with_source() {
+        let engine = TagEngine::from_toml(r#"
+            [[rules]]
+            pattern = '(?i)\brust\b'
+            tag = "Rust"
+
+            [[rules]]
+            pattern = '(?i)\bgo\b'
+            tag = "Go"
+            context = '(?i)\blanguage\b'
+            distance = 5
+        "#).unwrap();
+        let text = "Rust and the Go language";
+        let score = |source| engine.detect_tags_with_source(text, source).iter()
+            .map(|m| (m.tag, m.confidence))
+            .collect::<Vec<_>>();
+
+        assert_eq!(score(TagSource::Description), vec![("Rust", 1.0), ("Go", 0.5)]);
+        // Boosted, but never above 1.0
+        assert_eq!(score(TagSource::Title), vec![("Rust", 1.0), ("Go", 0.75)]);
+        assert_eq!(engine.with_min_confidence(0.6).min_confidence(), 0.6);
+    }
+
+    #[test]
+    fn test_description_quality() {
+        let filler = |n: usize| vec!["word"; n].join(" ");
+
+        let empty = description_quality("");
+        assert_eq!(empty, DescriptionQuality { word_count: 0, score: 0.0 });
+
+        let eeo = description_quality("<p>Acme is an equal opportunity employer.</p>");
+        assert_eq!(eeo.word_count, 6);
+        assert_eq!(eeo.score, 0.0);
+
+        let short = description_quality("<p>Join our team and build things.</p>");
+        assert!((short.score - 0.1).abs() < 1e-6, "{:?}", short);
+
+        let mid = description_quality(&format!("<p>{}</p>", filler(275)));
+        assert_eq!(mid.word_count, 275);
+        assert!((mid.score - 0.35).abs() < 1e-6, "{:?}", mid);
+
+        let full = description_quality(&format!(
+            "<h2>About the role</h2><p>{}</p><h3>Requirements</h3><ul><li>Python</li></ul><p>We are an equal opportunity employer.</p>",
+            filler(600),
+        ));
+        assert!(full.word_count > 600);
+        assert!((full.score - 1.0).abs() < 1e-6, "{:?}", full);
+    }
+
+    #[test]
+    fn test_detect_tags_delegates_to_scored() {
+        let engine = TagEngine::new();
+        let text = "Senior Rust engineer with Python, SEO specialist experience and a golang background";
+        let scored = engine.detect_tags_scored(text);
+
+        assert!(scored.iter().all(|m| (0.0..=1.0).contains(&m.confidence)));
+        let tags: Vec<_> = scored.iter().map(|m| m.tag).collect();
+        assert_eq!(tags, engine.detect_tags(text));
+    }
+
+    #[test]
+    fn test_tag_hierarchy_implied() {
+        let mut tags = vec!["Next.js".to_string(), "Django".to_string()];
+        TagHierarchy::expand_implied(&mut tags);
+
+        assert!(tags.contains(&"React".to_string()));
+        assert!(tags.contains(&"JavaScript".to_string()));
+        assert!(tags.contains(&"Python".to_string()));
+        assert!(TagHierarchy::implies("Rust").is_empty());
+    }
+
+    #[test]
+    fn test_tag_hierarchy_no_duplicates() {
+        let mut tags = vec!["React".to_string(), "Vue".to_string(), "JavaScript".to_string(), "Next.js".to_string()];
+        TagHierarchy::expand_implied(&mut tags);
+
+        assert_eq!(tags.iter().filter(|t| *t == "JavaScript").count(), 1);
+        assert_eq!(tags.iter().filter(|t| *t == "React").count(), 1);
+        assert_eq!(tags.len(), 4);
+    }
+
     // === Education Detection Tests ===
 
     #[test]
@@ -919,6 +1736,46 @@ mod tests {
         assert_eq!(info, EducationInfo::default());
     }
 
+    #[test]
+    fn test_education_degree_not_required() {
+        let detector = EducationDetector::new();
+
+        for text in [
+            "No degree required, just a passion for learning.",
+            "A university degree is not required for this role.",
+            "We do not require a degree.",
+            "Education level not important - show us your portfolio.",
+        ] {
+            let info = detector.detect(text);
+            assert!(info.degree_not_required, "{}", text);
+            assert!(!info.degree_required, "{}", text);
+        }
+    }
+
+    #[test]
+    fn test_education_degree_required_vs_preferred() {
+        let detector = EducationDetector::new();
+
+        let info = detector.detect("A Bachelor's degree in Computer Science is required.");
+        assert!(info.degree_required);
+        assert!(!info.degree_not_required);
+        assert_eq!(info.degree_levels, vec!["Bachelor's"]);
+
+        let info = detector.detect("This role requires a master's degree.");
+        assert!(info.degree_required);
+
+        let info = detector.detect("Bachelor's degree in Computer Science preferred.");
+        assert!(!info.degree_required);
+        assert!(!info.degree_not_required);
+        assert_eq!(info.degree_levels, vec!["Bachelor's"]);
+
+        for text in ["Bachelor's degree preferred, not required.", "A degree not required for this role."] {
+            assert!(!detector.requires_degree(text), "{}", text);
+            assert!(!detector.detect(text).degree_required, "{}", text);
+        }
+        assert!(detector.requires_degree("Degree in Computer Science or a related field required."));
+    }
+
     #[test]
     fn test_telehealth_tags() {
         let engine = TagEngine::new();
@@ -1120,18 +1977,214 @@ mod tests {
         assert!(set.contains("Backend"));
         assert!(set.contains("React"));
         assert!(set.contains("Node.js"));
+    }
 
-        // Paid Smart Tag
-        let tags = engine.detect_tags("Salary: $100,000 per annum.");
-        assert!(tags.contains(&"Paid"));
-        
-        let tags = engine.detect_tags("Hourly rate of $50/hr.");
-        assert!(tags.contains(&"Paid"));
-        
-        let tags = engine.detect_tags("Compensation: USD 80k.");
-        assert!(tags.contains(&"Paid"));
+    // === Paid Status Tests ===
+
+    #[test]
+    fn test_paid_status_paid() {
+        let detector = PaidStatusDetector::new();
+        for desc in [
+            "Paid internship. LGBTQ+ friendly. Visa sponsorship.",
+            "Salary: $100,000 per annum.",
+            "Hourly rate of $50/hr.",
+            "Compensation: USD 80k.",
+            "We offer a competitive salary and a monthly stipend for interns.",
+            "The pay range for this role is £28,000 - £32,000.",
+        ] {
+            assert_eq!(detector.detect("Software Intern", desc), Some(PaidStatus::Paid), "{}", desc);
+        }
+        assert_eq!(detector.detect("Paid Internship - Marketing", ""), Some(PaidStatus::Paid));
+    }
+
+    #[test]
+    fn test_paid_status_unpaid() {
+        let detector = PaidStatusDetector::new();
+        for desc in [
+            "This is an unpaid internship based in our Berlin office.",
+            "Please note this is a volunteer position with flexible hours.",
+            "There is no compensation for this role; it is offered for academic credit only.",
+        ] {
+            assert_eq!(detector.detect("Marketing Intern", desc), Some(PaidStatus::Unpaid), "{}", desc);
+        }
+    }
+
+    #[test]
+    fn test_paid_status_unknown_and_none() {
+        let detector = PaidStatusDetector::new();
+        assert_eq!(detector.detect("Research Intern", "Unpaid, but a $500 travel stipend is provided."), Some(PaidStatus::Unknown));
+        assert_eq!(detector.detect("Research Intern", "We pay well."), None);
+        assert_eq!(detector.detect("Research Intern", "You will help our teams volunteer at community events."), None);
+    }
+
+    // === Application Requirements Tests ===
 
-        let tags = engine.detect_tags("We pay well.");
-        assert!(!tags.contains(&"Paid")); // No symbol
+    #[test]
+    fn test_application_requirements_detected() {
+        let detector = ApplicationRequirementsDetector::new();
+        let cases = [
+            ("<p>Please include a cover letter explaining your interest.</p>", vec!["Cover Letter Required"]),
+            ("A portfolio link is required for all design roles.", vec!["Portfolio Required"]),
+            ("Submit your resume along with your GitHub profile.", vec!["GitHub Required"]),
+            ("Applicants must submit a writing sample and two references.", vec!["Writing Sample Required", "References Required"]),
+            ("Cover letters are mandatory. Please provide three references.", vec!["Cover Letter Required", "References Required"]),
+        ];
+        for (desc, expected) in cases {
+            assert_eq!(detector.detect(desc), expected, "{}", desc);
+        }
+    }
+
+    #[test]
+    fn test_application_requirements_ignored() {
+        let detector = ApplicationRequirementsDetector::new();
+        for desc in [
+            "You will work with our portfolio companies on growth.",
+            "We host everything on GitHub and review code daily.",
+            "No cover letter required.",
+            "A cover letter is optional but welcome.",
+            "Cover letter not required. Experience with Python is required.",
+            "Check the references section of our docs.",
+        ] {
+            assert!(detector.detect(desc).is_empty(), "{}", desc);
+        }
+    }
+
+    // === Benefits Extraction Tests ===
+
+    #[test]
+    fn test_benefits_canonical_phrasings() {
+        let extractor = BenefitsExtractor::new();
+        let cases = [
+            ("Comprehensive medical, dental & vision insurance", "Health Insurance"),
+            ("Private healthcare", "Health Insurance"),
+            ("401(k) with 4% company match", "401(k)"),
+            ("401k matching", "401(k)"),
+            ("Generous pension contributions", "Retirement Plan"),
+            ("KiwiSaver employer contributions", "Retirement Plan"),
+            ("Superannuation on top of salary", "Retirement Plan"),
+            ("Stock options in a fast-growing startup", "Stock Options"),
+            ("Meaningful equity", "Stock Options"),
+            ("RSUs", "Stock Options"),
+            ("Unlimited PTO", "Flexible PTO"),
+            ("Flexible vacation policy", "Flexible PTO"),
+            ("Work from home two days a week", "Remote Work"),
+            ("Fully remote team", "Remote Work"),
+            ("Free gym membership", "Gym Membership"),
+            ("Monthly fitness stipend", "Gym Membership"),
+            ("16 weeks paid parental leave", "Parental Leave"),
+            ("$1,000 annual learning budget", "Learning Budget"),
+        ];
+        for (item, expected) in cases {
+            let desc = format!("<h3>Benefits</h3><ul><li>{}</li></ul>", item);
+            assert_eq!(extractor.extract(&desc), vec![expected], "{}", item);
+        }
+    }
+
+    #[test]
+    fn test_benefits_section_bounds() {
+        let extractor = BenefitsExtractor::new();
+        let desc = "<p>Join our remote-friendly team.</p>\
+            <h3>Requirements</h3><ul><li>Rust experience</li></ul>\
+            <p><strong>What we offer:</strong></p>\
+            <ul><li>Health &amp; dental insurance</li><li>Dental plan</li><li>Free lunch on Fridays.</li></ul>\
+            <h3>About us</h3><ul><li>Hybrid office in Auckland</li></ul>";
+        // Only the offer list counts, duplicates collapse and uncommon perks keep their text
+        assert_eq!(extractor.extract(desc), vec!["Health Insurance", "Free lunch on Fridays"]);
+
+        let plain = "<p>Perks &amp; Benefits</p><p>- Unlimited time off<br>- Share options</p>";
+        assert_eq!(extractor.extract(plain), vec!["Flexible PTO", "Stock Options"]);
+
+        assert!(extractor.extract("<p>Great benefits and a remote-first culture.</p>").is_empty());
+    }
+
+    #[test]
+    fn test_detect_seniority() {
+        use SeniorityLevel::*;
+        let cases = [
+            ("Software Engineering Intern, Summer 2025", Intern),
+            ("Co-op Student - Data Engineering", Intern),
+            ("Senior Software Engineer Intern", Intern),
+            ("Lead Generation Intern", Intern),
+            ("Summer Analyst - Investment Banking", Intern),
+            ("New Grad Software Engineer", EntryLevel),
+            ("Software Engineer, New Graduate 2025", EntryLevel),
+            ("Graduate Data Scientist", EntryLevel),
+            ("Junior Frontend Developer", EntryLevel),
+            ("Jr. QA Analyst", EntryLevel),
+            ("Entry-Level Accountant", EntryLevel),
+            ("Early Career Product Manager", EntryLevel),
+            ("Software Engineer (L3)", EntryLevel),
+            ("Software Engineer, IC3", EntryLevel),
+            ("Software Engineer I", EntryLevel),
+            ("Associate Product Manager", Associate),
+            ("Software Engineer L4", MidLevel),
+            ("Machine Learning Engineer - IC4", MidLevel),
+            ("Backend Engineer P2", MidLevel),
+            ("Data Engineer II - Payments", MidLevel),
+            ("Software Engineer", MidLevel),
+            ("Senior Associate, Risk", Senior),
+            ("Sr. Site Reliability Engineer", Senior),
+            ("Software Engineer III", Senior),
+            ("Production Engineer E5", Senior),
+            ("Staff Software Engineer", Staff),
+            ("Software Engineer IV", Staff),
+            ("Principal Architect", Principal),
+            ("Tech Lead, Payments", Lead),
+            ("Associate Director of Engineering", Director),
+            ("Head of Growth", Director),
+            ("VP, Engineering", Executive),
+            ("Chief Technology Officer", Executive),
+        ];
+        for (title, expected) in cases {
+            assert_eq!(detect_seniority(title), expected, "{}", title);
+        }
+        // A lone "I" in a sentence isn't a level
+        assert_eq!(detect_seniority("What I Do: Support Engineer"), MidLevel);
+    }
+
+    // === Property Tests ===
+
+    mod properties {
+        use super::*;
+        use once_cell::sync::Lazy;
+        use proptest::prelude::*;
+
+        static ENGINE: Lazy<TagEngine> = Lazy::new(TagEngine::new);
+
+        const TECH_TAGS: &[&str] = &[
+            "Rust", "Python", "JavaScript", "TypeScript", "Go", "Java", "C++", "C#", "Ruby", "PHP",
+            "Swift", "Kotlin", "Scala", "React", "Vue", "Angular", "Node.js", ".NET", "Docker",
+            "Kubernetes", "AWS", "Azure", "GCP", "Linux", "Git", "SQL",
+        ];
+
+        proptest! {
+            #[test]
+            fn detect_tags_never_panics(text in any::<String>()) {
+                ENGINE.detect_tags(&text);
+            }
+
+            #[test]
+            fn detect_tags_is_idempotent(text in any::<String>()) {
+                prop_assert_eq!(ENGINE.detect_tags(&text), ENGINE.detect_tags(&text));
+            }
+
+            #[test]
+            fn javascript_is_always_tagged(before in any::<String>(), after in any::<String>()) {
+                // Spaces keep arbitrary neighbours from gluing onto the word
+                let text = format!("{} JavaScript {}", before, after);
+                prop_assert!(ENGINE.detect_tags(&text).contains(&"JavaScript"));
+            }
+
+            #[test]
+            fn no_tech_tags_without_letters(text in any::<String>().prop_map(|s| s.chars().filter(|c| !c.is_alphabetic()).collect::<String>())) {
+                let tags = ENGINE.detect_tags(&text);
+                prop_assert!(!tags.iter().any(|t| TECH_TAGS.contains(t)), "{:?} -> {:?}", text, tags);
+            }
+        }
+
+        #[test]
+        fn empty_string_has_no_tags() {
+            assert!(ENGINE.detect_tags("").is_empty());
+        }
     }
 }
@@ -0,0 +1,34 @@
+use anyhow::{Context, Result};
+use opentelemetry::trace::TracerProvider as _;
+use opentelemetry_sdk::Resource;
+use opentelemetry_sdk::trace::{SdkTracer, SdkTracerProvider};
+use tracing_opentelemetry::OpenTelemetryLayer;
+use tracing_subscriber::registry::LookupSpan;
+
+const OTLP_ENDPOINT_VAR: &str = "OTEL_EXPORTER_OTLP_ENDPOINT";
+
+/// Starts an OTLP (HTTP) span exporter when `OTEL_EXPORTER_OTLP_ENDPOINT` is set.
+/// The exporter reads the endpoint and any other `OTEL_*` settings itself.
+pub fn init_tracer_provider() -> Result<Option<SdkTracerProvider>> {
+    if std::env::var(OTLP_ENDPOINT_VAR).map_or(true, |v| v.trim().is_empty()) {
+        return Ok(None);
+    }
+    let exporter = opentelemetry_otlp::SpanExporter::builder()
+        .with_http()
+        .build()
+        .context("Failed to create OTLP exporter")?;
+    Ok(Some(
+        SdkTracerProvider::builder()
+            .with_batch_exporter(exporter)
+            .with_resource(Resource::builder().with_service_name("zapply").build())
+            .build(),
+    ))
+}
+
+/// Layer forwarding spans to the provider; `None` (a no-op layer) without one.
+pub fn otel_layer<S>(provider: Option<&SdkTracerProvider>) -> Option<OpenTelemetryLayer<S, SdkTracer>>
+where
+    S: tracing::Subscriber + for<'span> LookupSpan<'span>,
+{
+    provider.map(|p| tracing_opentelemetry::layer().with_tracer(p.tracer("zapply")))
+}
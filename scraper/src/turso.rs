@@ -0,0 +1,194 @@
+use anyhow::{Context, Result, bail};
+use serde_json::{Value, json};
+use std::collections::{HashMap, HashSet};
+
+use crate::{DbQuery, JOB_FINGERPRINTS_SQL, JobDb, fingerprint_row, geo_queries};
+
+/// Statements per pipeline request, matching the D1 backend's chunking.
+const TURSO_BATCH_SIZE: usize = 50;
+
+/// Writes to a Turso (libSQL) database over its HTTP API (`--turso`). The
+/// schema is the same as D1's, so `db/schema.sql` applies unchanged.
+pub struct TursoDb {
+    client: reqwest::Client,
+    /// `https://<db>-<org>.turso.io`, or a mock server in tests.
+    url: String,
+    auth_token: String,
+}
+
+impl TursoDb {
+    /// `libsql://` URLs, as printed by `turso db show`, are sent over HTTPS.
+    pub fn new(database_url: &str, auth_token: &str) -> Self {
+        let url = match database_url.strip_prefix("libsql://") {
+            Some(host) => format!("https://{}", host),
+            None => database_url.to_string(),
+        };
+        Self {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_string(),
+            auth_token: auth_token.to_string(),
+        }
+    }
+
+    pub fn from_env() -> Result<Self> {
+        let url = std::env::var("TURSO_DATABASE_URL").context("TURSO_DATABASE_URL not set")?;
+        let token = std::env::var("TURSO_AUTH_TOKEN").context("TURSO_AUTH_TOKEN not set")?;
+        Ok(Self::new(&url, &token))
+    }
+
+    /// Rows of one SELECT, as arrays of typed values in SELECT column order.
+    async fn query_rows(&self, sql: &str, context: &str) -> Result<Vec<Value>> {
+        let query = DbQuery { sql: sql.to_string(), params: vec![], context: Some(context.to_string()) };
+        let results = self.pipeline(&[query]).await?;
+        Ok(results.first().and_then(|r| r["rows"].as_array()).cloned().unwrap_or_default())
+    }
+
+    /// Runs the statements in order on one connection and returns each
+    /// statement's result, failing on the first statement that errored.
+    async fn pipeline(&self, queries: &[DbQuery]) -> Result<Vec<Value>> {
+        let requests: Vec<Value> = queries.iter()
+            .map(|q| json!({ "type": "execute", "stmt": statement(q) }))
+            .chain(std::iter::once(json!({ "type": "close" })))
+            .collect();
+        let resp = self.client.post(format!("{}/v2/pipeline", self.url))
+            .bearer_auth(&self.auth_token)
+            .json(&json!({ "requests": requests }))
+            .send()
+            .await?;
+        if !resp.status().is_success() {
+            let status = resp.status();
+            let text = resp.text().await?;
+            bail!("Turso API Error ({}): {}", status, text);
+        }
+
+        let data: Value = resp.json().await?;
+        let results = data["results"].as_array().context("Turso response has no results")?;
+        let mut responses = Vec::with_capacity(queries.len());
+        for (query, result) in queries.iter().zip(results) {
+            if result["type"] != "ok" {
+                let message = result["error"]["message"].as_str().unwrap_or("unknown error");
+                bail!("Turso API Error ({}): {}", query.context.as_deref().unwrap_or("unlabelled query"), message);
+            }
+            responses.push(result["response"]["result"].clone());
+        }
+        Ok(responses)
+    }
+}
+
+/// A `DbQuery` as a Hrana statement. `?N` placeholders are bound by position,
+/// so the params go through as typed args rather than being inlined.
+fn statement(query: &DbQuery) -> Value {
+    let args: Vec<Value> = query.params.iter().map(|param| match param {
+        Value::Null => json!({ "type": "null" }),
+        Value::Bool(b) => json!({ "type": "integer", "value": if *b { "1" } else { "0" } }),
+        Value::Number(n) if n.is_f64() => json!({ "type": "float", "value": n.as_f64() }),
+        // Integers are strings so values past 2^53 survive
+        Value::Number(n) => json!({ "type": "integer", "value": n.to_string() }),
+        Value::String(s) => json!({ "type": "text", "value": s }),
+        other => json!({ "type": "text", "value": other.to_string() }),
+    }).collect();
+    json!({ "sql": query.sql, "args": args })
+}
+
+#[async_trait::async_trait]
+impl JobDb for TursoDb {
+    async fn execute_batch(&self, queries: &[DbQuery]) -> Result<()> {
+        for chunk in queries.chunks(TURSO_BATCH_SIZE) {
+            self.pipeline(chunk).await?;
+        }
+        Ok(())
+    }
+
+    async fn get_existing_ids(&self) -> Result<HashSet<String>> {
+        let rows = self.query_rows("SELECT id FROM jobs", "existing_ids").await?;
+        Ok(rows.iter().filter_map(|row| row[0]["value"].as_str().map(String::from)).collect())
+    }
+
+    async fn get_job_fingerprints(&self) -> Result<HashMap<String, String>> {
+        let rows = self.query_rows(JOB_FINGERPRINTS_SQL, "job_fingerprints").await?;
+        Ok(rows.iter().filter_map(|row| {
+            Some(fingerprint_row(row[0]["value"].as_str()?, row[1]["value"].as_str(), row[2]["value"].as_str(), row[3]["value"].as_str()))
+        }).collect())
+    }
+
+    async fn initialize_geo_tables(&self, countries: &HashMap<String, String>, regions: &HashMap<String, String>) -> Result<()> {
+        self.execute_batch(&geo_queries(countries, regions)).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{body_partial_json, header, method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn ok(result: Value) -> Value {
+        json!({ "type": "ok", "response": { "type": "execute", "result": result } })
+    }
+
+    #[tokio::test]
+    async fn test_execute_batch_sends_typed_args() {
+        let server = MockServer::start().await;
+        let expected = json!({ "requests": [
+            { "type": "execute", "stmt": { "sql": "INSERT INTO jobs (id, description_length, description_quality_score, city) VALUES (?1, ?2, ?3, ?4)", "args": [
+                { "type": "text", "value": "lever-1" },
+                { "type": "integer", "value": "120" },
+                { "type": "float", "value": 0.5 },
+                { "type": "null" },
+            ] } },
+            { "type": "close" },
+        ] });
+        Mock::given(method("POST")).and(path("/v2/pipeline"))
+            .and(header("authorization", "Bearer secret"))
+            .and(body_partial_json(expected))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "results": [ok(json!({})), { "type": "ok", "response": { "type": "close" } }] })))
+            .expect(1)
+            .mount(&server).await;
+
+        let db = TursoDb::new(&format!("{}/", server.uri()), "secret");
+        let query = DbQuery {
+            sql: "INSERT INTO jobs (id, description_length, description_quality_score, city) VALUES (?1, ?2, ?3, ?4)".to_string(),
+            params: vec![Value::from("lever-1"), Value::from(120), Value::from(0.5), Value::Null],
+            context: Some("lever:acme:job_upsert".to_string()),
+        };
+        db.execute_batch(&[query]).await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn test_statement_errors_and_existing_jobs() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST")).and(path("/v2/pipeline"))
+            .and(body_partial_json(json!({ "requests": [{ "stmt": { "sql": JOB_FINGERPRINTS_SQL } }] })))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "results": [ok(json!({
+                "cols": [{ "name": "id" }, { "name": "title" }, { "name": "location" }, { "name": "description_hash" }],
+                "rows": [
+                    [{ "type": "text", "value": "lever-1" }, { "type": "text", "value": "Intern" }, { "type": "text", "value": "Remote" }, { "type": "text", "value": "abc" }],
+                    [{ "type": "text", "value": "ashby-2" }, { "type": "text", "value": "Intern" }, { "type": "text", "value": "" }, { "type": "null" }],
+                ],
+            }))] })))
+            .mount(&server).await;
+        Mock::given(method("POST")).and(path("/v2/pipeline"))
+            .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "results": [
+                ok(json!({})),
+                { "type": "error", "error": { "message": "SQLITE_CONSTRAINT: NOT NULL constraint failed: jobs.title", "code": "SQLITE_CONSTRAINT" } },
+            ] })))
+            .mount(&server).await;
+
+        let db = TursoDb::new(&server.uri(), "secret");
+        let jobs = db.get_job_fingerprints().await.unwrap();
+        assert_eq!(jobs, HashMap::from([
+            ("lever-1".to_string(), crate::utils::job_fingerprint("Intern", "Remote", "abc")),
+            ("ashby-2".to_string(), crate::utils::job_fingerprint("Intern", "", "")),
+        ]));
+
+        let query = |context: &str| DbQuery { sql: "INSERT INTO jobs (id) VALUES (?1)".to_string(), params: vec![Value::from("x")], context: Some(context.to_string()) };
+        let err = db.execute_batch(&[query("lever:acme:job_tags"), query("lever:acme:job_upsert")]).await.unwrap_err().to_string();
+        assert!(err.contains("lever:acme:job_upsert") && err.contains("NOT NULL"), "{}", err);
+    }
+
+    #[test]
+    fn test_libsql_url_uses_https() {
+        assert_eq!(TursoDb::new("libsql://zapply-acme.turso.io", "t").url, "https://zapply-acme.turso.io");
+        assert_eq!(TursoDb::new("http://127.0.0.1:8080/", "t").url, "http://127.0.0.1:8080");
+    }
+}
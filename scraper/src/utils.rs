@@ -0,0 +1,67 @@
+use sha2::{Digest, Sha256};
+
+/// Bare lowercase host for a company's `domain`, however it was written in
+/// `slugs.json`: "https://www.stripe.com/" -> "stripe.com". `None` when what's
+/// left doesn't look like a domain.
+pub fn normalize_domain(raw: &str) -> Option<String> {
+    let lower = raw.trim().to_lowercase();
+    let without_scheme = lower.strip_prefix("https://")
+        .or_else(|| lower.strip_prefix("http://"))
+        .unwrap_or(&lower);
+    let host = without_scheme.strip_prefix("www.").unwrap_or(without_scheme);
+    // Anything after the host ("/careers", "?ref=x") isn't part of the domain
+    let host = host.split(['/', '?', '#']).next().unwrap_or_default();
+
+    let valid = host.contains('.')
+        && !host.starts_with('.')
+        && !host.ends_with('.')
+        && !host.contains("..")
+        && host.chars().all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '-');
+    valid.then(|| host.to_string())
+}
+
+/// Lowercase hex SHA-256, e.g. for `Job::description_hash`.
+pub fn sha256_hex(text: &str) -> String {
+    format!("{:x}", Sha256::digest(text.as_bytes()))
+}
+
+/// Hash of the fields archived in `job_history`, so the batch consumer can
+/// tell a changed job from one it only has to mark seen.
+pub fn job_fingerprint(title: &str, location: &str, description_hash: &str) -> String {
+    sha256_hex(&format!("{}\n{}\n{}", title, location, description_hash))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize_domain_variants() {
+        for raw in [
+            "stripe.com",
+            "www.stripe.com",
+            "https://stripe.com",
+            "https://www.stripe.com/",
+            "http://www.stripe.com",
+            "  HTTPS://Stripe.com/  ",
+            "https://stripe.com/jobs?ref=zapply",
+        ] {
+            assert_eq!(normalize_domain(raw).as_deref(), Some("stripe.com"), "{}", raw);
+        }
+        assert_eq!(normalize_domain("https://jobs.air-nz.co.nz").as_deref(), Some("jobs.air-nz.co.nz"));
+    }
+
+    #[test]
+    fn test_normalize_domain_rejects_non_domains() {
+        for raw in ["", "stripe", "https://", "www.", "Stripe Inc.com", "stripe..com", ".stripe.com", "mailto:jobs@stripe.com"] {
+            assert_eq!(normalize_domain(raw), None, "{}", raw);
+        }
+    }
+
+    #[test]
+    fn test_sha256_hex() {
+        assert_eq!(sha256_hex(""), "e3b0c44298fc1c149afbf4c8996fb92427ae41e4649b934ca495991b7852b855");
+        assert_eq!(sha256_hex("<p>Intern</p>").len(), 64);
+        assert_ne!(sha256_hex("<p>Intern</p>"), sha256_hex("<p>Intern.</p>"));
+    }
+}
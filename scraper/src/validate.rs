@@ -0,0 +1,163 @@
+use serde_json::Value;
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::time::{Duration, Instant};
+
+use crate::models::CompanyEntry;
+use crate::parsers::AtsParser;
+use crate::stats::write_table;
+
+/// Outcome of one `zapply validate` request, in report order.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum SlugStatus {
+    Ok,
+    Timeout,
+    ClientError,
+    ServerError,
+    ParseError,
+    /// DNS, TLS or connection failures.
+    Unreachable,
+}
+
+impl SlugStatus {
+    pub fn label(&self) -> &'static str {
+        match self {
+            Self::Ok => "OK",
+            Self::Timeout => "TIMEOUT",
+            Self::ClientError => "4xx",
+            Self::ServerError => "5xx",
+            Self::ParseError => "PARSE_ERROR",
+            Self::Unreachable => "UNREACHABLE",
+        }
+    }
+}
+
+pub struct SlugCheck {
+    pub name: String,
+    pub slug: String,
+    pub status: SlugStatus,
+    pub http_status: Option<u16>,
+    pub elapsed: Duration,
+    /// Jobs the ATS parser found, when the body parsed.
+    pub jobs: Option<usize>,
+}
+
+/// Fetches a company's board once and checks the body parses as its ATS.
+pub async fn check_company(client: &reqwest::Client, company: &CompanyEntry) -> SlugCheck {
+    let url = match &company.custom_api_url {
+        Some(custom) if company.ats_type == crate::models::AtsType::Unknown => custom.as_str(),
+        _ => company.api_url.as_str(),
+    };
+    let started = Instant::now();
+    let mut check = SlugCheck {
+        name: company.name.clone(),
+        slug: company.slug.clone(),
+        status: SlugStatus::Ok,
+        http_status: None,
+        elapsed: Duration::ZERO,
+        jobs: None,
+    };
+
+    let body = match crate::company_request(client, company, url, None).send().await {
+        Ok(resp) => {
+            let status = resp.status();
+            check.http_status = Some(status.as_u16());
+            if status.is_client_error() {
+                check.status = SlugStatus::ClientError;
+            } else if !status.is_success() {
+                check.status = SlugStatus::ServerError;
+            }
+            resp.text().await
+        }
+        Err(e) => Err(e),
+    };
+    check.elapsed = started.elapsed();
+
+    match body {
+        _ if check.status != SlugStatus::Ok => {}
+        Err(e) if e.is_timeout() => check.status = SlugStatus::Timeout,
+        Err(_) => check.status = SlugStatus::Unreachable,
+        Ok(text) => {
+            let jobs = serde_json::from_str::<Value>(&text).ok()
+                .and_then(|data| company.ats_type.parse(company, &data).ok());
+            match jobs {
+                Some(jobs) => check.jobs = Some(jobs.len()),
+                None => check.status = SlugStatus::ParseError,
+            }
+        }
+    }
+    check
+}
+
+/// Counts per status, then every company sorted by status (failures last) and name.
+pub fn render_validation_report(checks: &mut [SlugCheck]) -> String {
+    checks.sort_by(|a, b| a.status.cmp(&b.status).then_with(|| a.name.cmp(&b.name)));
+
+    let mut out = String::new();
+    let mut counts: BTreeMap<SlugStatus, usize> = BTreeMap::new();
+    for check in checks.iter() {
+        *counts.entry(check.status).or_insert(0) += 1;
+    }
+    let summary: Vec<String> = counts.iter().map(|(status, n)| format!("{} {}", status.label(), n)).collect();
+    writeln!(out, "Companies checked: {} ({})", checks.len(), summary.join(", ")).ok();
+
+    let rows = checks.iter().map(|c| vec![
+        format!("{} ({})", c.name, c.slug),
+        c.status.label().to_string(),
+        c.http_status.map(|s| s.to_string()).unwrap_or_else(|| "-".to_string()),
+        c.elapsed.as_millis().to_string(),
+        c.jobs.map(|n| n.to_string()).unwrap_or_else(|| "-".to_string()),
+    ]).collect();
+    write_table(&mut out, "Companies", &["Company", "Status", "HTTP", "Time (ms)", "Jobs"], rows);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::AtsType;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn company(slug: &str, ats_type: AtsType, api_url: String) -> CompanyEntry {
+        CompanyEntry {
+            name: slug.to_string(),
+            ats_type,
+            slug: slug.to_string(),
+            api_url,
+            ..Default::default()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_check_company_statuses() {
+        let server = MockServer::start().await;
+        let routes = [
+            ("/ok", ResponseTemplate::new(200).set_body_string("[]")),
+            ("/gone", ResponseTemplate::new(404)),
+            ("/down", ResponseTemplate::new(503)),
+            ("/html", ResponseTemplate::new(200).set_body_string("<html>Moved</html>")),
+            ("/slow", ResponseTemplate::new(200).set_delay(Duration::from_secs(2))),
+        ];
+        for (route, response) in routes {
+            Mock::given(method("GET")).and(path(route)).respond_with(response).mount(&server).await;
+        }
+        let client = reqwest::Client::builder().timeout(Duration::from_millis(500)).build().unwrap();
+
+        let mut checks = Vec::new();
+        for route in ["/ok", "/gone", "/down", "/html", "/slow"] {
+            let company = company(route.trim_start_matches('/'), AtsType::Lever, format!("{}{}", server.uri(), route));
+            checks.push(check_company(&client, &company).await);
+        }
+        let statuses: Vec<_> = checks.iter().map(|c| c.status).collect();
+        assert_eq!(statuses, vec![SlugStatus::Ok, SlugStatus::ClientError, SlugStatus::ServerError, SlugStatus::ParseError, SlugStatus::Timeout]);
+        assert_eq!(checks[0].jobs, Some(0));
+        assert_eq!(checks[1].http_status, Some(404));
+
+        let report = render_validation_report(&mut checks);
+        assert!(report.starts_with("Companies checked: 5 (OK 1, TIMEOUT 1, 4xx 1, 5xx 1, PARSE_ERROR 1)"), "{}", report);
+        // Sorted by status, so the timeout comes right after the working board
+        let lines: Vec<&str> = report.lines().skip(5).collect();
+        assert!(lines[0].starts_with("ok (ok)") && lines[1].starts_with("slow (slow)"), "{}", report);
+    }
+}
@@ -0,0 +1,152 @@
+use chrono::Utc;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+use crate::models::Job;
+
+/// Where to POST newly inserted jobs (`WEBHOOK_URL`).
+#[derive(Debug, Clone)]
+pub struct WebhookConfig {
+    pub url: String,
+    /// Jobs per POST; bigger batches are split over several payloads.
+    pub max_jobs_per_payload: usize,
+    pub include_description: bool,
+}
+
+#[derive(Serialize)]
+struct WebhookJob<'a> {
+    title: &'a str,
+    company: &'a str,
+    url: &'a str,
+    location: &'a str,
+    tags: &'a [String],
+    #[serde(skip_serializing_if = "Option::is_none")]
+    description: Option<&'a str>,
+}
+
+#[derive(Serialize)]
+struct WebhookPayload<'a> {
+    inserted_at: String,
+    count: usize,
+    jobs: Vec<WebhookJob<'a>>,
+}
+
+fn payload<'a>(config: &WebhookConfig, jobs: &'a [Job]) -> WebhookPayload<'a> {
+    WebhookPayload {
+        inserted_at: Utc::now().to_rfc3339(),
+        count: jobs.len(),
+        jobs: jobs.iter().map(|j| WebhookJob {
+            title: &j.title,
+            company: &j.company,
+            url: &j.url,
+            location: &j.location,
+            tags: &j.tags,
+            description: config.include_description.then_some(j.description.as_str()),
+        }).collect(),
+    }
+}
+
+/// Notifies the webhook about an inserted batch, `max_jobs_per_payload` jobs
+/// per POST. Failures are logged, never returned: a dead webhook shouldn't stop
+/// the scrape.
+pub async fn fire_webhook(client: &reqwest::Client, config: &WebhookConfig, jobs: &[Job]) {
+    for chunk in jobs.chunks(config.max_jobs_per_payload.max(1)) {
+        let result = client.post(&config.url)
+            .json(&payload(config, chunk))
+            .send()
+            .await
+            .and_then(|resp| resp.error_for_status());
+        match result {
+            Ok(_) => debug!("Webhook notified of {} jobs", chunk.len()),
+            Err(e) => warn!("Webhook {} failed: {}", config.url, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn job(n: usize) -> Job {
+        Job {
+            title: format!("Intern {}", n),
+            description: "Build things".to_string(),
+            url: format!("https://jobs.lever.co/acme/{}", n),
+            location: "Berlin, Germany".to_string(),
+            tags: vec!["Remote".to_string()],
+            ..Job::fixture(&format!("lever-{}", n))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_fire_webhook_payload() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/hook"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = WebhookConfig { url: format!("{}/hook", server.uri()), max_jobs_per_payload: 2, include_description: false };
+        fire_webhook(&reqwest::Client::new(), &config, &[job(1), job(2)]).await;
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["count"], 2);
+        assert!(body["inserted_at"].as_str().is_some());
+        assert_eq!(body["jobs"].as_array().unwrap().len(), 2);
+        assert_eq!(body["jobs"][0], serde_json::json!({
+            "title": "Intern 1",
+            "company": "Acme",
+            "url": "https://jobs.lever.co/acme/1",
+            "location": "Berlin, Germany",
+            "tags": ["Remote"],
+        }));
+    }
+
+    #[tokio::test]
+    async fn test_fire_webhook_splits_large_batches() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(200))
+            .expect(3)
+            .mount(&server)
+            .await;
+
+        let config = WebhookConfig { url: server.uri(), max_jobs_per_payload: 2, include_description: false };
+        let jobs: Vec<Job> = (1..=5).map(job).collect();
+        fire_webhook(&reqwest::Client::new(), &config, &jobs).await;
+
+        let requests = server.received_requests().await.unwrap();
+        let titles: Vec<Vec<String>> = requests.iter().map(|r| {
+            let body: serde_json::Value = serde_json::from_slice(&r.body).unwrap();
+            assert_eq!(body["count"], body["jobs"].as_array().unwrap().len());
+            body["jobs"].as_array().unwrap().iter().map(|j| j["title"].as_str().unwrap().to_string()).collect()
+        }).collect();
+        assert_eq!(titles, vec![
+            vec!["Intern 1", "Intern 2"],
+            vec!["Intern 3", "Intern 4"],
+            vec!["Intern 5"],
+        ]);
+    }
+
+    #[tokio::test]
+    async fn test_fire_webhook_failure_is_not_fatal() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .respond_with(ResponseTemplate::new(500))
+            .expect(1)
+            .mount(&server)
+            .await;
+
+        let config = WebhookConfig { url: server.uri(), max_jobs_per_payload: 50, include_description: true };
+        fire_webhook(&reqwest::Client::new(), &config, &[job(1)]).await;
+
+        let requests = server.received_requests().await.unwrap();
+        let body: serde_json::Value = serde_json::from_slice(&requests[0].body).unwrap();
+        assert_eq!(body["jobs"][0]["description"], "Build things");
+    }
+}
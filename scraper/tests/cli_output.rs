@@ -0,0 +1,73 @@
+//! Runs the `zapply` binary against a mocked Lever board and checks what
+//! `--output-format` prints to stdout.
+
+use std::path::PathBuf;
+use std::process::Command;
+
+use serde_json::{Value, json};
+use wiremock::matchers::{method, path};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// Serves the Lever fixture and writes a one-company slugs file pointing at it.
+async fn lever_board(name: &str) -> (MockServer, PathBuf) {
+    let server = MockServer::start().await;
+    let fixture = std::fs::read_to_string(concat!(env!("CARGO_MANIFEST_DIR"), "/tests/fixtures/lever_sample.json")).unwrap();
+    Mock::given(method("GET"))
+        .and(path("/v0/postings/acme"))
+        .respond_with(ResponseTemplate::new(200).set_body_raw(fixture, "application/json"))
+        .mount(&server)
+        .await;
+
+    let dir = std::env::temp_dir().join(format!("zapply_cli_{}_{}", name, std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let slugs = json!([{
+        "name": "Acme",
+        "type": "lever",
+        "slug": "acme",
+        "api_url": format!("{}/v0/postings/acme", server.uri()),
+    }]);
+    std::fs::write(dir.join("slugs.json"), slugs.to_string()).unwrap();
+    (server, dir)
+}
+
+/// Runs the scraper in `dir` off the async runtime, so the mock server keeps serving.
+async fn run_zapply(dir: PathBuf, format: &str) -> String {
+    let format = format.to_string();
+    let output = tokio::task::spawn_blocking(move || {
+        Command::new(env!("CARGO_BIN_EXE_zapply"))
+            .args([format!("--output-format={}", format).as_str(), "--since=2000-01-01"])
+            .current_dir(&dir)
+            .env("SLUGS_FILE", "slugs.json")
+            .env("RUST_LOG", "warn")
+            .output()
+            .unwrap()
+    }).await.unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    String::from_utf8(output.stdout).unwrap()
+}
+
+#[tokio::test]
+async fn json_output_is_a_single_array() {
+    let (_server, dir) = lever_board("json").await;
+    let stdout = run_zapply(dir.clone(), "json").await;
+
+    let jobs: Vec<Value> = serde_json::from_str(&stdout).unwrap();
+    assert_eq!(jobs.len(), 2);
+    assert!(jobs.iter().any(|j| j["title"] == "Backend Engineering Intern" && j["company"] == "Acme"));
+    // Pretty-printed, not one long line
+    assert!(stdout.lines().count() > jobs.len());
+
+    std::fs::remove_dir_all(dir).ok();
+}
+
+#[tokio::test]
+async fn ndjson_output_is_one_job_per_line() {
+    let (_server, dir) = lever_board("ndjson").await;
+    let stdout = run_zapply(dir.clone(), "ndjson").await;
+
+    let jobs: Vec<Value> = stdout.lines().map(|l| serde_json::from_str(l).unwrap()).collect();
+    assert_eq!(jobs.len(), 2);
+    assert!(jobs.iter().all(|j| j["id"].as_str().unwrap().starts_with("lever-")));
+
+    std::fs::remove_dir_all(dir).ok();
+}
@@ -0,0 +1,86 @@
+//! Golden tests: each `fixtures/<ats>_sample.json` is a real-shaped API response,
+//! and its parsed jobs are compared with the `fixtures/<ats>_expected.snap` snapshot.
+//!
+//! After an intentional parser change, regenerate the snapshots with
+//! `REGENERATE_SNAPSHOTS=1 cargo test --test parser_integration` and review the diff.
+
+use std::sync::Once;
+
+use serde_json::Value;
+use zapply::models::{AtsType, CompanyEntry};
+use zapply::parsers::{AtsParser, Severity};
+
+static REGENERATE: Once = Once::new();
+
+fn company(ats_type: AtsType) -> CompanyEntry {
+    CompanyEntry {
+        name: "Acme".to_string(),
+        ats_type,
+        slug: "acme".to_string(),
+        api_url: "https://example.com".to_string(),
+        domain: Some("acme.com".to_string()),
+        ..Default::default()
+    }
+}
+
+fn assert_golden(name: &str, ats_type: AtsType) {
+    REGENERATE.call_once(|| {
+        if std::env::var("REGENERATE_SNAPSHOTS").is_ok_and(|v| v == "1") {
+            // SAFETY: every test passes through this `Once` before insta reads
+            // the environment, so nothing reads it concurrently.
+            unsafe { std::env::set_var("INSTA_UPDATE", "always") };
+        }
+    });
+
+    let path = format!("{}/tests/fixtures/{}_sample.json", env!("CARGO_MANIFEST_DIR"), name);
+    let content = std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path, e));
+    let data: Value = serde_json::from_str(&content).unwrap();
+    let critical: Vec<_> = ats_type.validate_schema(&data).into_iter().filter(|w| w.severity == Severity::Critical).collect();
+    assert!(critical.is_empty(), "{} fixture fails schema validation: {:?}", name, critical);
+    let jobs = ats_type.parse(&company(ats_type), &data).unwrap();
+    assert!(!jobs.is_empty(), "{} fixture parsed to no jobs", name);
+
+    insta::with_settings!({ snapshot_path => "fixtures", prepend_module_to_snapshot => false, omit_expression => true }, {
+        insta::assert_json_snapshot!(format!("{}_expected", name), jobs);
+    });
+}
+
+#[test]
+fn greenhouse_golden() {
+    assert_golden("greenhouse", AtsType::Greenhouse);
+}
+
+#[test]
+fn lever_golden() {
+    assert_golden("lever", AtsType::Lever);
+}
+
+#[test]
+fn smartrecruiters_golden() {
+    assert_golden("smartrecruiters", AtsType::SmartRecruiters);
+}
+
+#[test]
+fn ashby_golden() {
+    assert_golden("ashby", AtsType::Ashby);
+}
+
+#[test]
+fn ashby_v2_golden() {
+    assert_golden("ashby_v2", AtsType::Ashby);
+}
+
+#[test]
+fn workable_golden() {
+    assert_golden("workable", AtsType::Workable);
+}
+
+#[test]
+fn recruitee_golden() {
+    assert_golden("recruitee", AtsType::Recruitee);
+}
+
+#[test]
+fn breezy_golden() {
+    assert_golden("breezy", AtsType::Breezy);
+}
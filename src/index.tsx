@@ -20,6 +20,8 @@ export type SearchParams = {
   source?: string
   degree?: string
   field?: string
+  industry?: string
+  stage?: string
   posted?: string
   page?: number
   isDetected?: boolean
@@ -240,6 +242,8 @@ export const getSearchParams = (c: Context<Env>): SearchParams => {
   const source = c.req.query('source')
   const degree = c.req.query('degree')
   const field = c.req.query('field')
+  const industry = c.req.query('industry')
+  const stage = c.req.query('stage')
   const posted = c.req.query('posted')
   const queryLocation = c.req.query('location')
   const job = c.req.query('job')
@@ -254,6 +258,8 @@ export const getSearchParams = (c: Context<Env>): SearchParams => {
     (source && source.trim()) ||
     (degree && degree.trim()) ||
     (field && field.trim()) ||
+    (industry && industry.trim()) ||
+    (stage && stage.trim()) ||
     (posted && posted.trim()) ||
     (queryLocation !== undefined)
   )
@@ -273,6 +279,8 @@ export const getSearchParams = (c: Context<Env>): SearchParams => {
     source,
     degree,
     field,
+    industry,
+    stage,
     posted,
     page: parseInt(c.req.query('page') || '1'),
     isDetected: queryLocation === undefined && shouldAutoDetect && !!detectedLocation
@@ -376,12 +384,33 @@ export const getJobs = async (
     }
   }
 
+  if (params.industry) {
+    const industries = params.industry.split(',').map(i => i.trim()).filter(Boolean)
+    if (industries.length > 0) {
+      const conditions = industries.map(() => 'company_industry LIKE ? ESCAPE "\\"').join(' OR ')
+      whereClause += ` AND (${conditions})`
+      industries.forEach(industry => {
+        sqlParams.push(`%${escapeLike(industry)}%`)
+      })
+    }
+  }
+
+  if (params.stage) {
+    // Stages are normalized by the scraper, so match exactly
+    const stages = params.stage.split(',').map(s => s.trim()).filter(Boolean)
+    if (stages.length > 0) {
+      const conditions = stages.map(() => 'company_stage = ? COLLATE NOCASE').join(' OR ')
+      whereClause += ` AND (${conditions})`
+      sqlParams.push(...stages)
+    }
+  }
+
   // Consolidated Counts and Data query with JSON aggregation
   const countSql = `SELECT COUNT(*) as total FROM jobs ${whereClause}`
   const companyCountSql = `SELECT COUNT(DISTINCT company) as total FROM jobs ${whereClause}`
   const dataSql = `
     SELECT 
-      j.id, j.title, j.company, j.slug, j.ats, j.url, j.company_url, j.location, j.posted, j.created_at,
+      j.id, j.title, j.company, j.slug, j.ats, j.url, j.company_url, j.company_industry, j.company_stage, j.location, j.posted, j.created_at,
       (SELECT json_group_array(name) FROM job_tags WHERE job_id = j.id) as tags,
       (SELECT json_group_array(name) FROM job_departments WHERE job_id = j.id) as departments,
       (SELECT json_group_array(name) FROM job_degree_levels WHERE job_id = j.id) as degree_levels,
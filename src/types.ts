@@ -1,19 +1,35 @@
 export interface Job {
     id: number
+    ats_job_id?: string
     title: string
     description?: string
+    description_length?: number
+    description_quality_score?: number
+    text_description?: string
+    description_hash?: string
     company: string
     location: string
     city?: string
     region?: string
     country?: string
     country_code?: string
+    metro_area?: string
+    preferred_timezone?: string
     url: string
-    posted: string
+    apply_url?: string
+    posted: string | null
+    last_seen?: string
+    seniority_level?: string
+    employment_type?: string
     ats: string
     company_url?: string
+    company_industry?: string
+    company_stage?: string
+    logo_url?: string
     tags?: string[]
     departments?: string[]
+    normalized_department?: string
     degree_levels?: string[]
     subject_areas?: string[]
+    benefits?: string[]
 }